@@ -228,12 +228,108 @@ impl BorrowedFd {
         Ok(())
     }
 
+    /// Add the given seals to the file referred to by this file descriptor.
+    ///
+    /// See [`fcntl_add_seals()`](./fn.fcntl_add_seals.html).
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd")))
+    )]
+    #[cfg(any(linuxlike, target_os = "freebsd"))]
+    #[inline]
+    pub fn add_seals(&self, seals: crate::SealFlags) -> Result<()> {
+        crate::fcntl_add_seals(self.0, seals)
+    }
+
+    /// Get the seals that have been applied to the file referred to by this file descriptor.
+    ///
+    /// See [`fcntl_get_seals()`](./fn.fcntl_get_seals.html).
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd")))
+    )]
+    #[cfg(any(linuxlike, target_os = "freebsd"))]
+    #[inline]
+    pub fn get_seals(&self) -> Result<crate::SealFlags> {
+        crate::fcntl_get_seals(self.0)
+    }
+
+    /// Get the value of an extended attribute of the file referred to by this file descriptor.
+    ///
+    /// See [`fgetxattr()`](./fn.fgetxattr.html).
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    #[inline]
+    pub fn getxattr<N: AsPath>(&self, name: N, buf: &mut [u8]) -> Result<usize> {
+        crate::fgetxattr(self.0, name, buf)
+    }
+
+    /// Set the value of an extended attribute of the file referred to by this file descriptor.
+    ///
+    /// See [`fsetxattr()`](./fn.fsetxattr.html).
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    #[inline]
+    pub fn setxattr<N: AsPath>(
+        &self,
+        name: N,
+        value: &[u8],
+        flags: crate::XattrFlags,
+    ) -> Result<()> {
+        crate::fsetxattr(self.0, name, value, flags)
+    }
+
+    /// List the extended attributes of the file referred to by this file descriptor.
+    ///
+    /// See [`flistxattr()`](./fn.flistxattr.html).
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    #[inline]
+    pub fn listxattr(&self, buf: &mut [u8]) -> Result<usize> {
+        crate::flistxattr(self.0, buf)
+    }
+
+    /// Remove an extended attribute from the file referred to by this file descriptor.
+    ///
+    /// See [`fremovexattr()`](./fn.fremovexattr.html).
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    #[inline]
+    pub fn removexattr<N: AsPath>(&self, name: N) -> Result<()> {
+        crate::fremovexattr(self.0, name)
+    }
+
     /// Check whether this file descriptor refers to a terminal.
     #[inline]
     pub fn isatty(&self) -> Result<bool> {
         crate::isatty(self.0)
     }
 
+    /// Get the attributes of the terminal referred to by this file descriptor.
+    ///
+    /// See [`tcgetattr()`](./fn.tcgetattr.html).
+    #[inline]
+    pub fn tcgetattr(&self) -> Result<crate::Termios> {
+        crate::tcgetattr(self.0)
+    }
+
+    /// Set the attributes of the terminal referred to by this file descriptor.
+    ///
+    /// See [`tcsetattr()`](./fn.tcsetattr.html).
+    #[inline]
+    pub fn tcsetattr(&self, when: crate::SetAttrWhen, t: &crate::Termios) -> Result<()> {
+        crate::tcsetattr(self.0, when, t)
+    }
+
+    /// Put the terminal referred to by this file descriptor into "raw" mode.
+    ///
+    /// The original attributes are restored when the returned guard is dropped. See
+    /// [`RawModeGuard`](./struct.RawModeGuard.html).
+    #[inline]
+    pub fn raw_mode(&self) -> Result<crate::RawModeGuard<'_>> {
+        crate::RawModeGuard::new(self)
+    }
+
     /// Seek to the specified position within the file referred to by this file descriptor.
     ///
     /// This only works on files that support seeking (e.g. regular files, certain shared memory
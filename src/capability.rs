@@ -0,0 +1,612 @@
+use crate::internal_prelude::*;
+
+use core::fmt;
+use core::str::FromStr;
+
+macro_rules! define_capability {
+    ($($(#[doc = $doc:literal])* $name:ident = $val:literal,)+) => {
+        /// A Linux capability.
+        ///
+        /// See `capabilities(7)` for a description of each capability.
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[allow(non_camel_case_types)]
+        #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        #[repr(u32)]
+        pub enum Capability {
+            $(
+                $(#[doc = $doc])*
+                $name = $val,
+            )+
+        }
+
+        impl Capability {
+            const ALL: &'static [Self] = &[$(Self::$name,)+];
+
+            /// Construct a `Capability` from the given raw capability number (if it is known).
+            #[inline]
+            pub fn from_u32(cap: u32) -> Option<Self> {
+                match cap {
+                    $($val => Some(Self::$name),)+
+                    _ => None,
+                }
+            }
+
+            #[inline]
+            fn name(self) -> &'static str {
+                match self {
+                    $(Self::$name => stringify!($name),)+
+                }
+            }
+        }
+    };
+}
+
+define_capability! {
+    CAP_CHOWN = 0,
+    CAP_DAC_OVERRIDE = 1,
+    CAP_DAC_READ_SEARCH = 2,
+    CAP_FOWNER = 3,
+    CAP_FSETID = 4,
+    CAP_KILL = 5,
+    CAP_SETGID = 6,
+    CAP_SETUID = 7,
+    CAP_SETPCAP = 8,
+    CAP_LINUX_IMMUTABLE = 9,
+    CAP_NET_BIND_SERVICE = 10,
+    CAP_NET_BROADCAST = 11,
+    CAP_NET_ADMIN = 12,
+    CAP_NET_RAW = 13,
+    CAP_IPC_LOCK = 14,
+    CAP_IPC_OWNER = 15,
+    CAP_SYS_MODULE = 16,
+    CAP_SYS_RAWIO = 17,
+    CAP_SYS_CHROOT = 18,
+    CAP_SYS_PTRACE = 19,
+    CAP_SYS_PACCT = 20,
+    CAP_SYS_ADMIN = 21,
+    CAP_SYS_BOOT = 22,
+    CAP_SYS_NICE = 23,
+    CAP_SYS_RESOURCE = 24,
+    CAP_SYS_TIME = 25,
+    CAP_SYS_TTY_CONFIG = 26,
+    CAP_MKNOD = 27,
+    CAP_LEASE = 28,
+    CAP_AUDIT_WRITE = 29,
+    CAP_AUDIT_CONTROL = 30,
+    CAP_SETFCAP = 31,
+    CAP_MAC_OVERRIDE = 32,
+    CAP_MAC_ADMIN = 33,
+    CAP_SYSLOG = 34,
+    CAP_WAKE_ALARM = 35,
+    CAP_BLOCK_SUSPEND = 36,
+    /// (Linux 3.16+)
+    CAP_AUDIT_READ = 37,
+    /// (Linux 5.8+)
+    CAP_PERFMON = 38,
+    /// (Linux 5.8+)
+    CAP_BPF = 39,
+    /// (Linux 5.9+)
+    CAP_CHECKPOINT_RESTORE = 40,
+}
+
+impl Capability {
+    /// Get the raw capability number.
+    #[inline]
+    pub fn as_u32(self) -> u32 {
+        self as u32
+    }
+
+    /// Create an iterator over all of the capabilities known to this library.
+    ///
+    /// Note that the running kernel may not support all of these capabilities; see
+    /// [`Self::is_supported()`].
+    #[inline]
+    pub fn iter() -> CapabilityIter {
+        CapabilityIter(Self::ALL.iter())
+    }
+
+    /// Check whether the running kernel supports this capability.
+    ///
+    /// This is determined by checking whether `prctl(PR_CAPBSET_READ)` fails with `EINVAL`.
+    #[inline]
+    pub fn is_supported(self) -> bool {
+        crate::prctl_capbset_read(self).is_ok()
+    }
+}
+
+impl fmt::Display for Capability {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Capability {
+    type Err = CapabilityParseError;
+
+    /// Parse a capability name.
+    ///
+    /// The name is matched case-insensitively, and the `CAP_` prefix is optional. For example,
+    /// `CAP_CHOWN`, `cap_chown`, and `chown` all parse as [`Capability::CAP_CHOWN`].
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        // Compare bytes, since s[..4] may not be on a char boundary
+        let s = if s.len() >= 4 && s.as_bytes()[..4].eq_ignore_ascii_case(b"CAP_") {
+            &s[4..]
+        } else {
+            s
+        };
+
+        Self::iter()
+            .find(|cap| cap.name()[4..].eq_ignore_ascii_case(s))
+            .ok_or(CapabilityParseError(()))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[derive(Clone)]
+pub struct CapabilityParseError(());
+
+impl fmt::Display for CapabilityParseError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Unknown capability")
+    }
+}
+
+impl fmt::Debug for CapabilityParseError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CapabilityParseError")
+            .field("message", &"Unknown capability")
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapabilityParseError {}
+
+/// An iterator over all of the capabilities known to this library.
+///
+/// This can be created by [`Capability::iter()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[derive(Clone, Debug)]
+pub struct CapabilityIter(core::slice::Iter<'static, Capability>);
+
+impl Iterator for CapabilityIter {
+    type Item = Capability;
+
+    #[inline]
+    fn next(&mut self) -> Option<Capability> {
+        self.0.next().copied()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for CapabilityIter {
+    #[inline]
+    fn next_back(&mut self) -> Option<Capability> {
+        self.0.next_back().copied()
+    }
+}
+
+impl ExactSizeIterator for CapabilityIter {
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl core::iter::FusedIterator for CapabilityIter {}
+
+/// Represents a set of capabilities.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[derive(Copy, Clone, Default, Eq, Hash, PartialEq)]
+pub struct CapSet(u64);
+
+impl CapSet {
+    /// Create an empty capability set.
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Create a capability set containing all of the capabilities known to this library.
+    #[inline]
+    pub fn full() -> Self {
+        Capability::iter().collect()
+    }
+
+    /// Empty this capability set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0 = 0;
+    }
+
+    /// Fill this capability set with all of the capabilities known to this library.
+    #[inline]
+    pub fn fill(&mut self) {
+        *self = Self::full();
+    }
+
+    /// Check whether the given capability is present in this set.
+    #[inline]
+    pub fn contains(&self, cap: Capability) -> bool {
+        self.0 & (1 << cap as u32) != 0
+    }
+
+    /// Add the given capability to this set.
+    #[inline]
+    pub fn add(&mut self, cap: Capability) {
+        self.0 |= 1 << cap as u32;
+    }
+
+    /// Remove the given capability from this set.
+    #[inline]
+    pub fn remove(&mut self, cap: Capability) {
+        self.0 &= !(1 << cap as u32);
+    }
+
+    /// Check if this capability set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Return the number of capabilities in this set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Create a new capability set that is the union of the two provided sets (i.e. all
+    /// capabilities present in either set).
+    #[inline]
+    pub fn union(&self, other: &CapSet) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Create a new capability set that is the intersection of the two provided sets (i.e. all
+    /// capabilities present in both sets).
+    #[inline]
+    pub fn intersection(&self, other: &CapSet) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// Create an iterator over this capability set.
+    #[inline]
+    pub fn iter(&self) -> CapSetIter {
+        self.into_iter()
+    }
+
+    /// Get the raw bitmask representation of this set.
+    ///
+    /// Bit `N` is set if the capability with number `N` is present.
+    #[inline]
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    /// Create a capability set from the given raw bitmask.
+    ///
+    /// Bits that do not correspond to capabilities known to this library are preserved (so they
+    /// will be passed back to the kernel unchanged), but they will not be yielded when iterating
+    /// over the set.
+    #[inline]
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    /// Get the current thread's capability bounding set.
+    ///
+    /// Capabilities that are not supported by the running kernel are omitted.
+    pub fn get_bounding() -> Result<Self> {
+        let mut set = Self::empty();
+        for cap in Capability::iter() {
+            match crate::prctl_capbset_read(cap) {
+                Ok(true) => set.add(cap),
+                Ok(false) => (),
+                Err(e) if e.code() == libc::EINVAL => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(set)
+    }
+
+    /// Remove all of the capabilities in this set from the current thread's capability bounding
+    /// set.
+    ///
+    /// This requires the `CAP_SETPCAP` capability. Capabilities that are not supported by the
+    /// running kernel are ignored.
+    pub fn drop_bounding(&self) -> Result<()> {
+        for cap in self.iter() {
+            match crate::prctl_capbset_drop(cap) {
+                Ok(()) => (),
+                Err(e) if e.code() == libc::EINVAL && !cap.is_supported() => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the current thread's ambient capability set.
+    ///
+    /// Capabilities that are not supported by the running kernel are omitted.
+    pub fn get_ambient() -> Result<Self> {
+        let mut set = Self::empty();
+        for cap in Capability::iter() {
+            match crate::prctl_cap_ambient_is_set(cap) {
+                Ok(true) => set.add(cap),
+                Ok(false) => (),
+                Err(e) if e.code() == libc::EINVAL && !cap.is_supported() => (),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(set)
+    }
+
+    /// Set the current thread's ambient capability set to this set.
+    ///
+    /// The ambient set is cleared, and then each capability in this set is raised. (A
+    /// capability can only be raised if it is present in both the permitted and inheritable
+    /// sets; see [`CapState`].)
+    pub fn set_ambient(&self) -> Result<()> {
+        crate::prctl_cap_ambient_clear_all()?;
+        for cap in self.iter() {
+            crate::prctl_cap_ambient_raise(cap)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CapSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl core::iter::FromIterator<Capability> for CapSet {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Capability>>(it: I) -> Self {
+        let mut set = Self::empty();
+        set.extend(it);
+        set
+    }
+}
+
+impl core::iter::Extend<Capability> for CapSet {
+    #[inline]
+    fn extend<I: IntoIterator<Item = Capability>>(&mut self, it: I) {
+        for cap in it.into_iter() {
+            self.add(cap);
+        }
+    }
+}
+
+impl IntoIterator for CapSet {
+    type Item = Capability;
+    type IntoIter = CapSetIter;
+
+    #[inline]
+    fn into_iter(self) -> CapSetIter {
+        CapSetIter {
+            set: self,
+            it: Capability::iter(),
+        }
+    }
+}
+
+/// An iterator over all the capabilities in a [`CapSet`].
+///
+/// Can be created by [`CapSet::iter()`] or [`CapSet::into_iter()`]. Capabilities are yielded in
+/// ascending order.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[derive(Clone, Debug)]
+pub struct CapSetIter {
+    set: CapSet,
+    it: CapabilityIter,
+}
+
+impl Iterator for CapSetIter {
+    type Item = Capability;
+
+    #[inline]
+    fn next(&mut self) -> Option<Capability> {
+        let set = self.set;
+        self.it.find(|&cap| set.contains(cap))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.it.len()))
+    }
+}
+
+impl core::iter::FusedIterator for CapSetIter {}
+
+/// The effective, permitted, and inheritable capability sets of a thread.
+///
+/// See [`capget()`] and [`capset()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CapState {
+    pub effective: CapSet,
+    pub permitted: CapSet,
+    pub inheritable: CapSet,
+}
+
+impl CapState {
+    /// Get the capability sets of the current thread.
+    ///
+    /// This is equivalent to `capget(0)`.
+    #[inline]
+    pub fn get_current() -> Result<Self> {
+        capget(0)
+    }
+
+    /// Set the capability sets of the current thread.
+    ///
+    /// See [`capset()`].
+    #[inline]
+    pub fn set_current(&self) -> Result<()> {
+        capset(self)
+    }
+}
+
+/// Get the capability sets of the thread specified by `pid`.
+///
+/// If `pid` is 0, this operates on the current thread.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn capget(pid: libc::pid_t) -> Result<CapState> {
+    let mut header = sys::__user_cap_header_struct {
+        version: sys::_LINUX_CAPABILITY_VERSION_3,
+        pid,
+    };
+    let mut data = [sys::__user_cap_data_struct::default(); sys::_LINUX_CAPABILITY_U32S_3];
+
+    Error::unpack_nz(unsafe {
+        libc::syscall(libc::SYS_capget, &mut header, data.as_mut_ptr()) as i32
+    })?;
+
+    let combine = |f: fn(&sys::__user_cap_data_struct) -> u32| {
+        CapSet::from_bits(f(&data[0]) as u64 | ((f(&data[1]) as u64) << 32))
+    };
+
+    Ok(CapState {
+        effective: combine(|d| d.effective),
+        permitted: combine(|d| d.permitted),
+        inheritable: combine(|d| d.inheritable),
+    })
+}
+
+/// Set the capability sets of the current thread.
+///
+/// Unprivileged threads may only drop capabilities from the effective and permitted sets, and
+/// may only add capabilities to the inheritable set if they are present in the bounding set and
+/// the permitted set. See `capabilities(7)` for more information.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn capset(state: &CapState) -> Result<()> {
+    let mut header = sys::__user_cap_header_struct {
+        version: sys::_LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+
+    let split = |i: usize| sys::__user_cap_data_struct {
+        effective: (state.effective.bits() >> (i * 32)) as u32,
+        permitted: (state.permitted.bits() >> (i * 32)) as u32,
+        inheritable: (state.inheritable.bits() >> (i * 32)) as u32,
+    };
+    let data = [split(0), split(1)];
+
+    Error::unpack_nz(unsafe { libc::syscall(libc::SYS_capset, &mut header, data.as_ptr()) as i32 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capability_u32() {
+        for cap in Capability::iter() {
+            assert_eq!(Capability::from_u32(cap.as_u32()), Some(cap));
+        }
+        assert_eq!(Capability::from_u32(u32::MAX), None);
+        assert_eq!(Capability::iter().len(), 41);
+        assert_eq!(Capability::iter().next(), Some(Capability::CAP_CHOWN));
+        assert!(Capability::CAP_CHOWN.is_supported());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_capability_string() {
+        for cap in Capability::iter() {
+            let name = cap.to_string();
+            assert_eq!(name, format!("{:?}", cap));
+            assert_eq!(Capability::from_str(&name).unwrap(), cap);
+            assert_eq!(Capability::from_str(&name[4..]).unwrap(), cap);
+            assert_eq!(
+                Capability::from_str(&name.to_ascii_lowercase()).unwrap(),
+                cap
+            );
+        }
+
+        assert_eq!(
+            Capability::from_str("cap_sys_admin").unwrap(),
+            Capability::CAP_SYS_ADMIN
+        );
+        Capability::from_str("").unwrap_err();
+        Capability::from_str("CAP_").unwrap_err();
+        Capability::from_str("CAP_BOGUS").unwrap_err();
+        // Multi-byte character spanning the prefix boundary
+        Capability::from_str("CAP\u{20ac}").unwrap_err();
+    }
+
+    #[test]
+    fn test_capset() {
+        let mut set = CapSet::empty();
+        assert!(set.is_empty());
+        assert_eq!(set.len(), 0);
+        assert_eq!(set, CapSet::default());
+
+        set.add(Capability::CAP_CHOWN);
+        set.add(Capability::CAP_CHECKPOINT_RESTORE);
+        assert!(set.contains(Capability::CAP_CHOWN));
+        assert!(set.contains(Capability::CAP_CHECKPOINT_RESTORE));
+        assert!(!set.contains(Capability::CAP_KILL));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.bits(), 1 | (1 << 40));
+        assert!(set
+            .iter()
+            .eq([Capability::CAP_CHOWN, Capability::CAP_CHECKPOINT_RESTORE]
+                .iter()
+                .copied()));
+
+        set.remove(Capability::CAP_CHOWN);
+        assert!(set
+            .iter()
+            .eq(core::iter::once(Capability::CAP_CHECKPOINT_RESTORE)));
+
+        let other = [Capability::CAP_KILL, Capability::CAP_CHECKPOINT_RESTORE]
+            .iter()
+            .copied()
+            .collect::<CapSet>();
+        assert!(set.union(&other).iter().eq(other.iter()));
+        assert_eq!(set.intersection(&other), set);
+
+        set.fill();
+        assert_eq!(set, CapSet::full());
+        assert_eq!(set.len(), Capability::iter().len());
+        set.clear();
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_capget_capset() {
+        let state = CapState::get_current().unwrap();
+        assert_eq!(capget(crate::getpid()).unwrap(), state);
+        assert_eq!(capget(0).unwrap(), state);
+
+        // Setting the same state should always work
+        state.set_current().unwrap();
+
+        CapSet::get_bounding().unwrap();
+        let ambient = CapSet::get_ambient().unwrap();
+        assert!(ambient.iter().all(|cap| state.permitted.contains(cap)));
+
+        crate::util::run_in_child(|| {
+            // Drop all our capabilities
+            capset(&CapState::default()).is_ok()
+                && CapState::get_current().ok() == Some(CapState::default())
+                && CapSet::empty().set_ambient().is_ok()
+                && CapSet::get_ambient().ok() == Some(CapSet::empty())
+                && capset(&CapState {
+                    permitted: [Capability::CAP_CHOWN].iter().copied().collect(),
+                    ..Default::default()
+                })
+                .is_err()
+        });
+    }
+}
@@ -0,0 +1,840 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+use crate::internal_prelude::*;
+use crate::{
+    PosixSpawnAttr, PosixSpawnFileActions, PosixSpawnFlags, SigSet, WaitFlags, WaitStatus,
+};
+
+/// Specifies what to connect one of a child's standard I/O streams to.
+///
+/// See [`Command::stdin()`], [`Command::stdout()`], and [`Command::stderr()`].
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", not(target_os = "android")))))]
+#[derive(Debug)]
+pub enum Stdio {
+    /// Inherit the stream from the parent (this is the default).
+    Inherit,
+    /// Connect the stream to `/dev/null`.
+    Null,
+    /// Create a new pipe (with [`pipe_cloexec()`](crate::pipe_cloexec)) and connect the stream to
+    /// one end of it.
+    ///
+    /// The other end is available in the corresponding field of the [`Child`].
+    Piped,
+    /// Connect the stream to the given file descriptor.
+    ///
+    /// The file descriptor is duplicated into the child, so the same `Command` can be used to
+    /// spawn multiple children.
+    Fd(FileDesc),
+}
+
+impl Default for Stdio {
+    #[inline]
+    fn default() -> Self {
+        Self::Inherit
+    }
+}
+
+impl From<FileDesc> for Stdio {
+    #[inline]
+    fn from(fd: FileDesc) -> Self {
+        Self::Fd(fd)
+    }
+}
+
+/// A builder for spawning child processes, similar to `std::process::Command`.
+///
+/// Children are launched with [`posix_spawn_raw()`](crate::posix_spawn_raw) (or
+/// [`posix_spawnp_raw()`](crate::posix_spawnp_raw) if the program name does not contain a slash)
+/// whenever possible. If an option is set that `posix_spawn()` cannot handle on the current
+/// platform (e.g. [`Self::uid()`], or [`Self::current_dir()`] when
+/// `PosixSpawnFileActions::has_addchdir_np()` returns `false`), this falls back to
+/// `fork()`ing and `execve()`ing the program manually.
+///
+/// Unlike `std::process::Command`, this does not modify signal dispositions in the child
+/// (except as specified with [`Self::sigdefault()`]).
+///
+/// ```
+/// # use slibc::{Command, Stdio, WaitStatus};
+/// let mut child = Command::new("sh")
+///     .arg("-c")
+///     .arg("echo hello")
+///     .stdout(Stdio::Piped)
+///     .spawn()
+///     .unwrap();
+///
+/// let mut buf = [0; 6];
+/// child.stdout.as_ref().unwrap().read_exact(&mut buf).unwrap();
+/// assert_eq!(&buf, b"hello\n");
+/// assert_eq!(child.wait().unwrap(), WaitStatus::Exited(0));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", not(target_os = "android")))))]
+#[derive(Debug)]
+pub struct Command {
+    prog: OsString,
+    argv: Vec<OsString>,
+    env_clear: bool,
+    env: BTreeMap<OsString, Option<OsString>>,
+    cwd: Option<OsString>,
+    stdio: [Stdio; 3],
+    pgroup: Option<libc::pid_t>,
+    sigmask: Option<SigSet>,
+    sigdefault: Option<SigSet>,
+    uid: Option<libc::uid_t>,
+    gid: Option<libc::gid_t>,
+    groups: Option<Vec<libc::gid_t>>,
+    #[cfg(target_os = "linux")]
+    pidfd: bool,
+}
+
+impl Command {
+    /// Create a new `Command` to run the given program.
+    ///
+    /// If `prog` does not contain a slash, it is searched for in the directories listed in the
+    /// `PATH` environment variable of the current process. `argv[0]` is set to `prog`; use
+    /// [`Self::arg0()`] to change it.
+    pub fn new<P: AsPath>(prog: P) -> Self {
+        let prog = prog.as_os_str().to_os_string();
+
+        Self {
+            argv: vec![prog.clone()],
+            prog,
+            env_clear: false,
+            env: BTreeMap::new(),
+            cwd: None,
+            stdio: [Stdio::Inherit, Stdio::Inherit, Stdio::Inherit],
+            pgroup: None,
+            sigmask: None,
+            sigdefault: None,
+            uid: None,
+            gid: None,
+            groups: None,
+            #[cfg(target_os = "linux")]
+            pidfd: false,
+        }
+    }
+
+    /// Set `argv[0]` for the child.
+    #[inline]
+    pub fn arg0<S: AsPath>(&mut self, arg: S) -> &mut Self {
+        self.argv[0] = arg.as_os_str().to_os_string();
+        self
+    }
+
+    /// Add an argument to pass to the program.
+    #[inline]
+    pub fn arg<S: AsPath>(&mut self, arg: S) -> &mut Self {
+        self.argv.push(arg.as_os_str().to_os_string());
+        self
+    }
+
+    /// Add multiple arguments to pass to the program.
+    #[inline]
+    pub fn args<S: AsPath, I: IntoIterator<Item = S>>(&mut self, args: I) -> &mut Self {
+        self.argv
+            .extend(args.into_iter().map(|arg| arg.as_os_str().to_os_string()));
+        self
+    }
+
+    /// Set an environment variable in the child.
+    #[inline]
+    pub fn env<K: AsPath, V: AsPath>(&mut self, key: K, val: V) -> &mut Self {
+        self.env.insert(
+            key.as_os_str().to_os_string(),
+            Some(val.as_os_str().to_os_string()),
+        );
+        self
+    }
+
+    /// Remove an environment variable from the child's environment.
+    #[inline]
+    pub fn env_remove<K: AsPath>(&mut self, key: K) -> &mut Self {
+        self.env.insert(key.as_os_str().to_os_string(), None);
+        self
+    }
+
+    /// Clear the child's environment, including any variables set with [`Self::env()`].
+    ///
+    /// Otherwise, the child inherits the environment of the current process.
+    #[inline]
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.env_clear = true;
+        self.env.clear();
+        self
+    }
+
+    /// Set the working directory of the child.
+    ///
+    /// Note that if `dir` is a relative path, it is interpreted relative to the parent's working
+    /// directory; however, a relative program path may be interpreted relative to either
+    /// directory, depending on the platform.
+    #[inline]
+    pub fn current_dir<P: AsPath>(&mut self, dir: P) -> &mut Self {
+        self.cwd = Some(dir.as_os_str().to_os_string());
+        self
+    }
+
+    /// Configure the child's standard input.
+    #[inline]
+    pub fn stdin<S: Into<Stdio>>(&mut self, stdin: S) -> &mut Self {
+        self.stdio[0] = stdin.into();
+        self
+    }
+
+    /// Configure the child's standard output.
+    #[inline]
+    pub fn stdout<S: Into<Stdio>>(&mut self, stdout: S) -> &mut Self {
+        self.stdio[1] = stdout.into();
+        self
+    }
+
+    /// Configure the child's standard error.
+    #[inline]
+    pub fn stderr<S: Into<Stdio>>(&mut self, stderr: S) -> &mut Self {
+        self.stdio[2] = stderr.into();
+        self
+    }
+
+    /// Move the child into the process group with the given ID.
+    ///
+    /// If `pgroup` is 0, the child is placed in a new process group whose ID is equal to its PID.
+    /// See [`PosixSpawnAttr::setpgroup()`].
+    #[inline]
+    pub fn process_group(&mut self, pgroup: libc::pid_t) -> &mut Self {
+        self.pgroup = Some(pgroup);
+        self
+    }
+
+    /// Set the signal mask of the child.
+    ///
+    /// Otherwise, the child inherits the signal mask of the calling thread.
+    #[inline]
+    pub fn sigmask(&mut self, mask: &SigSet) -> &mut Self {
+        self.sigmask = Some(*mask);
+        self
+    }
+
+    /// Reset the dispositions of the signals in the given set to their defaults in the child.
+    #[inline]
+    pub fn sigdefault(&mut self, mask: &SigSet) -> &mut Self {
+        self.sigdefault = Some(*mask);
+        self
+    }
+
+    /// Set the user ID of the child (with `setuid()`).
+    ///
+    /// This forces the `fork()`/`execve()` fallback to be used.
+    #[inline]
+    pub fn uid(&mut self, uid: libc::uid_t) -> &mut Self {
+        self.uid = Some(uid);
+        self
+    }
+
+    /// Set the group ID of the child (with `setgid()`).
+    ///
+    /// This forces the `fork()`/`execve()` fallback to be used.
+    #[inline]
+    pub fn gid(&mut self, gid: libc::gid_t) -> &mut Self {
+        self.gid = Some(gid);
+        self
+    }
+
+    /// Set the supplementary group list of the child (with `setgroups()`).
+    ///
+    /// This forces the `fork()`/`execve()` fallback to be used.
+    #[inline]
+    pub fn groups(&mut self, groups: &[libc::gid_t]) -> &mut Self {
+        self.groups = Some(groups.to_vec());
+        self
+    }
+
+    /// Open a [`PidFd`](crate::PidFd) referring to the child when it is spawned.
+    ///
+    /// It will be available from [`Child::pidfd()`]. If the pidfd cannot be opened (for example,
+    /// with `ENOSYS` on Linux before 5.3), the child is killed and reaped, and
+    /// [`Self::spawn()`] fails with that error.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[cfg(target_os = "linux")]
+    #[inline]
+    pub fn pidfd(&mut self, pidfd: bool) -> &mut Self {
+        self.pidfd = pidfd;
+        self
+    }
+
+    fn build_envp(&self) -> Result<crate::CStringVec> {
+        let mut env = BTreeMap::new();
+        if !self.env_clear {
+            env.extend(std::env::vars_os().map(|(key, val)| (key, Some(val))));
+        }
+        for (key, val) in self.env.iter() {
+            env.insert(key.clone(), val.clone());
+        }
+
+        let mut envp = crate::CStringVec::with_capacity(env.len() + 1);
+        for (key, val) in env {
+            if let Some(val) = val {
+                let mut var = key.into_vec();
+                var.push(b'=');
+                var.extend_from_slice(val.as_bytes());
+                envp.push(CString::new(var).map_err(|_| Error::mid_nul())?);
+            }
+        }
+        Ok(envp)
+    }
+
+    /// Check whether `posix_spawn()` can handle all of the options on this `Command`.
+    fn can_posix_spawn(&self) -> bool {
+        if self.uid.is_some() || self.gid.is_some() || self.groups.is_some() {
+            return false;
+        }
+
+        if self.cwd.is_some() {
+            #[cfg(target_os = "linux")]
+            return PosixSpawnFileActions::has_addchdir_np();
+            #[cfg(not(target_os = "linux"))]
+            return false;
+        }
+
+        true
+    }
+
+    /// Spawn the program as a child process.
+    ///
+    /// If the program cannot be executed, this returns the error from `execve()` on most
+    /// platforms (and always when the `fork()`/`execve()` fallback is used).
+    pub fn spawn(&mut self) -> Result<Child> {
+        let cstr = |s: &OsStr| CString::new(s.as_bytes()).map_err(|_| Error::mid_nul());
+
+        let prog = cstr(&self.prog)?;
+        let mut argv = crate::CStringVec::with_capacity(self.argv.len() + 1);
+        for arg in self.argv.iter() {
+            argv.push(cstr(arg)?);
+        }
+        let envp = self.build_envp()?;
+        let cwd = self.cwd.as_deref().map(cstr).transpose()?;
+
+        // The file descriptors to dup2() onto stdin/stdout/stderr in the child, and the ends of any
+        // pipes that will be kept by the parent
+        let mut child_fds: [Option<FileDesc>; 3] = [None, None, None];
+        let mut parent_fds: [Option<FileDesc>; 3] = [None, None, None];
+        let mut stdio_fds = [None; 3];
+
+        for (i, stdio) in self.stdio.iter().enumerate() {
+            match stdio {
+                Stdio::Inherit => (),
+                Stdio::Null => {
+                    let fd = crate::open(
+                        CStr::from_bytes_with_nul(b"/dev/null\0").unwrap(),
+                        OFlag::O_RDWR | OFlag::O_CLOEXEC,
+                        0,
+                    )?;
+                    stdio_fds[i] = Some(fd.fd());
+                    child_fds[i] = Some(fd);
+                }
+                Stdio::Piped => {
+                    let (r, w) = crate::pipe_cloexec()?;
+                    let (child, parent) = if i == 0 { (r, w) } else { (w, r) };
+                    stdio_fds[i] = Some(child.fd());
+                    child_fds[i] = Some(child);
+                    parent_fds[i] = Some(parent);
+                }
+                Stdio::Fd(fd) => stdio_fds[i] = Some(fd.fd()),
+            }
+        }
+
+        let pid = if self.can_posix_spawn() {
+            self.spawn_posix(&prog, &argv, &envp, cwd.as_deref(), &stdio_fds)?
+        } else {
+            let progs = self.exec_candidates(&prog)?;
+            self.spawn_fork(&progs, &argv, &envp, cwd.as_deref(), &stdio_fds)?
+        };
+
+        #[cfg(target_os = "linux")]
+        let pidfd = if self.pidfd {
+            match crate::PidFd::open(pid, crate::PidFdOpenFlags::empty()) {
+                Ok(pidfd) => Some(pidfd),
+                Err(e) => {
+                    // The caller would never see the PID, so don't leave the child running (or
+                    // unreaped)
+                    let _ = crate::kill(pid, crate::Signal::SIGKILL);
+                    let _ = crate::waitpid(pid, WaitFlags::empty());
+                    return Err(e);
+                }
+            }
+        } else {
+            None
+        };
+
+        // Close the child's ends of the pipes (and /dev/null) in the parent
+        drop(child_fds);
+        let [stdin, stdout, stderr] = parent_fds;
+
+        Ok(Child {
+            pid,
+            status: None,
+            stdin,
+            stdout,
+            stderr,
+            #[cfg(target_os = "linux")]
+            pidfd,
+        })
+    }
+
+    fn spawn_posix(
+        &self,
+        prog: &CStr,
+        argv: &crate::CStringVec,
+        envp: &crate::CStringVec,
+        cwd: Option<&CStr>,
+        stdio_fds: &[Option<RawFd>; 3],
+    ) -> Result<libc::pid_t> {
+        let mut factions = PosixSpawnFileActions::new()?;
+        for (i, fd) in stdio_fds.iter().enumerate() {
+            if let Some(fd) = *fd {
+                factions.adddup2(fd, i as RawFd)?;
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(cwd) = cwd {
+            factions.addchdir_np(cwd)?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        debug_assert!(cwd.is_none());
+
+        let mut attr = PosixSpawnAttr::new()?;
+        let mut flags = PosixSpawnFlags::empty();
+        if let Some(pgroup) = self.pgroup {
+            attr.setpgroup(pgroup)?;
+            flags |= PosixSpawnFlags::SETPGROUP;
+        }
+        if let Some(mask) = self.sigmask.as_ref() {
+            attr.setsigmask(mask)?;
+            flags |= PosixSpawnFlags::SETSIGMASK;
+        }
+        if let Some(mask) = self.sigdefault.as_ref() {
+            attr.setsigdefault(mask)?;
+            flags |= PosixSpawnFlags::SETSIGDEF;
+        }
+        attr.setflags(flags)?;
+
+        // SAFETY: argv and envp are valid NULL-terminated arrays, and envp is not NULL
+        unsafe {
+            if prog.to_bytes().contains(&b'/') {
+                crate::posix_spawn_raw(
+                    prog,
+                    Some(&factions),
+                    Some(&attr),
+                    argv.as_ptr(),
+                    envp.as_ptr(),
+                )
+            } else {
+                crate::posix_spawnp_raw(
+                    prog,
+                    Some(&factions),
+                    Some(&attr),
+                    argv.as_ptr(),
+                    envp.as_ptr(),
+                )
+            }
+        }
+    }
+
+    /// Get the list of paths that the `fork()`/`execve()` fallback should try to execute.
+    fn exec_candidates(&self, prog: &CStr) -> Result<Vec<CString>> {
+        let prog = prog.to_bytes();
+        if prog.contains(&b'/') {
+            return Ok(vec![CString::new(prog).unwrap()]);
+        }
+
+        let path = std::env::var_os("PATH").unwrap_or_else(|| "/bin:/usr/bin".into());
+
+        let mut progs = Vec::new();
+        for dir in path.as_bytes().split(|&ch| ch == b':') {
+            let mut full = if dir.is_empty() {
+                b".".to_vec()
+            } else {
+                dir.to_vec()
+            };
+            full.push(b'/');
+            full.extend_from_slice(prog);
+            progs.push(CString::new(full).map_err(|_| Error::mid_nul())?);
+        }
+
+        Ok(progs)
+    }
+
+    fn spawn_fork(
+        &self,
+        progs: &[CString],
+        argv: &crate::CStringVec,
+        envp: &crate::CStringVec,
+        cwd: Option<&CStr>,
+        stdio_fds: &[Option<RawFd>; 3],
+    ) -> Result<libc::pid_t> {
+        let (err_r, err_w) = crate::pipe_cloexec()?;
+
+        match unsafe { crate::fork() }? {
+            None => {
+                // Only async-signal-safe operations are allowed here
+                let eno = self.exec_child(progs, argv, envp, cwd, stdio_fds).code();
+                let _ = err_w.write(&eno.to_ne_bytes());
+                unsafe { crate::_exit(127) }
+            }
+
+            Some(pid) => {
+                drop(err_w);
+
+                let mut buf = [0; 4];
+                let n = loop {
+                    match err_r.read(&mut buf) {
+                        Err(e) if e == Errno::EINTR => (),
+                        res => break res,
+                    }
+                };
+
+                if let Ok(4) = n {
+                    // The child failed; reap it and report the error
+                    let _ = crate::waitpid(pid, WaitFlags::empty());
+                    Err(Error::from_code(i32::from_ne_bytes(buf)))
+                } else {
+                    Ok(pid)
+                }
+            }
+        }
+    }
+
+    fn exec_child(
+        &self,
+        progs: &[CString],
+        argv: &crate::CStringVec,
+        envp: &crate::CStringVec,
+        cwd: Option<&CStr>,
+        stdio_fds: &[Option<RawFd>; 3],
+    ) -> Error {
+        macro_rules! tryerr {
+            ($e:expr) => {
+                if let Err(e) = $e {
+                    return e;
+                }
+            };
+        }
+
+        for (i, fd) in stdio_fds.iter().enumerate() {
+            if let Some(fd) = *fd {
+                let target = i as RawFd;
+                if fd == target {
+                    tryerr!(unsafe { BorrowedFd::new(fd) }.set_cloexec(false));
+                } else {
+                    tryerr!(Error::unpack(unsafe { libc::dup2(fd, target) }));
+                }
+            }
+        }
+
+        if let Some(cwd) = cwd {
+            tryerr!(crate::chdir(cwd));
+        }
+
+        if let Some(pgroup) = self.pgroup {
+            tryerr!(crate::setpgid(0, pgroup));
+        }
+
+        if let Some(mask) = self.sigdefault.as_ref() {
+            for sig in mask.iter() {
+                unsafe {
+                    libc::signal(sig.as_i32(), libc::SIG_DFL);
+                }
+            }
+        }
+
+        if let Some(mask) = self.sigmask.as_ref() {
+            tryerr!(crate::pthread_sigmask(
+                crate::SigmaskHow::SETMASK,
+                Some(mask)
+            ));
+        }
+
+        if let Some(groups) = self.groups.as_ref() {
+            tryerr!(crate::setgroups(groups));
+        }
+        if let Some(gid) = self.gid {
+            tryerr!(crate::setgid(gid));
+        }
+        if let Some(uid) = self.uid {
+            tryerr!(crate::setuid(uid));
+        }
+
+        // Like execvp(), report ENOENT only if no other error was encountered
+        let mut err = Error::from_code(libc::ENOENT);
+        for prog in progs {
+            unsafe {
+                libc::execve(prog.as_ptr(), argv.as_ptr(), envp.as_ptr());
+            }
+            let e = Error::last();
+            if e != Errno::ENOENT && e != Errno::ENOTDIR {
+                err = e;
+            }
+        }
+        err
+    }
+}
+
+/// A handle to a child process spawned with [`Command::spawn()`].
+///
+/// The child is NOT killed or waited for when this is dropped.
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", not(target_os = "android")))))]
+#[derive(Debug)]
+pub struct Child {
+    pid: libc::pid_t,
+    status: Option<WaitStatus>,
+    /// The parent's end of the child's stdin pipe, if [`Stdio::Piped`] was specified.
+    pub stdin: Option<FileDesc>,
+    /// The parent's end of the child's stdout pipe, if [`Stdio::Piped`] was specified.
+    pub stdout: Option<FileDesc>,
+    /// The parent's end of the child's stderr pipe, if [`Stdio::Piped`] was specified.
+    pub stderr: Option<FileDesc>,
+    #[cfg(target_os = "linux")]
+    pidfd: Option<crate::PidFd>,
+}
+
+impl Child {
+    /// Get the PID of the child.
+    #[inline]
+    pub fn id(&self) -> libc::pid_t {
+        self.pid
+    }
+
+    /// Get the [`PidFd`](crate::PidFd) referring to the child, if [`Command::pidfd()`] was used.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[cfg(target_os = "linux")]
+    #[inline]
+    pub fn pidfd(&self) -> Option<&crate::PidFd> {
+        self.pidfd.as_ref()
+    }
+
+    /// Send a signal to the child.
+    ///
+    /// If the child has already been waited for, this fails with `ESRCH` (instead of potentially
+    /// signaling an unrelated process that reused the PID).
+    #[inline]
+    pub fn kill(&self, sig: crate::Signal) -> Result<()> {
+        if self.status.is_some() {
+            return Err(Error::from_code(libc::ESRCH));
+        }
+        crate::kill(self.pid, sig)
+    }
+
+    /// Wait for the child to exit.
+    ///
+    /// [`Self::stdin`] is closed before waiting to avoid deadlocks. If the child has already been
+    /// waited for, its exit status is returned again.
+    pub fn wait(&mut self) -> Result<WaitStatus> {
+        drop(self.stdin.take());
+
+        if let Some(status) = self.status {
+            return Ok(status);
+        }
+
+        let (_, status) = crate::waitpid(self.pid, WaitFlags::empty())?.unwrap();
+        self.status = Some(status);
+        Ok(status)
+    }
+
+    /// Check if the child has exited, without blocking.
+    ///
+    /// Returns `Ok(None)` if the child is still running.
+    pub fn try_wait(&mut self) -> Result<Option<WaitStatus>> {
+        if let Some(status) = self.status {
+            return Ok(Some(status));
+        }
+
+        if let Some((_, status)) = crate::waitpid(self.pid, WaitFlags::WNOHANG)? {
+            self.status = Some(status);
+            Ok(Some(status))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{SigSet, Signal};
+
+    fn read_all(fd: &FileDesc) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let mut chunk = [0; 256];
+        loop {
+            let n = fd.read(&mut chunk).unwrap();
+            if n == 0 {
+                return buf;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    #[test]
+    fn test_command_basic() {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("cat; echo \"$0 $FOO${BAR-}\" >&2; exit 3")
+            .arg0("name")
+            .env("FOO", "foo")
+            .env("BAR", "bar")
+            .env_remove("BAR")
+            .stdin(Stdio::Piped)
+            .stdout(Stdio::Piped)
+            .stderr(Stdio::Piped)
+            .spawn()
+            .unwrap();
+
+        assert!(child.try_wait().unwrap().is_none());
+
+        child.stdin.as_ref().unwrap().write_all(b"abc").unwrap();
+        drop(child.stdin.take());
+        assert_eq!(read_all(child.stdout.as_ref().unwrap()), b"abc");
+        assert_eq!(read_all(child.stderr.as_ref().unwrap()), b"name foo\n");
+
+        assert_eq!(child.wait().unwrap(), WaitStatus::Exited(3));
+        assert_eq!(child.wait().unwrap(), WaitStatus::Exited(3));
+        assert_eq!(child.try_wait().unwrap(), Some(WaitStatus::Exited(3)));
+        assert_eq!(child.kill(Signal::SIGKILL).unwrap_err(), Errno::ESRCH);
+    }
+
+    #[test]
+    fn test_command_env_clear() {
+        let (r, w) = crate::pipe_cloexec().unwrap();
+        let mut child = Command::new("/bin/sh")
+            .arg("-c")
+            .arg("echo \"${HOME-unset} $FOO\"")
+            .env_clear()
+            .env("FOO", "foo")
+            .stdout(w)
+            .stdin(Stdio::Null)
+            .spawn()
+            .unwrap();
+        assert_eq!(read_all(&r), b"unset foo\n");
+        assert_eq!(child.wait().unwrap(), WaitStatus::Exited(0));
+    }
+
+    #[test]
+    fn test_command_errors() {
+        for &fork in [false, true].iter() {
+            let mut cmd = Command::new("/nonexistent");
+            if fork {
+                cmd.uid(crate::getuid());
+            }
+            assert!(cmd.can_posix_spawn() != fork);
+            assert_eq!(cmd.spawn().unwrap_err(), Errno::ENOENT);
+
+            let mut cmd = Command::new("nonexistent-slibc-test-program");
+            if fork {
+                cmd.uid(crate::getuid());
+            }
+            assert_eq!(cmd.spawn().unwrap_err(), Errno::ENOENT);
+        }
+
+        assert_eq!(
+            Command::new("sh").arg("a\0b").spawn().unwrap_err(),
+            Errno::EINVAL
+        );
+        assert_eq!(
+            Command::new("sh").env("A", "\0").spawn().unwrap_err(),
+            Errno::EINVAL
+        );
+    }
+
+    #[test]
+    fn test_command_attrs() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let tmpdir = tmpdir.path().canonicalize().unwrap();
+
+        for &fork in [false, true].iter() {
+            let mut cmd = Command::new("cat");
+            cmd.current_dir(&tmpdir)
+                .process_group(0)
+                .sigmask(&crate::sigset!(Signal::SIGUSR1))
+                .sigdefault(&SigSet::full())
+                .stdin(Stdio::Piped);
+            if fork {
+                cmd.uid(crate::getuid()).gid(crate::getgid());
+            }
+            assert!(cmd.can_posix_spawn() != fork);
+
+            // The child has already called execve() by the time spawn() returns, and it will
+            // block until we close its stdin
+            let mut child = cmd.spawn().unwrap();
+            let pid = child.id();
+
+            assert_eq!(crate::getpgid(pid).unwrap(), pid);
+
+            #[cfg(target_os = "linux")]
+            {
+                assert_eq!(
+                    crate::readlink_alloc(format!("/proc/{}/cwd", pid)).unwrap(),
+                    tmpdir.as_os_str()
+                );
+
+                let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).unwrap();
+                let get_mask = |name: &str| {
+                    let line = status.lines().find(|l| l.starts_with(name)).unwrap();
+                    u64::from_str_radix(line[name.len()..].trim(), 16).unwrap()
+                };
+                assert_eq!(get_mask("SigBlk:"), 1 << (Signal::SIGUSR1.as_i32() - 1));
+                // Ignore the real-time signals that libc reserves for internal use
+                assert_eq!(get_mask("SigIgn:") & 0x7fff_ffff, 0);
+            }
+
+            assert_eq!(child.wait().unwrap(), WaitStatus::Exited(0));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_command_pidfd() {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("exit 5")
+            .pidfd(true)
+            .spawn()
+            .unwrap();
+
+        let pidfd = child.pidfd().unwrap();
+        let mut fds = [crate::PollFd::new(pidfd.fd(), crate::PollEvents::IN)];
+        assert_eq!(crate::poll(&mut fds, -1).unwrap(), 1);
+        assert_eq!(child.wait().unwrap(), WaitStatus::Exited(5));
+
+        assert!(Command::new("true").spawn().unwrap().pidfd().is_none());
+    }
+
+    // glibc's posix_spawn() doesn't open any file descriptors, so only opening the pidfd will fail
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    #[test]
+    fn test_command_pidfd_error() {
+        crate::util::run_in_child(|| {
+            let null = crate::open(
+                CStr::from_bytes_with_nul(b"/dev/null\0").unwrap(),
+                OFlag::O_RDONLY | OFlag::O_CLOEXEC,
+                0,
+            )
+            .unwrap();
+
+            // Every file descriptor below the limit is now in use
+            let limit = null.fd() as crate::Limit + 1;
+            let (_, hard) = crate::getrlimit(crate::Resource::NOFILE).unwrap();
+            unsafe { crate::setrlimit(crate::Resource::NOFILE, (limit, hard)) }.unwrap();
+
+            let err = Command::new("sleep")
+                .arg("10")
+                .pidfd(true)
+                .spawn()
+                .unwrap_err();
+
+            // The child was killed and reaped
+            err == Errno::EMFILE
+                && crate::waitpid(-1, WaitFlags::WNOHANG).unwrap_err() == Errno::ECHILD
+        });
+    }
+}
@@ -0,0 +1,941 @@
+use core::convert::TryInto;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
+
+use crate::internal_prelude::*;
+
+/// The size of a `fanotify_event_metadata` struct, not including any information records.
+const METADATA_SIZE: usize = core::mem::size_of::<sys::fanotify_event_metadata>();
+
+/// The size of a `fanotify_event_info_header` struct.
+const INFO_HEADER_SIZE: usize = core::mem::size_of::<libc::fanotify_event_info_header>();
+
+bitflags::bitflags! {
+    /// Flags to [`fanotify_init()`] or [`Fanotify::new()`].
+    ///
+    /// See `fanotify_init(2)` for more information.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    pub struct FanotifyInitFlags: libc::c_uint {
+        /// Set the close-on-exec flag on the returned fanotify file descriptor.
+        const CLOEXEC = libc::FAN_CLOEXEC;
+        /// Set the `O_NONBLOCK` flag on the returned fanotify file descriptor.
+        const NONBLOCK = libc::FAN_NONBLOCK;
+
+        /// Receive notification events only (this is the default).
+        const CLASS_NOTIF = libc::FAN_CLASS_NOTIF;
+        /// Receive notification events and permission events, after the file content is final.
+        const CLASS_CONTENT = libc::FAN_CLASS_CONTENT;
+        /// Receive notification events and permission events, before the file content is
+        /// final.
+        const CLASS_PRE_CONTENT = libc::FAN_CLASS_PRE_CONTENT;
+
+        /// Remove the limit on the number of events in the event queue.
+        const UNLIMITED_QUEUE = libc::FAN_UNLIMITED_QUEUE;
+        /// Remove the limit on the number of marks.
+        const UNLIMITED_MARKS = libc::FAN_UNLIMITED_MARKS;
+        /// Enable generation of audit log records about permission events (Linux 4.15+).
+        const ENABLE_AUDIT = libc::FAN_ENABLE_AUDIT;
+
+        /// Report a PID file descriptor for the process that caused each event, in a
+        /// [`FanotifyInfo::Pidfd`] information record (Linux 5.15+).
+        const REPORT_PIDFD = libc::FAN_REPORT_PIDFD;
+        /// Report thread IDs instead of process IDs in [`FanotifyEvent::pid()`] (Linux 4.20+).
+        const REPORT_TID = libc::FAN_REPORT_TID;
+        /// Identify filesystem objects with file handles (in [`FanotifyInfo::Fid`] records)
+        /// instead of file descriptors (Linux 5.1+).
+        const REPORT_FID = libc::FAN_REPORT_FID;
+        /// Report directory file handles in [`FanotifyInfo::Dfid`] records (Linux 5.9+).
+        const REPORT_DIR_FID = libc::FAN_REPORT_DIR_FID;
+        /// Report the names of directory entries (Linux 5.9+). This must be combined with
+        /// [`Self::REPORT_DIR_FID`].
+        const REPORT_NAME = libc::FAN_REPORT_NAME;
+        /// Report file handles of the targets of directory entry modification events
+        /// (Linux 5.17+).
+        const REPORT_TARGET_FID = libc::FAN_REPORT_TARGET_FID;
+        /// An alias for [`Self::REPORT_DIR_FID`] | [`Self::REPORT_NAME`].
+        const REPORT_DFID_NAME = libc::FAN_REPORT_DFID_NAME;
+        /// An alias for [`Self::REPORT_DFID_NAME`] | [`Self::REPORT_FID`] |
+        /// [`Self::REPORT_TARGET_FID`].
+        const REPORT_DFID_NAME_TARGET = libc::FAN_REPORT_DFID_NAME_TARGET;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags to [`fanotify_mark()`] or [`Fanotify::mark()`].
+    ///
+    /// Exactly one of [`Self::ADD`], [`Self::REMOVE`], or [`Self::FLUSH`] must be specified.
+    /// See `fanotify_mark(2)` for more information.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    pub struct FanotifyMarkFlags: libc::c_uint {
+        /// Add the events in the mask to the mark.
+        const ADD = libc::FAN_MARK_ADD;
+        /// Remove the events in the mask from the mark.
+        const REMOVE = libc::FAN_MARK_REMOVE;
+        /// Remove all marks of the specified type (inode marks by default, or mount/filesystem
+        /// marks if [`Self::MOUNT`]/[`Self::FILESYSTEM`] are specified).
+        const FLUSH = libc::FAN_MARK_FLUSH;
+
+        /// Don't dereference the specified path if it is a symbolic link.
+        const DONT_FOLLOW = libc::FAN_MARK_DONT_FOLLOW;
+        /// Fail with `ENOTDIR` if the specified path is not a directory.
+        const ONLYDIR = libc::FAN_MARK_ONLYDIR;
+        /// Mark the mount containing the specified path, instead of the path itself.
+        const MOUNT = libc::FAN_MARK_MOUNT;
+        /// Mark the filesystem containing the specified path, instead of the path itself
+        /// (Linux 4.20+).
+        const FILESYSTEM = libc::FAN_MARK_FILESYSTEM;
+
+        /// Add/remove the events to/from the "ignored mask" instead of the regular mask.
+        const IGNORED_MASK = libc::FAN_MARK_IGNORED_MASK;
+        /// Don't clear the ignored mask when the file is modified.
+        const IGNORED_SURV_MODIFY = libc::FAN_MARK_IGNORED_SURV_MODIFY;
+        /// Allow the kernel to evict the inode mark from memory if the inode is not otherwise
+        /// in use (Linux 5.19+).
+        const EVICTABLE = libc::FAN_MARK_EVICTABLE;
+        /// Like [`Self::IGNORED_MASK`], but with semantics that work properly for directories
+        /// and for mount/filesystem marks (Linux 6.0+).
+        const IGNORE = libc::FAN_MARK_IGNORE;
+        /// An alias for [`Self::IGNORE`] | [`Self::IGNORED_SURV_MODIFY`].
+        const IGNORE_SURV = libc::FAN_MARK_IGNORE_SURV;
+    }
+}
+
+bitflags::bitflags! {
+    /// Masks identifying fanotify events (and some other flags).
+    ///
+    /// These are passed to [`fanotify_mark()`]/[`Fanotify::mark()`], and they are returned in
+    /// the events yielded by a [`FanotifyEventIter`]. See `fanotify(7)` for more information.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    pub struct FanotifyMask: u64 {
+        /// A file or directory was accessed (read).
+        const ACCESS = libc::FAN_ACCESS;
+        /// A file was modified.
+        const MODIFY = libc::FAN_MODIFY;
+        /// The metadata of a file or directory was changed (Linux 5.1+).
+        const ATTRIB = libc::FAN_ATTRIB;
+        /// A file that was opened for writing was closed.
+        const CLOSE_WRITE = libc::FAN_CLOSE_WRITE;
+        /// A file or directory that was opened read-only was closed.
+        const CLOSE_NOWRITE = libc::FAN_CLOSE_NOWRITE;
+        /// A file or directory was opened.
+        const OPEN = libc::FAN_OPEN;
+        /// A file or directory was moved out of a marked directory (Linux 5.1+).
+        const MOVED_FROM = libc::FAN_MOVED_FROM;
+        /// A file or directory was moved into a marked directory (Linux 5.1+).
+        const MOVED_TO = libc::FAN_MOVED_TO;
+        /// A file or directory was created in a marked directory (Linux 5.1+).
+        const CREATE = libc::FAN_CREATE;
+        /// A file or directory was deleted from a marked directory (Linux 5.1+).
+        const DELETE = libc::FAN_DELETE;
+        /// A marked file or directory was deleted (Linux 5.1+).
+        const DELETE_SELF = libc::FAN_DELETE_SELF;
+        /// A marked file or directory was moved (Linux 5.1+).
+        const MOVE_SELF = libc::FAN_MOVE_SELF;
+        /// A file was opened with the intent to be executed (Linux 5.0+).
+        const OPEN_EXEC = libc::FAN_OPEN_EXEC;
+        /// A file or directory was renamed (Linux 5.17+).
+        const RENAME = libc::FAN_RENAME;
+        /// A filesystem error was detected (Linux 5.16+).
+        const FS_ERROR = libc::FAN_FS_ERROR;
+
+        /// A permission event for opening a file or directory.
+        const OPEN_PERM = libc::FAN_OPEN_PERM;
+        /// A permission event for reading a file or directory.
+        const ACCESS_PERM = libc::FAN_ACCESS_PERM;
+        /// A permission event for opening a file with the intent to execute it (Linux 5.0+).
+        const OPEN_EXEC_PERM = libc::FAN_OPEN_EXEC_PERM;
+
+        /// An alias for [`Self::CLOSE_WRITE`] | [`Self::CLOSE_NOWRITE`].
+        const CLOSE = libc::FAN_CLOSE;
+        /// An alias for [`Self::MOVED_FROM`] | [`Self::MOVED_TO`].
+        const MOVE = libc::FAN_MOVE;
+
+        /// When passed to [`fanotify_mark()`]/[`Fanotify::mark()`], also generate events for
+        /// the immediate children of marked directories.
+        const EVENT_ON_CHILD = libc::FAN_EVENT_ON_CHILD;
+        /// When passed to [`fanotify_mark()`]/[`Fanotify::mark()`], also generate events for
+        /// directories. When returned in an event, this means that the subject of the event is
+        /// a directory.
+        const ONDIR = libc::FAN_ONDIR;
+
+        /// When returned in an event, this means that the event queue overflowed and some
+        /// events may have been discarded.
+        const Q_OVERFLOW = libc::FAN_Q_OVERFLOW;
+    }
+}
+
+/// A response to a permission event.
+///
+/// See [`fanotify_respond()`] and [`Fanotify::respond()`].
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum FanotifyResponse {
+    /// Allow the operation.
+    Allow,
+    /// Deny the operation; it will fail with `EPERM`.
+    Deny,
+}
+
+/// A file handle identifying a filesystem object, as reported in a fanotify information record.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FanotifyFid<'a> {
+    fsid: [i32; 2],
+    handle_type: i32,
+    handle: &'a [u8],
+}
+
+impl<'a> FanotifyFid<'a> {
+    /// The ID of the filesystem containing the object (as returned by `statfs()`).
+    #[inline]
+    pub fn fsid(&self) -> [i32; 2] {
+        self.fsid
+    }
+
+    /// The type of the file handle (the `handle_type` field of a `struct file_handle`).
+    #[inline]
+    pub fn handle_type(&self) -> i32 {
+        self.handle_type
+    }
+
+    /// The opaque file handle data (the `f_handle` field of a `struct file_handle`).
+    #[inline]
+    pub fn handle(&self) -> &'a [u8] {
+        self.handle
+    }
+
+    fn parse(buf: &'a [u8]) -> Option<(Self, &'a [u8])> {
+        let int_at = |i: usize| -> Option<i32> {
+            Some(i32::from_ne_bytes(buf.get(i..i + 4)?.try_into().ok()?))
+        };
+
+        let fsid = [int_at(0)?, int_at(4)?];
+        let handle_bytes = int_at(8)? as u32 as usize;
+        let handle_type = int_at(12)?;
+
+        let rest = buf.get(16..)?;
+        if handle_bytes > rest.len() {
+            return None;
+        }
+        let (handle, rest) = rest.split_at(handle_bytes);
+
+        Some((
+            Self {
+                fsid,
+                handle_type,
+                handle,
+            },
+            rest,
+        ))
+    }
+}
+
+/// An information record attached to a fanotify event.
+///
+/// These can be retrieved with [`FanotifyEvent::info()`].
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum FanotifyInfo<'a> {
+    /// A file handle identifying the object that the event occurred on
+    /// (`FAN_EVENT_INFO_TYPE_FID`).
+    Fid(FanotifyFid<'a>),
+    /// A file handle identifying the directory that the event occurred in
+    /// (`FAN_EVENT_INFO_TYPE_DFID`).
+    Dfid(FanotifyFid<'a>),
+    /// A file handle identifying the directory that the event occurred in, and the name of the
+    /// directory entry (`FAN_EVENT_INFO_TYPE_DFID_NAME`).
+    DfidName(FanotifyFid<'a>, &'a OsStr),
+    /// For [`FanotifyMask::RENAME`] events, the old directory and name
+    /// (`FAN_EVENT_INFO_TYPE_OLD_DFID_NAME`).
+    OldDfidName(FanotifyFid<'a>, &'a OsStr),
+    /// For [`FanotifyMask::RENAME`] events, the new directory and name
+    /// (`FAN_EVENT_INFO_TYPE_NEW_DFID_NAME`).
+    NewDfidName(FanotifyFid<'a>, &'a OsStr),
+    /// A PID file descriptor referring to the process that caused the event
+    /// (`FAN_EVENT_INFO_TYPE_PIDFD`).
+    ///
+    /// This file descriptor is owned by the [`FanotifyEvent`], and it will be closed when the
+    /// event is dropped. It may also be `FAN_NOPIDFD` if the process has already exited, or
+    /// `FAN_EPIDFD` if an error occurred when creating the PID file descriptor.
+    Pidfd(RawFd),
+    /// Information about a filesystem error (`FAN_EVENT_INFO_TYPE_ERROR`).
+    Error { error: i32, error_count: u32 },
+    /// An information record of an unknown type; the type and the raw data (not including the
+    /// header) are given.
+    Unknown(u8, &'a [u8]),
+}
+
+impl<'a> FanotifyInfo<'a> {
+    fn parse(info_type: u8, data: &'a [u8]) -> Self {
+        let with_name = |fid: FanotifyFid<'a>, rest: &'a [u8]| {
+            let name = match crate::memchr(rest, 0) {
+                Some(i) => &rest[..i],
+                None => rest,
+            };
+            (fid, OsStr::from_bytes(name))
+        };
+
+        let res = match info_type {
+            libc::FAN_EVENT_INFO_TYPE_FID => {
+                FanotifyFid::parse(data).map(|(fid, _)| Self::Fid(fid))
+            }
+            libc::FAN_EVENT_INFO_TYPE_DFID => {
+                FanotifyFid::parse(data).map(|(fid, _)| Self::Dfid(fid))
+            }
+            libc::FAN_EVENT_INFO_TYPE_DFID_NAME => FanotifyFid::parse(data).map(|(fid, rest)| {
+                let (fid, name) = with_name(fid, rest);
+                Self::DfidName(fid, name)
+            }),
+            libc::FAN_EVENT_INFO_TYPE_OLD_DFID_NAME => {
+                FanotifyFid::parse(data).map(|(fid, rest)| {
+                    let (fid, name) = with_name(fid, rest);
+                    Self::OldDfidName(fid, name)
+                })
+            }
+            libc::FAN_EVENT_INFO_TYPE_NEW_DFID_NAME => {
+                FanotifyFid::parse(data).map(|(fid, rest)| {
+                    let (fid, name) = with_name(fid, rest);
+                    Self::NewDfidName(fid, name)
+                })
+            }
+            libc::FAN_EVENT_INFO_TYPE_PIDFD => data
+                .get(..4)
+                .map(|b| Self::Pidfd(i32::from_ne_bytes(b.try_into().unwrap()))),
+            libc::FAN_EVENT_INFO_TYPE_ERROR => data.get(..8).map(|b| Self::Error {
+                error: i32::from_ne_bytes(b[..4].try_into().unwrap()),
+                error_count: u32::from_ne_bytes(b[4..].try_into().unwrap()),
+            }),
+            _ => None,
+        };
+
+        res.unwrap_or(Self::Unknown(info_type, data))
+    }
+}
+
+/// An iterator over the information records attached to a fanotify event.
+///
+/// This can be created by [`FanotifyEvent::info()`].
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Clone)]
+pub struct FanotifyInfoIter<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Iterator for FanotifyInfoIter<'a> {
+    type Item = FanotifyInfo<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.len() < INFO_HEADER_SIZE {
+            return None;
+        }
+
+        // SAFETY: self.buf is >= sizeof(fanotify_event_info_header) bytes long
+        let header = unsafe {
+            core::ptr::read_unaligned(self.buf.as_ptr() as *const libc::fanotify_event_info_header)
+        };
+
+        let len = (header.len as usize)
+            .max(INFO_HEADER_SIZE)
+            .min(self.buf.len());
+        let data = &self.buf[INFO_HEADER_SIZE..len];
+        self.buf = &self.buf[len..];
+
+        Some(FanotifyInfo::parse(header.info_type, data))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (
+            (self.buf.len() >= INFO_HEADER_SIZE) as usize,
+            Some(self.buf.len() / INFO_HEADER_SIZE),
+        )
+    }
+}
+
+impl core::iter::FusedIterator for FanotifyInfoIter<'_> {}
+
+impl fmt::Debug for FanotifyInfoIter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("FanotifyInfoIter")
+            .field(&util::DebugListField(self.clone()))
+            .finish()
+    }
+}
+
+/// An event read from a fanotify file descriptor.
+///
+/// Any file descriptors attached to the event (see [`Self::fd()`] and [`FanotifyInfo::Pidfd`])
+/// are closed when the event is dropped.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+pub struct FanotifyEvent<'a> {
+    meta: sys::fanotify_event_metadata,
+    info: &'a [u8],
+    fd: Option<FileDesc>,
+    pidfd: Option<FileDesc>,
+}
+
+impl<'a> FanotifyEvent<'a> {
+    /// A mask describing the event.
+    #[inline]
+    pub fn mask(&self) -> FanotifyMask {
+        FanotifyMask::from_bits_truncate(self.meta.mask)
+    }
+
+    /// An open file descriptor for the object being accessed.
+    ///
+    /// This is `None` if the event queue overflowed, or if the fanotify instance reports file
+    /// handles instead of file descriptors (e.g. [`FanotifyInitFlags::REPORT_FID`]).
+    #[inline]
+    pub fn fd(&self) -> Option<&FileDesc> {
+        self.fd.as_ref()
+    }
+
+    /// Take ownership of the file descriptor returned by [`Self::fd()`].
+    #[inline]
+    pub fn take_fd(&mut self) -> Option<FileDesc> {
+        self.fd.take()
+    }
+
+    /// The ID of the process (or thread, if [`FanotifyInitFlags::REPORT_TID`] was specified)
+    /// that caused the event.
+    #[inline]
+    pub fn pid(&self) -> libc::pid_t {
+        self.meta.pid
+    }
+
+    /// The PID file descriptor from the [`FanotifyInfo::Pidfd`] information record attached to
+    /// this event, if any.
+    ///
+    /// This is only reported if [`FanotifyInitFlags::REPORT_PIDFD`] was specified.
+    #[inline]
+    pub fn pidfd(&self) -> Option<&FileDesc> {
+        self.pidfd.as_ref()
+    }
+
+    /// Create an iterator over the information records attached to this event.
+    #[inline]
+    pub fn info(&self) -> FanotifyInfoIter<'a> {
+        FanotifyInfoIter { buf: self.info }
+    }
+}
+
+impl fmt::Debug for FanotifyEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FanotifyEvent")
+            .field("mask", &self.mask())
+            .field("fd", &self.fd.as_ref().map(|fd| fd.fd()))
+            .field("pid", &self.pid())
+            .field("info", &self.info())
+            .finish()
+    }
+}
+
+/// Create a new fanotify file descriptor.
+///
+/// `event_f_flags` specifies the flags with which the file descriptors returned in events will
+/// be opened (for example, `O_RDONLY | O_CLOEXEC`). See `fanotify_init(2)` for more
+/// information.
+///
+/// This usually requires the `CAP_SYS_ADMIN` capability. Since Linux 5.13, unprivileged users
+/// may create fanotify instances that report file handles (e.g. with
+/// [`FanotifyInitFlags::REPORT_FID`]), with some restrictions.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn fanotify_init(flags: FanotifyInitFlags, event_f_flags: OFlag) -> Result<FileDesc> {
+    unsafe { Error::unpack_fdesc(libc::fanotify_init(flags.bits(), event_f_flags.bits() as _)) }
+}
+
+/// Add, remove, or modify a mark on the filesystem object specified by `dirfd` and `path`.
+///
+/// `path` is interpreted relative to `dirfd` (which may be `AT_FDCWD`), similarly to
+/// `openat()`. See [`fanotify_mark_fd()`] to mark the object referred to by a file descriptor
+/// directly.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn fanotify_mark<P: AsPath>(
+    fd: RawFd,
+    flags: FanotifyMarkFlags,
+    mask: FanotifyMask,
+    dirfd: RawFd,
+    path: P,
+) -> Result<()> {
+    path.with_cstr(|path| {
+        Error::unpack_nz(unsafe {
+            libc::fanotify_mark(fd, flags.bits(), mask.bits(), dirfd, path.as_ptr())
+        })
+    })
+}
+
+/// Add, remove, or modify a mark on the filesystem object referred to by `target`.
+///
+/// This is equivalent to calling `fanotify_mark()` with `dirfd=target` and a NULL path. See
+/// [`fanotify_mark()`].
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn fanotify_mark_fd(
+    fd: RawFd,
+    flags: FanotifyMarkFlags,
+    mask: FanotifyMask,
+    target: RawFd,
+) -> Result<()> {
+    Error::unpack_nz(unsafe {
+        libc::fanotify_mark(fd, flags.bits(), mask.bits(), target, core::ptr::null())
+    })
+}
+
+/// Respond to the permission event whose file descriptor is `event_fd` on the fanotify instance
+/// specified by `fd`.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn fanotify_respond(fd: RawFd, event_fd: RawFd, response: FanotifyResponse) -> Result<()> {
+    let response = libc::fanotify_response {
+        fd: event_fd,
+        response: match response {
+            FanotifyResponse::Allow => libc::FAN_ALLOW,
+            FanotifyResponse::Deny => libc::FAN_DENY,
+        },
+    };
+
+    let buf = unsafe {
+        core::slice::from_raw_parts(
+            &response as *const _ as *const u8,
+            core::mem::size_of::<libc::fanotify_response>(),
+        )
+    };
+    crate::write(fd, buf)?;
+    Ok(())
+}
+
+/// An iterator over events that were `read()` from a fanotify file descriptor.
+///
+/// The easiest way to obtain one of these iterators is by calling [`Fanotify::read_events()`].
+///
+/// When this iterator is dropped, any file descriptors attached to events that have not yet been
+/// yielded are closed.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+pub struct FanotifyEventIter<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> FanotifyEventIter<'a> {
+    /// Read one or more events from the fanotify instance specified by `fd` into the given
+    /// buffer, and return an iterator over the events.
+    ///
+    /// `buf` must be large enough to hold at least one event; when information records are
+    /// being reported, a buffer of 4096 bytes or more is recommended.
+    ///
+    /// For a safe version of this method, use [`Fanotify`] and see [`Fanotify::read_events()`].
+    ///
+    /// # Safety
+    ///
+    /// `fd` MUST refer to a fanotify file descriptor. If it does not, the returned iterator will
+    /// try to interpret whatever data was read from `fd` as fanotify events, and it may close
+    /// arbitrary file descriptors.
+    #[inline]
+    pub unsafe fn read_from(fd: RawFd, buf: &'a mut [u8]) -> Result<Self> {
+        let n = crate::read(fd, buf)?;
+        Ok(Self { buf: &buf[..n] })
+    }
+}
+
+impl<'a> Iterator for FanotifyEventIter<'a> {
+    type Item = FanotifyEvent<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.len() < METADATA_SIZE {
+            return None;
+        }
+
+        // SAFETY: self.buf is >= sizeof(fanotify_event_metadata) bytes long, and initialized by
+        // the kernel. It may not be properly aligned, so use read_unaligned().
+        let meta = unsafe {
+            core::ptr::read_unaligned(self.buf.as_ptr() as *const sys::fanotify_event_metadata)
+        };
+
+        let event_len = (meta.event_len as usize)
+            .max(METADATA_SIZE)
+            .min(self.buf.len());
+        let metadata_len = (meta.metadata_len as usize)
+            .max(METADATA_SIZE)
+            .min(event_len);
+
+        let info = &self.buf[metadata_len..event_len];
+        self.buf = &self.buf[event_len..];
+
+        // Take ownership of any file descriptors attached to the event
+        let fd = if meta.fd >= 0 {
+            Some(unsafe { FileDesc::new(meta.fd) })
+        } else {
+            None
+        };
+        let pidfd = FanotifyInfoIter { buf: info }.find_map(|info| match info {
+            FanotifyInfo::Pidfd(pidfd) if pidfd >= 0 => Some(unsafe { FileDesc::new(pidfd) }),
+            _ => None,
+        });
+
+        Some(FanotifyEvent {
+            meta,
+            info,
+            fd,
+            pidfd,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (
+            (self.buf.len() >= METADATA_SIZE) as usize,
+            Some(self.buf.len() / METADATA_SIZE),
+        )
+    }
+}
+
+impl core::iter::FusedIterator for FanotifyEventIter<'_> {}
+
+impl Drop for FanotifyEventIter<'_> {
+    fn drop(&mut self) {
+        // Close the file descriptors for any remaining events
+        self.for_each(drop);
+    }
+}
+
+impl fmt::Debug for FanotifyEventIter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FanotifyEventIter")
+            .field("len", &self.buf.len())
+            .finish()
+    }
+}
+
+/// A wrapper around a fanotify file descriptor.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Debug)]
+pub struct Fanotify(FileDesc);
+
+impl Fanotify {
+    /// Create a new fanotify file descriptor.
+    ///
+    /// See [`fanotify_init()`].
+    #[inline]
+    pub fn new(flags: FanotifyInitFlags, event_f_flags: OFlag) -> Result<Self> {
+        fanotify_init(flags, event_f_flags).map(Self)
+    }
+
+    /// See [`fanotify_mark()`].
+    #[inline]
+    pub fn mark<P: AsPath>(
+        &self,
+        flags: FanotifyMarkFlags,
+        mask: FanotifyMask,
+        dirfd: RawFd,
+        path: P,
+    ) -> Result<()> {
+        fanotify_mark(self.fd(), flags, mask, dirfd, path)
+    }
+
+    /// See [`fanotify_mark_fd()`].
+    #[inline]
+    pub fn mark_fd(
+        &self,
+        flags: FanotifyMarkFlags,
+        mask: FanotifyMask,
+        target: RawFd,
+    ) -> Result<()> {
+        fanotify_mark_fd(self.fd(), flags, mask, target)
+    }
+
+    /// Read one or more events from this fanotify instance into the given buffer, and return an
+    /// iterator over the events.
+    ///
+    /// See [`FanotifyEventIter::read_from()`] for information on the buffer size.
+    #[inline]
+    pub fn read_events<'a>(&self, buf: &'a mut [u8]) -> Result<FanotifyEventIter<'a>> {
+        unsafe { FanotifyEventIter::read_from(self.fd(), buf) }
+    }
+
+    /// Respond to the given permission event.
+    ///
+    /// This fails with `EBADF` (without calling into the kernel) if the event has no file
+    /// descriptor, or it has been taken with [`FanotifyEvent::take_fd()`].
+    #[inline]
+    pub fn respond(&self, event: &FanotifyEvent, response: FanotifyResponse) -> Result<()> {
+        match event.fd() {
+            Some(fd) => fanotify_respond(self.fd(), fd.fd(), response),
+            None => Err(Error::from_code(libc::EBADF)),
+        }
+    }
+
+    #[inline]
+    pub fn fd(&self) -> RawFd {
+        self.0.fd()
+    }
+
+    #[inline]
+    pub fn into_fd(self) -> RawFd {
+        self.0.into_fd()
+    }
+
+    /// Create a new `Fanotify` wrapper around the given fanotify file descriptor.
+    ///
+    /// # Safety
+    ///
+    /// The given file descriptor must refer to a valid fanotify instance, and it must not be in
+    /// use by other code.
+    #[inline]
+    pub unsafe fn from_fd(fd: RawFd) -> Self {
+        Self(FileDesc::new(fd))
+    }
+}
+
+impl From<Fanotify> for FileDesc {
+    #[inline]
+    fn from(f: Fanotify) -> Self {
+        f.0
+    }
+}
+
+impl AsRef<BorrowedFd> for Fanotify {
+    #[inline]
+    fn as_ref(&self) -> &BorrowedFd {
+        self.0.as_ref()
+    }
+}
+
+#[cfg(feature = "std")]
+impl AsRawFd for Fanotify {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+#[cfg(feature = "std")]
+impl IntoRawFd for Fanotify {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromRawFd for Fanotify {
+    #[inline]
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self::from_fd(fd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags() {
+        let f = match Fanotify::new(FanotifyInitFlags::empty(), OFlag::O_RDONLY) {
+            Ok(f) => f,
+            Err(e) if e == Errno::EPERM => return,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(!f.as_ref().get_cloexec().unwrap());
+        assert!(!f.as_ref().get_nonblocking().unwrap());
+
+        let f = Fanotify::new(
+            FanotifyInitFlags::CLOEXEC | FanotifyInitFlags::NONBLOCK,
+            OFlag::O_RDONLY,
+        )
+        .unwrap();
+        assert!(f.as_ref().get_cloexec().unwrap());
+        assert!(f.as_ref().get_nonblocking().unwrap());
+
+        let mut buf = [0; 4096];
+        assert_eq!(f.read_events(&mut buf).unwrap_err(), Errno::EAGAIN);
+    }
+
+    #[test]
+    fn test_eventiter_empty() {
+        let mut it = FanotifyEventIter { buf: &[] };
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert!(it.next().is_none());
+
+        let mut it = FanotifyInfoIter { buf: &[] };
+        assert_eq!(it.size_hint(), (0, Some(0)));
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn test_info_parse() {
+        let mut buf = [0u8; 24];
+        buf[0..4].copy_from_slice(&1i32.to_ne_bytes());
+        buf[4..8].copy_from_slice(&2i32.to_ne_bytes());
+        buf[8..12].copy_from_slice(&3u32.to_ne_bytes());
+        buf[12..16].copy_from_slice(&4i32.to_ne_bytes());
+        buf[16..19].copy_from_slice(b"abc");
+        buf[19..22].copy_from_slice(b"de\0");
+
+        let fid = FanotifyFid {
+            fsid: [1, 2],
+            handle_type: 4,
+            handle: b"abc",
+        };
+
+        assert_eq!(
+            FanotifyInfo::parse(libc::FAN_EVENT_INFO_TYPE_FID, &buf),
+            FanotifyInfo::Fid(fid)
+        );
+        assert_eq!(
+            FanotifyInfo::parse(libc::FAN_EVENT_INFO_TYPE_DFID_NAME, &buf),
+            FanotifyInfo::DfidName(fid, OsStr::from_bytes(b"de"))
+        );
+        // Truncated
+        assert_eq!(
+            FanotifyInfo::parse(libc::FAN_EVENT_INFO_TYPE_FID, &buf[..18]),
+            FanotifyInfo::Unknown(libc::FAN_EVENT_INFO_TYPE_FID, &buf[..18])
+        );
+        assert_eq!(
+            FanotifyInfo::parse(libc::FAN_EVENT_INFO_TYPE_PIDFD, &buf[..4]),
+            FanotifyInfo::Pidfd(1)
+        );
+        assert_eq!(
+            FanotifyInfo::parse(libc::FAN_EVENT_INFO_TYPE_ERROR, &buf[..8]),
+            FanotifyInfo::Error {
+                error: 1,
+                error_count: 2
+            }
+        );
+        assert_eq!(
+            FanotifyInfo::parse(255, &buf[..1]),
+            FanotifyInfo::Unknown(255, &buf[..1])
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_report_dfid_name() {
+        let tmpdir = tempfile::tempdir().unwrap();
+
+        let f = match Fanotify::new(
+            FanotifyInitFlags::CLOEXEC | FanotifyInitFlags::REPORT_DFID_NAME,
+            OFlag::O_RDONLY,
+        ) {
+            Ok(f) => f,
+            Err(e) if e == Errno::EPERM || e == Errno::EINVAL => return,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(f.as_ref().get_cloexec().unwrap());
+
+        f.mark(
+            FanotifyMarkFlags::ADD | FanotifyMarkFlags::ONLYDIR,
+            FanotifyMask::CREATE | FanotifyMask::DELETE,
+            libc::AT_FDCWD,
+            tmpdir.path(),
+        )
+        .unwrap();
+
+        fn handle_fid(info: FanotifyInfo) -> FanotifyFid {
+            match info {
+                FanotifyInfo::DfidName(fid, name) => {
+                    assert_eq!(name, "file");
+                    fid
+                }
+                info => panic!("{:?}", info),
+            }
+        }
+
+        let mut buf1 = [0; 4096];
+        let mut buf2 = [0; 4096];
+
+        std::fs::File::create(tmpdir.path().join("file")).unwrap();
+        let mut events = f.read_events(&mut buf1).unwrap();
+        let event = events.next().unwrap();
+        assert!(events.next().is_none());
+        assert_eq!(event.mask(), FanotifyMask::CREATE);
+        assert!(event.fd().is_none());
+        assert!(event.pidfd().is_none());
+        assert_eq!(event.pid(), crate::getpid());
+        let mut info = event.info();
+        let fid1 = handle_fid(info.next().unwrap());
+        assert!(info.next().is_none());
+
+        std::fs::remove_file(tmpdir.path().join("file")).unwrap();
+        let mut events = f.read_events(&mut buf2).unwrap();
+        let event = events.next().unwrap();
+        assert!(events.next().is_none());
+        assert_eq!(event.mask(), FanotifyMask::DELETE);
+        let mut info = event.info();
+        let fid2 = handle_fid(info.next().unwrap());
+        assert!(info.next().is_none());
+
+        assert_eq!(fid1, fid2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_permission_events() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+
+        let f = match Fanotify::new(
+            FanotifyInitFlags::CLOEXEC | FanotifyInitFlags::CLASS_CONTENT,
+            OFlag::O_RDONLY | OFlag::O_CLOEXEC,
+        ) {
+            Ok(f) => f,
+            Err(e) if e == Errno::EPERM => return,
+            Err(e) => panic!("{}", e),
+        };
+
+        f.mark(
+            FanotifyMarkFlags::ADD,
+            FanotifyMask::OPEN_PERM,
+            libc::AT_FDCWD,
+            tmpfile.path(),
+        )
+        .unwrap();
+
+        for &response in [FanotifyResponse::Deny, FanotifyResponse::Allow].iter() {
+            let path = tmpfile.path();
+
+            let pid = match unsafe { crate::fork() }.unwrap() {
+                None => {
+                    let code = match crate::open(path, OFlag::O_RDONLY, 0) {
+                        Ok(_) => 0,
+                        Err(e) if e == Errno::EPERM => 1,
+                        Err(_) => 2,
+                    };
+                    unsafe { crate::_exit(code) }
+                }
+                Some(pid) => pid,
+            };
+
+            let mut buf = [0; 4096];
+            let mut events = f.read_events(&mut buf).unwrap();
+            let mut event = events.next().unwrap();
+            assert!(events.next().is_none());
+
+            assert_eq!(event.mask(), FanotifyMask::OPEN_PERM);
+            assert_eq!(event.pid(), pid);
+            assert_eq!(event.info().next(), None);
+
+            let stat1 = event.fd().unwrap().stat().unwrap();
+            let stat2 = crate::stat(path).unwrap();
+            assert_eq!((stat1.dev(), stat1.ino()), (stat2.dev(), stat2.ino()));
+
+            f.respond(&event, response).unwrap();
+
+            drop(event.take_fd());
+            assert_eq!(f.respond(&event, response).unwrap_err(), Errno::EBADF);
+
+            assert_eq!(
+                crate::waitpid(pid, crate::WaitFlags::empty()).unwrap(),
+                Some((
+                    pid,
+                    crate::WaitStatus::Exited(if response == FanotifyResponse::Allow {
+                        0
+                    } else {
+                        1
+                    })
+                ))
+            );
+        }
+    }
+}
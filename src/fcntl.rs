@@ -154,6 +154,9 @@ bitflags::bitflags! {
         const AT_EMPTY_PATH = libc::AT_EMPTY_PATH;
         #[cfg(target_os = "linux")]
         const AT_NO_AUTOMOUNT = libc::AT_NO_AUTOMOUNT;
+        /// Apply the change to the entire mount tree (for [`mount_setattr()`]).
+        #[cfg(target_os = "linux")]
+        const AT_RECURSIVE = sys::AT_RECURSIVE;
 
         /// When using [`statx()`] to query a remote filesystem, match the behavior of `stat()`
         /// when deciding whether to synchronize (this is the default).
@@ -615,6 +618,340 @@ pub fn fcntl_setpipe_sz(fd: RawFd, cap: libc::c_int) -> Result<()> {
     Ok(())
 }
 
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd")))
+)]
+#[cfg(any(linuxlike, target_os = "freebsd"))]
+bitflags::bitflags! {
+    /// File seals, as used by [`fcntl_add_seals()`] and [`fcntl_get_seals()`].
+    #[derive(Default)]
+    pub struct SealFlags: libc::c_int {
+        /// Prevent further seals from being added.
+        const F_SEAL_SEAL = libc::F_SEAL_SEAL;
+        /// Prevent the file from being shrunk.
+        const F_SEAL_SHRINK = libc::F_SEAL_SHRINK;
+        /// Prevent the file from being grown.
+        const F_SEAL_GROW = libc::F_SEAL_GROW;
+        /// Prevent the contents of the file from being modified.
+        ///
+        /// This cannot be added while any writable shared mappings of the file exist (`EBUSY`).
+        const F_SEAL_WRITE = libc::F_SEAL_WRITE;
+        /// Like `F_SEAL_WRITE`, but existing writable shared mappings are still allowed to
+        /// modify the file contents.
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const F_SEAL_FUTURE_WRITE = libc::F_SEAL_FUTURE_WRITE;
+    }
+}
+
+/// Add the given seals to the file referred to by `fd`.
+///
+/// The file must support sealing (for example, a file created with [`memfd_create()`] and
+/// `MemfdFlags::ALLOW_SEALING`); otherwise this fails with `EINVAL`. If `F_SEAL_SEAL` has already
+/// been set, this fails with `EPERM`.
+///
+/// [`memfd_create()`]: ./fn.memfd_create.html
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd")))
+)]
+#[cfg(any(linuxlike, target_os = "freebsd"))]
+#[inline]
+pub fn fcntl_add_seals(fd: RawFd, seals: SealFlags) -> Result<()> {
+    unsafe {
+        fcntl_arg(fd, libc::F_ADD_SEALS, seals.bits())?;
+    }
+    Ok(())
+}
+
+/// Get the seals that have been applied to the file referred to by `fd`.
+///
+/// See [`fcntl_add_seals()`].
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd")))
+)]
+#[cfg(any(linuxlike, target_os = "freebsd"))]
+#[inline]
+pub fn fcntl_get_seals(fd: RawFd) -> Result<SealFlags> {
+    let seals = unsafe { fcntl_arg(fd, libc::F_GET_SEALS, 0)? };
+    Ok(SealFlags::from_bits_truncate(seals))
+}
+
+/// The type of a POSIX record lock (see [`Flock`]).
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[repr(i32)]
+pub enum LockType {
+    /// A read (shared) lock.
+    F_RDLCK = libc::F_RDLCK as _,
+    /// A write (exclusive) lock.
+    F_WRLCK = libc::F_WRLCK as _,
+    /// No lock; used to release a lock.
+    F_UNLCK = libc::F_UNLCK as _,
+}
+
+/// The position relative to which [`Flock::start`] is interpreted.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[repr(i32)]
+pub enum LockWhence {
+    /// Relative to the start of the file.
+    SEEK_SET = libc::SEEK_SET,
+    /// Relative to the current file offset.
+    SEEK_CUR = libc::SEEK_CUR,
+    /// Relative to the end of the file.
+    SEEK_END = libc::SEEK_END,
+}
+
+/// A description of a POSIX record lock (or an open file description lock) on a range of bytes
+/// in a file.
+///
+/// The range begins `start` bytes from the position specified by `whence`, and covers `len`
+/// bytes. If `len` is 0, the range extends to the end of the file (including any data appended
+/// later). A negative `len` covers the `-len` bytes preceding `start`.
+///
+/// See [`fcntl_setlk()`] and [`fcntl_getlk()`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Flock {
+    pub ltype: LockType,
+    pub whence: LockWhence,
+    pub start: i64,
+    pub len: i64,
+    /// The PID of the process holding a conflicting lock (as returned by [`fcntl_getlk()`]).
+    ///
+    /// This is ignored in locks that are passed in (when setting or checking locks). It is -1 for
+    /// open file description locks returned by [`fcntl_ofd_getlk()`].
+    pub pid: libc::pid_t,
+}
+
+impl Flock {
+    /// Create a new `Flock` describing the given range.
+    #[inline]
+    pub fn new(ltype: LockType, whence: LockWhence, start: i64, len: i64) -> Self {
+        Self {
+            ltype,
+            whence,
+            start,
+            len,
+            pid: 0,
+        }
+    }
+
+    #[inline]
+    fn to_raw(self) -> libc::flock {
+        let mut fl: libc::flock = unsafe { core::mem::zeroed() };
+        fl.l_type = self.ltype as _;
+        fl.l_whence = self.whence as _;
+        fl.l_start = self.start as _;
+        fl.l_len = self.len as _;
+        fl
+    }
+
+    #[inline]
+    fn from_raw(fl: &libc::flock) -> Self {
+        Self {
+            ltype: match fl.l_type as _ {
+                libc::F_RDLCK => LockType::F_RDLCK,
+                libc::F_WRLCK => LockType::F_WRLCK,
+                _ => LockType::F_UNLCK,
+            },
+            whence: match fl.l_whence as _ {
+                libc::SEEK_CUR => LockWhence::SEEK_CUR,
+                libc::SEEK_END => LockWhence::SEEK_END,
+                _ => LockWhence::SEEK_SET,
+            },
+            start: fl.l_start as _,
+            len: fl.l_len as _,
+            pid: fl.l_pid,
+        }
+    }
+}
+
+#[inline]
+fn fcntl_getlk_imp(fd: RawFd, cmd: libc::c_int, lock: &Flock) -> Result<Option<Flock>> {
+    let mut fl = lock.to_raw();
+    unsafe {
+        fcntl_ptr(fd, cmd, &mut fl as *mut _ as *mut _)?;
+    }
+
+    Ok(if fl.l_type as libc::c_int == libc::F_UNLCK {
+        None
+    } else {
+        Some(Flock::from_raw(&fl))
+    })
+}
+
+#[inline]
+fn fcntl_setlk_imp(fd: RawFd, cmd: libc::c_int, lock: &Flock) -> Result<()> {
+    let mut fl = lock.to_raw();
+    unsafe {
+        fcntl_ptr(fd, cmd, &mut fl as *mut _ as *mut _)?;
+    }
+    Ok(())
+}
+
+/// Check whether the lock described by `lock` could be placed (`F_GETLK`).
+///
+/// If the lock could be placed, `None` is returned. Otherwise, a description of one of the
+/// conflicting locks is returned (including the PID of the process holding it).
+///
+/// Note that POSIX record locks are owned by processes; locks held by the current process never
+/// conflict.
+#[inline]
+pub fn fcntl_getlk(fd: RawFd, lock: &Flock) -> Result<Option<Flock>> {
+    fcntl_getlk_imp(fd, libc::F_GETLK, lock)
+}
+
+/// Acquire or release a POSIX record lock (`F_SETLK`).
+///
+/// If `lock.ltype` is `F_RDLCK` or `F_WRLCK`, the lock is acquired; if it is `F_UNLCK`, the lock
+/// is released. If a conflicting lock is held by another process, this fails with `EAGAIN` or
+/// `EACCES`.
+///
+/// **WARNING**: POSIX record locks are associated with the process, and *all* of the process's
+/// locks on a file are released when *any* file descriptor referring to that file is closed. See
+/// [`fcntl_ofd_setlk()`] for a saner alternative on Linux.
+#[inline]
+pub fn fcntl_setlk(fd: RawFd, lock: &Flock) -> Result<()> {
+    fcntl_setlk_imp(fd, libc::F_SETLK, lock)
+}
+
+/// Acquire or release a POSIX record lock, waiting for conflicting locks to be released
+/// (`F_SETLKW`).
+///
+/// This is identical to [`fcntl_setlk()`], except that it blocks (instead of failing) if a
+/// conflicting lock is held. It may fail with `EDEADLK` if a deadlock is detected, or `EINTR` if
+/// interrupted by a signal.
+#[inline]
+pub fn fcntl_setlkw(fd: RawFd, lock: &Flock) -> Result<()> {
+    fcntl_setlk_imp(fd, libc::F_SETLKW, lock)
+}
+
+/// Check whether the open file description lock described by `lock` could be placed
+/// (`F_OFD_GETLK`).
+///
+/// This is the open file description lock equivalent of [`fcntl_getlk()`]. Unlike POSIX record
+/// locks, these locks are associated with the open file description, so locks held through other
+/// open file descriptions in the same process *do* conflict.
+///
+/// `lock.pid` is ignored.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[cfg(linuxlike)]
+#[inline]
+pub fn fcntl_ofd_getlk(fd: RawFd, lock: &Flock) -> Result<Option<Flock>> {
+    fcntl_getlk_imp(fd, libc::F_OFD_GETLK, lock)
+}
+
+/// Acquire or release an open file description lock (`F_OFD_SETLK`).
+///
+/// These locks are associated with the open file description (rather than the process), so they
+/// are only released when the last file descriptor referring to the open file description is
+/// closed (or when they are explicitly unlocked). Otherwise, this behaves like [`fcntl_setlk()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[cfg(linuxlike)]
+#[inline]
+pub fn fcntl_ofd_setlk(fd: RawFd, lock: &Flock) -> Result<()> {
+    fcntl_setlk_imp(fd, libc::F_OFD_SETLK, lock)
+}
+
+/// Acquire or release an open file description lock, waiting for conflicting locks to be released
+/// (`F_OFD_SETLKW`).
+///
+/// See [`fcntl_ofd_setlk()`] and [`fcntl_setlkw()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[cfg(linuxlike)]
+#[inline]
+pub fn fcntl_ofd_setlkw(fd: RawFd, lock: &Flock) -> Result<()> {
+    fcntl_setlk_imp(fd, libc::F_OFD_SETLKW, lock)
+}
+
+/// A guard that holds a lock on a range of a file, and releases it when dropped.
+///
+/// Errors that occur while releasing the lock are ignored.
+#[derive(Debug)]
+pub struct FlockGuard<'a> {
+    fd: &'a BorrowedFd,
+    lock: Flock,
+    setlk_cmd: libc::c_int,
+}
+
+impl<'a> FlockGuard<'a> {
+    /// Acquire a POSIX record lock on the given file descriptor, returning a guard that will
+    /// release it when dropped.
+    ///
+    /// If `wait` is true, this uses [`fcntl_setlkw()`] to wait for conflicting locks to be
+    /// released; otherwise it uses [`fcntl_setlk()`]. `lock.ltype` must not be `F_UNLCK`, and
+    /// `lock.whence` must be `SEEK_SET` (`EINVAL` is returned otherwise). The latter ensures that
+    /// the range released on drop is the same one that was locked, even if the file offset or
+    /// the file size changes in the meantime.
+    #[inline]
+    pub fn new(fd: &'a BorrowedFd, lock: Flock, wait: bool) -> Result<Self> {
+        Self::new_imp(
+            fd,
+            lock,
+            if wait { libc::F_SETLKW } else { libc::F_SETLK },
+            libc::F_SETLK,
+        )
+    }
+
+    /// Acquire an open file description lock on the given file descriptor, returning a guard
+    /// that will release it when dropped.
+    ///
+    /// See [`Self::new()`] and [`fcntl_ofd_setlk()`].
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    #[inline]
+    pub fn new_ofd(fd: &'a BorrowedFd, lock: Flock, wait: bool) -> Result<Self> {
+        Self::new_imp(
+            fd,
+            lock,
+            if wait {
+                libc::F_OFD_SETLKW
+            } else {
+                libc::F_OFD_SETLK
+            },
+            libc::F_OFD_SETLK,
+        )
+    }
+
+    fn new_imp(
+        fd: &'a BorrowedFd,
+        lock: Flock,
+        lock_cmd: libc::c_int,
+        setlk_cmd: libc::c_int,
+    ) -> Result<Self> {
+        if lock.ltype == LockType::F_UNLCK || lock.whence != LockWhence::SEEK_SET {
+            return Err(Error::from_code(libc::EINVAL));
+        }
+
+        fcntl_setlk_imp(fd.fd(), lock_cmd, &lock)?;
+        Ok(Self {
+            fd,
+            lock,
+            setlk_cmd,
+        })
+    }
+
+    /// Get the description of the lock held by this guard.
+    #[inline]
+    pub fn lock(&self) -> &Flock {
+        &self.lock
+    }
+}
+
+impl Drop for FlockGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        let unlock = Flock {
+            ltype: LockType::F_UNLCK,
+            ..self.lock
+        };
+        let _ = fcntl_setlk_imp(self.fd.fd(), self.setlk_cmd, &unlock);
+    }
+}
+
 /// Get the path to which the given file descriptor is open.
 ///
 /// `buf` must be an array [`PATH_MAX`](./constant.PATH_MAX.html) bytes long.
@@ -743,6 +1080,118 @@ mod tests {
         assert_eq!(file.stat().unwrap().size(), 1024);
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_flock() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let f1 = open(file.path(), OFlag::O_RDWR, 0).unwrap();
+        let f2 = open(file.path(), OFlag::O_RDWR, 0).unwrap();
+
+        let wrlock = Flock::new(LockType::F_WRLCK, LockWhence::SEEK_SET, 0, 10);
+        let unlock = Flock::new(LockType::F_UNLCK, LockWhence::SEEK_SET, 0, 10);
+
+        // Locks held by the same process never conflict
+        fcntl_setlk(f1.fd(), &wrlock).unwrap();
+        assert_eq!(fcntl_getlk(f2.fd(), &wrlock).unwrap(), None);
+        fcntl_setlkw(f2.fd(), &wrlock).unwrap();
+        fcntl_setlk(f1.fd(), &unlock).unwrap();
+
+        assert_eq!(fcntl_getlk(-1, &wrlock).unwrap_err(), Errno::EBADF);
+        assert_eq!(fcntl_setlk(-1, &wrlock).unwrap_err(), Errno::EBADF);
+
+        assert_eq!(
+            FlockGuard::new(&f1, unlock, false).unwrap_err(),
+            Errno::EINVAL
+        );
+        assert_eq!(
+            FlockGuard::new(
+                &f1,
+                Flock::new(LockType::F_WRLCK, LockWhence::SEEK_CUR, 0, 10),
+                false
+            )
+            .unwrap_err(),
+            Errno::EINVAL
+        );
+        let guard = FlockGuard::new(&f1, wrlock, true).unwrap();
+        assert_eq!(guard.lock(), &wrlock);
+        drop(guard);
+
+        #[cfg(linuxlike)]
+        {
+            let guard = FlockGuard::new_ofd(&f1, wrlock, false).unwrap();
+
+            // OFD locks conflict with other open file descriptions (and with POSIX locks), even
+            // in the same process
+            let rdlock = Flock::new(LockType::F_RDLCK, LockWhence::SEEK_SET, 5, 10);
+            let conflict = fcntl_ofd_getlk(f2.fd(), &rdlock).unwrap().unwrap();
+            assert_eq!(conflict.ltype, LockType::F_WRLCK);
+            assert_eq!(conflict.whence, LockWhence::SEEK_SET);
+            assert_eq!((conflict.start, conflict.len), (0, 10));
+            assert_eq!(conflict.pid, -1);
+            assert_eq!(fcntl_getlk(f2.fd(), &rdlock).unwrap(), Some(conflict));
+
+            assert!(matches!(
+                Errno::from_code(fcntl_ofd_setlk(f2.fd(), &rdlock).unwrap_err().code()),
+                Errno::EAGAIN | Errno::EACCES
+            ));
+
+            // Non-overlapping ranges are fine
+            let rdlock2 = Flock::new(LockType::F_RDLCK, LockWhence::SEEK_SET, 10, 5);
+            assert_eq!(fcntl_ofd_getlk(f2.fd(), &rdlock2).unwrap(), None);
+
+            drop(guard);
+            assert_eq!(fcntl_ofd_getlk(f2.fd(), &rdlock).unwrap(), None);
+            fcntl_ofd_setlkw(f2.fd(), &rdlock).unwrap();
+        }
+    }
+
+    #[cfg(linuxlike)]
+    #[test]
+    fn test_seals() {
+        let mfd = crate::memfd_create(
+            CStr::from_bytes_with_nul(b"test_seals\0").unwrap(),
+            crate::MemfdFlags::ALLOW_SEALING,
+        )
+        .unwrap();
+        mfd.write_all(b"abc").unwrap();
+        assert_eq!(fcntl_get_seals(mfd.fd()).unwrap(), SealFlags::empty());
+
+        mfd.add_seals(SealFlags::F_SEAL_SHRINK | SealFlags::F_SEAL_GROW)
+            .unwrap();
+        assert_eq!(
+            mfd.get_seals().unwrap(),
+            SealFlags::F_SEAL_SHRINK | SealFlags::F_SEAL_GROW
+        );
+        assert_eq!(mfd.pwrite(b"xyz", 3).unwrap_err(), Errno::EPERM);
+        mfd.pwrite(b"x", 0).unwrap();
+
+        fcntl_add_seals(mfd.fd(), SealFlags::F_SEAL_WRITE | SealFlags::F_SEAL_SEAL).unwrap();
+        assert_eq!(mfd.pwrite(b"y", 0).unwrap_err(), Errno::EPERM);
+        assert_eq!(
+            fcntl_add_seals(mfd.fd(), SealFlags::F_SEAL_FUTURE_WRITE).unwrap_err(),
+            Errno::EPERM
+        );
+
+        let mut buf = [0; 3];
+        mfd.pread(&mut buf, 0).unwrap();
+        assert_eq!(&buf, b"xbc");
+
+        // Sealing isn't allowed without ALLOW_SEALING
+        let mfd = crate::memfd_create(
+            CStr::from_bytes_with_nul(b"test_seals\0").unwrap(),
+            crate::MemfdFlags::empty(),
+        )
+        .unwrap();
+        assert_eq!(fcntl_get_seals(mfd.fd()).unwrap(), SealFlags::F_SEAL_SEAL);
+        assert_eq!(
+            fcntl_add_seals(mfd.fd(), SealFlags::F_SEAL_WRITE).unwrap_err(),
+            Errno::EPERM
+        );
+
+        let (r, _w) = crate::pipe().unwrap();
+        assert_eq!(fcntl_get_seals(r.fd()).unwrap_err(), Errno::EINVAL);
+    }
+
     #[cfg(target_os = "macos")]
     #[test]
     fn test_getpath() {
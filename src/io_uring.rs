@@ -0,0 +1,1183 @@
+use core::convert::TryInto;
+use core::fmt;
+use core::sync::atomic::{fence, AtomicU32, Ordering};
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use std::os::unix::io::{AsRawFd, IntoRawFd};
+
+use crate::internal_prelude::*;
+use crate::{
+    IoVec, IoVecMut, MapFlags, MsgFlag, PollEvents, ProtFlags, RWFlags, SockAddr, SockFlag,
+};
+
+bitflags::bitflags! {
+    /// Flags for [`IoUringParams::new()`] that control how an io_uring instance is set up.
+    ///
+    /// See `io_uring_setup(2)` for more information.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct IoUringSetupFlags: u32 {
+        /// Perform busy-waiting for I/O completion (only supported for `O_DIRECT` files).
+        const IOPOLL = 1 << 0;
+        /// Create a kernel thread to poll the submission queue.
+        const SQPOLL = 1 << 1;
+        /// Bind the submission queue polling thread to a CPU (with [`Self::SQPOLL`]).
+        const SQ_AFF = 1 << 2;
+        /// Use the completion queue size given by [`IoUringParams::set_cq_entries()`].
+        const CQSIZE = 1 << 3;
+        /// Clamp the queue sizes to the maximum instead of failing with `EINVAL`.
+        const CLAMP = 1 << 4;
+        /// Share the asynchronous worker thread backend with another instance (Linux 5.6+).
+        const ATTACH_WQ = 1 << 5;
+        /// Start the instance disabled (Linux 5.10+).
+        const R_DISABLED = 1 << 6;
+        /// Continue submitting requests after one fails (Linux 5.18+).
+        const SUBMIT_ALL = 1 << 7;
+        /// Don't interrupt tasks to run completion work (Linux 5.19+).
+        const COOP_TASKRUN = 1 << 8;
+        /// Report deferred completion work in the completion queue flags (Linux 5.19+).
+        const TASKRUN_FLAG = 1 << 9;
+        /// Only one task (thread) will submit requests (Linux 6.0+).
+        const SINGLE_ISSUER = 1 << 12;
+        /// Defer completion work until `io_uring_enter()` is called with
+        /// [`IoUringEnterFlags::GETEVENTS`] (Linux 6.1+).
+        const DEFER_TASKRUN = 1 << 13;
+    }
+}
+
+bitflags::bitflags! {
+    /// Features supported by the kernel, as reported by [`IoUringParams::features()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    pub struct IoUringFeatures: u32 {
+        const SINGLE_MMAP = 1 << 0;
+        const NODROP = 1 << 1;
+        const SUBMIT_STABLE = 1 << 2;
+        const RW_CUR_POS = 1 << 3;
+        const CUR_PERSONALITY = 1 << 4;
+        const FAST_POLL = 1 << 5;
+        const POLL_32BITS = 1 << 6;
+        const SQPOLL_NONFIXED = 1 << 7;
+        const EXT_ARG = 1 << 8;
+        const NATIVE_WORKERS = 1 << 9;
+        const RSRC_TAGS = 1 << 10;
+        const CQE_SKIP = 1 << 11;
+        const LINKED_FILE = 1 << 12;
+        const REG_REG_RING = 1 << 13;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`io_uring_enter()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct IoUringEnterFlags: u32 {
+        /// Wait for `min_complete` completions before returning.
+        const GETEVENTS = 1 << 0;
+        /// Wake up the submission queue polling thread.
+        const SQ_WAKEUP = 1 << 1;
+        /// Wait until there is space in the submission queue (with
+        /// [`IoUringSetupFlags::SQPOLL`]).
+        const SQ_WAIT = 1 << 2;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags that can be set on a submission queue entry with [`IoUringSqe::flags()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct IoUringSqeFlags: u8 {
+        /// The file descriptor is an index into the files registered with
+        /// [`IoUring::register_files()`].
+        const FIXED_FILE = 1 << 0;
+        /// Don't start this request until all previously submitted requests have completed.
+        const IO_DRAIN = 1 << 1;
+        /// Link this request with the next one; the next one won't start until this one
+        /// completes successfully.
+        const IO_LINK = 1 << 2;
+        /// Like [`Self::IO_LINK`], but the link is not broken if this request fails.
+        const IO_HARDLINK = 1 << 3;
+        /// Always issue this request asynchronously.
+        const ASYNC = 1 << 4;
+        /// Select a buffer from a registered buffer group.
+        const BUFFER_SELECT = 1 << 5;
+        /// Don't post a completion queue entry if this request succeeds (Linux 5.17+).
+        const CQE_SKIP_SUCCESS = 1 << 6;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`IoUringSqe::fsync()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct IoUringFsyncFlags: u32 {
+        /// Behave like `fdatasync()` instead of `fsync()`.
+        const DATASYNC = 1 << 0;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`IoUringSqe::timeout()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct IoUringTimeoutFlags: u32 {
+        /// The timeout is an absolute time instead of a relative one.
+        const ABS = 1 << 0;
+        /// Measure the timeout with `CLOCK_BOOTTIME` (Linux 5.15+).
+        const BOOTTIME = 1 << 2;
+        /// Measure the timeout with `CLOCK_REALTIME` (Linux 5.15+).
+        const REALTIME = 1 << 3;
+        /// Complete with a result of 0 instead of `-ETIME` when the timeout expires (Linux
+        /// 6.0+).
+        const ETIME_SUCCESS = 1 << 5;
+    }
+}
+
+/// An `io_uring_register()` operation.
+///
+/// See `io_uring_register(2)` for more information.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[repr(u32)]
+#[non_exhaustive]
+pub enum IoUringRegisterOp {
+    REGISTER_BUFFERS = 0,
+    UNREGISTER_BUFFERS = 1,
+    REGISTER_FILES = 2,
+    UNREGISTER_FILES = 3,
+    REGISTER_EVENTFD = 4,
+    UNREGISTER_EVENTFD = 5,
+    REGISTER_FILES_UPDATE = 6,
+    REGISTER_EVENTFD_ASYNC = 7,
+    REGISTER_PROBE = 8,
+    REGISTER_PERSONALITY = 9,
+    UNREGISTER_PERSONALITY = 10,
+    REGISTER_RESTRICTIONS = 11,
+    REGISTER_ENABLE_RINGS = 12,
+}
+
+/// A timestamp or duration in the format used by the kernel for io_uring timeouts.
+///
+/// Unlike [`TimeSpec`](crate::TimeSpec), this always uses 64-bit fields, regardless of the size
+/// of `time_t`.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[repr(C)]
+pub struct KernelTimeSpec {
+    pub tv_sec: i64,
+    pub tv_nsec: i64,
+}
+
+impl From<Duration> for KernelTimeSpec {
+    #[inline]
+    fn from(d: Duration) -> Self {
+        Self {
+            tv_sec: d.as_secs() as i64,
+            tv_nsec: d.subsec_nanos() as i64,
+        }
+    }
+}
+
+impl From<crate::TimeSpec> for KernelTimeSpec {
+    #[allow(clippy::unnecessary_cast)]
+    #[inline]
+    fn from(t: crate::TimeSpec) -> Self {
+        Self {
+            tv_sec: t.tv_sec as i64,
+            tv_nsec: t.tv_nsec as i64,
+        }
+    }
+}
+
+/// Parameters used to set up an io_uring instance.
+///
+/// After a successful call to [`io_uring_setup()`], this contains information on the created
+/// instance.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
+pub struct IoUringParams(sys::io_uring_params);
+
+impl IoUringParams {
+    #[inline]
+    pub fn new(flags: IoUringSetupFlags) -> Self {
+        Self(sys::io_uring_params {
+            flags: flags.bits(),
+            ..Default::default()
+        })
+    }
+
+    /// Set the number of completion queue entries.
+    ///
+    /// This also sets [`IoUringSetupFlags::CQSIZE`].
+    #[inline]
+    pub fn set_cq_entries(&mut self, entries: u32) {
+        self.0.cq_entries = entries;
+        self.0.flags |= IoUringSetupFlags::CQSIZE.bits();
+    }
+
+    /// Set the idle timeout (in milliseconds) of the submission queue polling thread (with
+    /// [`IoUringSetupFlags::SQPOLL`]).
+    #[inline]
+    pub fn set_sq_thread_idle(&mut self, idle_ms: u32) {
+        self.0.sq_thread_idle = idle_ms;
+    }
+
+    #[inline]
+    pub fn flags(&self) -> IoUringSetupFlags {
+        IoUringSetupFlags::from_bits_truncate(self.0.flags)
+    }
+
+    /// The number of submission queue entries.
+    #[inline]
+    pub fn sq_entries(&self) -> u32 {
+        self.0.sq_entries
+    }
+
+    /// The number of completion queue entries.
+    #[inline]
+    pub fn cq_entries(&self) -> u32 {
+        self.0.cq_entries
+    }
+
+    /// The features supported by the kernel.
+    #[inline]
+    pub fn features(&self) -> IoUringFeatures {
+        IoUringFeatures::from_bits_truncate(self.0.features)
+    }
+}
+
+impl Default for IoUringParams {
+    #[inline]
+    fn default() -> Self {
+        Self::new(IoUringSetupFlags::empty())
+    }
+}
+
+/// Create a new io_uring instance with at least `entries` submission queue entries.
+///
+/// On success, `params` is filled in with information about the new instance, which is needed
+/// to map its submission and completion queues. Most users should use [`IoUring`] instead. The
+/// returned file descriptor will have its close-on-exec flag set.
+///
+/// This fails with `ENOSYS` if io_uring is not supported by the kernel, or with `EPERM` if it
+/// has been disabled (e.g. with the `kernel.io_uring_disabled` sysctl).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn io_uring_setup(entries: u32, params: &mut IoUringParams) -> Result<FileDesc> {
+    unsafe {
+        Error::unpack_fdesc(libc::syscall(libc::SYS_io_uring_setup, entries, &mut params.0) as i32)
+    }
+}
+
+/// Submit `to_submit` entries from the submission queue of the io_uring instance specified by
+/// `fd`, and (with [`IoUringEnterFlags::GETEVENTS`]) wait for at least `min_complete`
+/// completions.
+///
+/// On success, the number of submitted entries is returned.
+///
+/// # Safety
+///
+/// All of the entries in the submission queue must reference memory (buffers, `iovec`s,
+/// addresses, etc.) that remains valid until the corresponding requests have completed.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub unsafe fn io_uring_enter(
+    fd: RawFd,
+    to_submit: u32,
+    min_complete: u32,
+    flags: IoUringEnterFlags,
+) -> Result<u32> {
+    let res = Error::unpack(libc::syscall(
+        libc::SYS_io_uring_enter,
+        fd,
+        to_submit,
+        min_complete,
+        flags.bits(),
+        core::ptr::null::<libc::sigset_t>(),
+        0usize,
+    ) as i32)?;
+    Ok(res as u32)
+}
+
+/// Register or unregister resources (files, buffers, etc.) with the io_uring instance specified
+/// by `fd`.
+///
+/// See `io_uring_register(2)` for more information. [`IoUring`] has safe wrappers for some of
+/// these operations.
+///
+/// # Safety
+///
+/// `arg` and `nr_args` must be valid for the given operation. For operations that register
+/// memory, that memory must remain valid until it is unregistered.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub unsafe fn io_uring_register(
+    fd: RawFd,
+    op: IoUringRegisterOp,
+    arg: *const libc::c_void,
+    nr_args: u32,
+) -> Result<u32> {
+    let res = Error::unpack(
+        libc::syscall(libc::SYS_io_uring_register, fd, op as u32, arg, nr_args) as i32,
+    )?;
+    Ok(res as u32)
+}
+
+/// A submission queue entry describing an I/O request.
+///
+/// These are created with the constructor methods below (such as [`Self::read()`]), optionally
+/// modified with [`Self::user_data()`] and [`Self::flags()`], and then added to the submission
+/// queue with [`IoUring::push()`].
+///
+/// Most of the constructors record pointers to the buffers (or other data) that they are given.
+/// Those pointers are only used after the entry is submitted, so it is the responsibility of the
+/// caller of [`IoUring::push()`] to keep the data alive until the request completes.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
+pub struct IoUringSqe(sys::io_uring_sqe);
+
+impl IoUringSqe {
+    #[inline]
+    fn new(opcode: u8, fd: RawFd, addr: u64, len: u32, off: u64) -> Self {
+        Self(sys::io_uring_sqe {
+            opcode,
+            fd,
+            addr,
+            len,
+            off,
+            ..Default::default()
+        })
+    }
+
+    /// A request that does nothing.
+    #[inline]
+    pub fn nop() -> Self {
+        Self::new(sys::IORING_OP_NOP, -1, 0, 0, 0)
+    }
+
+    /// Read from `fd` into `buf`, like `pread()` (Linux 5.6+).
+    ///
+    /// If `offset` is `None`, the current file offset is used (and updated).
+    #[inline]
+    pub fn read(fd: RawFd, buf: &mut [u8], offset: Option<u64>) -> Self {
+        Self::new(
+            sys::IORING_OP_READ,
+            fd,
+            buf.as_mut_ptr() as u64,
+            buf.len().try_into().unwrap_or(u32::MAX),
+            offset.unwrap_or(u64::MAX),
+        )
+    }
+
+    /// Write `buf` to `fd`, like `pwrite()` (Linux 5.6+).
+    ///
+    /// If `offset` is `None`, the current file offset is used (and updated).
+    #[inline]
+    pub fn write(fd: RawFd, buf: &[u8], offset: Option<u64>) -> Self {
+        Self::new(
+            sys::IORING_OP_WRITE,
+            fd,
+            buf.as_ptr() as u64,
+            buf.len().try_into().unwrap_or(u32::MAX),
+            offset.unwrap_or(u64::MAX),
+        )
+    }
+
+    /// Read from `fd` into the given buffers, like [`preadv2()`](crate::preadv2).
+    ///
+    /// Both the `iov` slice and the buffers it refers to must remain valid until the request
+    /// completes.
+    #[inline]
+    pub fn readv(fd: RawFd, iov: &mut [IoVecMut], offset: Option<u64>, flags: RWFlags) -> Self {
+        let mut sqe = Self::new(
+            sys::IORING_OP_READV,
+            fd,
+            iov.as_mut_ptr() as u64,
+            iov.len().try_into().unwrap_or(u32::MAX),
+            offset.unwrap_or(u64::MAX),
+        );
+        sqe.0.op_flags = flags.bits() as u32;
+        sqe
+    }
+
+    /// Write the given buffers to `fd`, like [`pwritev2()`](crate::pwritev2).
+    ///
+    /// Both the `iov` slice and the buffers it refers to must remain valid until the request
+    /// completes.
+    #[inline]
+    pub fn writev(fd: RawFd, iov: &[IoVec], offset: Option<u64>, flags: RWFlags) -> Self {
+        let mut sqe = Self::new(
+            sys::IORING_OP_WRITEV,
+            fd,
+            iov.as_ptr() as u64,
+            iov.len().try_into().unwrap_or(u32::MAX),
+            offset.unwrap_or(u64::MAX),
+        );
+        sqe.0.op_flags = flags.bits() as u32;
+        sqe
+    }
+
+    /// Synchronize the file referred to by `fd` to disk, like `fsync()`/`fdatasync()`.
+    #[inline]
+    pub fn fsync(fd: RawFd, flags: IoUringFsyncFlags) -> Self {
+        let mut sqe = Self::new(sys::IORING_OP_FSYNC, fd, 0, 0, 0);
+        sqe.0.op_flags = flags.bits();
+        sqe
+    }
+
+    /// Accept a connection on the listening socket `sock`, like `accept4()` (Linux 5.5+).
+    ///
+    /// The peer address is not retrieved. On success, the result of the completion is the new
+    /// file descriptor; the caller is responsible for taking ownership of it (e.g. with
+    /// [`FileDesc::new()`]).
+    #[inline]
+    pub fn accept(sock: &crate::Socket, flags: SockFlag) -> Self {
+        let mut sqe = Self::new(sys::IORING_OP_ACCEPT, sock.fd(), 0, 0, 0);
+        sqe.0.op_flags = flags.bits() as u32;
+        sqe
+    }
+
+    /// Connect `sock` to the given address, like [`Socket::connect()`](crate::Socket::connect)
+    /// (Linux 5.5+).
+    ///
+    /// `addr` must remain valid until the request completes.
+    #[inline]
+    pub fn connect(sock: &crate::Socket, addr: &SockAddr) -> Self {
+        let (addr, addrlen) = addr.as_raw();
+        Self::new(
+            sys::IORING_OP_CONNECT,
+            sock.fd(),
+            addr as u64,
+            0,
+            addrlen as u64,
+        )
+    }
+
+    /// Send data on `sock`, like [`Socket::send()`](crate::Socket::send) (Linux 5.6+).
+    #[inline]
+    pub fn send(sock: &crate::Socket, buf: &[u8], flags: MsgFlag) -> Self {
+        let mut sqe = Self::new(
+            sys::IORING_OP_SEND,
+            sock.fd(),
+            buf.as_ptr() as u64,
+            buf.len().try_into().unwrap_or(u32::MAX),
+            0,
+        );
+        sqe.0.op_flags = flags.bits() as u32;
+        sqe
+    }
+
+    /// Receive data on `sock`, like [`Socket::recv()`](crate::Socket::recv) (Linux 5.6+).
+    #[inline]
+    pub fn recv(sock: &crate::Socket, buf: &mut [u8], flags: MsgFlag) -> Self {
+        let mut sqe = Self::new(
+            sys::IORING_OP_RECV,
+            sock.fd(),
+            buf.as_mut_ptr() as u64,
+            buf.len().try_into().unwrap_or(u32::MAX),
+            0,
+        );
+        sqe.0.op_flags = flags.bits() as u32;
+        sqe
+    }
+
+    /// A request that completes when the given timeout expires, or after `count` other requests
+    /// have completed (if `count` is not 0).
+    ///
+    /// If the timeout expires, the request completes with `ETIME`. `ts` must remain valid until
+    /// the request completes.
+    #[inline]
+    pub fn timeout(ts: &KernelTimeSpec, count: u32, flags: IoUringTimeoutFlags) -> Self {
+        let mut sqe = Self::new(
+            sys::IORING_OP_TIMEOUT,
+            -1,
+            ts as *const _ as u64,
+            1,
+            count as u64,
+        );
+        sqe.0.op_flags = flags.bits();
+        sqe
+    }
+
+    /// A request that completes when `fd` is ready for one of the given events, like `poll()`.
+    ///
+    /// On success, the result of the completion is the mask of ready events (which can be
+    /// converted with `PollEvents::from_bits_truncate(res as _)`).
+    #[inline]
+    pub fn poll_add(fd: RawFd, events: PollEvents) -> Self {
+        let mut sqe = Self::new(sys::IORING_OP_POLL_ADD, fd, 0, 0, 0);
+        let events = events.bits() as u16 as u32;
+        // The kernel swaps the 16-bit halves of poll32_events on big-endian systems
+        sqe.0.op_flags = if cfg!(target_endian = "big") {
+            events.rotate_left(16)
+        } else {
+            events
+        };
+        sqe
+    }
+
+    /// Set the user data that will be returned in the completion for this request.
+    #[inline]
+    pub fn user_data(mut self, user_data: u64) -> Self {
+        self.0.user_data = user_data;
+        self
+    }
+
+    /// Set flags that modify how this request is processed.
+    #[inline]
+    pub fn flags(mut self, flags: IoUringSqeFlags) -> Self {
+        self.0.flags = flags.bits();
+        self
+    }
+}
+
+/// A completion queue entry, describing the result of a request.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+pub struct IoUringCqe(sys::io_uring_cqe);
+
+impl IoUringCqe {
+    /// The user data that was set on the corresponding submission queue entry with
+    /// [`IoUringSqe::user_data()`].
+    #[inline]
+    pub fn user_data(&self) -> u64 {
+        self.0.user_data
+    }
+
+    /// The result of the request.
+    ///
+    /// Negative results from the kernel are converted to errors.
+    #[inline]
+    pub fn result(&self) -> Result<u32> {
+        if self.0.res < 0 {
+            Err(Error::from_code(-self.0.res))
+        } else {
+            Ok(self.0.res as u32)
+        }
+    }
+
+    /// The raw `flags` field of the completion.
+    #[inline]
+    pub fn flags(&self) -> u32 {
+        self.0.flags
+    }
+}
+
+impl fmt::Debug for IoUringCqe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IoUringCqe")
+            .field("user_data", &self.user_data())
+            .field("result", &self.result())
+            .field("flags", &self.flags())
+            .finish()
+    }
+}
+
+/// A memory mapping of one of an io_uring instance's rings.
+struct RingMap {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl RingMap {
+    fn new(fd: RawFd, len: usize, offset: u64) -> Result<Self> {
+        let ptr = unsafe {
+            crate::mmap_raw(
+                core::ptr::null_mut(),
+                len,
+                ProtFlags::READ | ProtFlags::WRITE,
+                MapFlags::SHARED | MapFlags::POPULATE,
+                fd,
+                offset,
+            )?
+        };
+        Ok(Self { ptr, len })
+    }
+
+    /// Get a pointer to the value at the given offset.
+    #[inline]
+    fn at<T>(&self, offset: u32) -> *mut T {
+        debug_assert!(offset as usize + core::mem::size_of::<T>() <= self.len);
+        unsafe { self.ptr.add(offset as usize) as *mut T }
+    }
+
+    #[inline]
+    fn atomic(&self, offset: u32) -> &AtomicU32 {
+        unsafe { &*self.at::<AtomicU32>(offset) }
+    }
+}
+
+impl Drop for RingMap {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr as *mut _, self.len);
+        }
+    }
+}
+
+/// An io_uring instance, with its submission and completion queues mapped into memory.
+///
+/// Requests are added to the submission queue with [`Self::push()`] and submitted to the kernel
+/// with [`Self::submit()`] or [`Self::submit_and_wait()`]. Completed requests can then be
+/// retrieved with [`Self::completions()`].
+///
+/// Dropping an `IoUring` does NOT wait for requests that are still in flight. The kernel cancels
+/// them asynchronously, and it may keep accessing their buffers after the drop has returned. See
+/// [`Self::push()`] for what this means for the memory that requests refer to.
+///
+/// ```
+/// # use slibc::{IoUring, IoUringSqe};
+/// # let mut ring = match IoUring::new(8) {
+/// #     Ok(ring) => ring,
+/// #     Err(e) if e == slibc::Errno::ENOSYS || e == slibc::Errno::EPERM => return,
+/// #     Err(e) => panic!("{}", e),
+/// # };
+/// unsafe {
+///     ring.push(&IoUringSqe::nop().user_data(1)).unwrap();
+/// }
+/// ring.submit_and_wait(1).unwrap();
+///
+/// let cqe = ring.completions().next().unwrap();
+/// assert_eq!(cqe.user_data(), 1);
+/// assert_eq!(cqe.result().unwrap(), 0);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+pub struct IoUring {
+    fd: FileDesc,
+    params: IoUringParams,
+    sq_ring: RingMap,
+    // None if the kernel supports IORING_FEAT_SINGLE_MMAP
+    cq_ring: Option<RingMap>,
+    sqes: RingMap,
+}
+
+impl IoUring {
+    /// Create a new io_uring instance with at least `entries` submission queue entries and
+    /// default parameters.
+    #[inline]
+    pub fn new(entries: u32) -> Result<Self> {
+        Self::with_params(entries, IoUringParams::default())
+    }
+
+    /// Create a new io_uring instance with at least `entries` submission queue entries and the
+    /// given parameters.
+    pub fn with_params(entries: u32, mut params: IoUringParams) -> Result<Self> {
+        let fd = io_uring_setup(entries, &mut params)?;
+        let p = &params.0;
+
+        let sq_len = p.sq_off.array as usize + p.sq_entries as usize * 4;
+        let cq_len = p.cq_off.cqes as usize
+            + p.cq_entries as usize * core::mem::size_of::<sys::io_uring_cqe>();
+
+        let (sq_ring, cq_ring) = if params.features().contains(IoUringFeatures::SINGLE_MMAP) {
+            let ring = RingMap::new(fd.fd(), sq_len.max(cq_len), sys::IORING_OFF_SQ_RING)?;
+            (ring, None)
+        } else {
+            let sq_ring = RingMap::new(fd.fd(), sq_len, sys::IORING_OFF_SQ_RING)?;
+            let cq_ring = RingMap::new(fd.fd(), cq_len, sys::IORING_OFF_CQ_RING)?;
+            (sq_ring, Some(cq_ring))
+        };
+
+        let sqes = RingMap::new(
+            fd.fd(),
+            p.sq_entries as usize * core::mem::size_of::<sys::io_uring_sqe>(),
+            sys::IORING_OFF_SQES,
+        )?;
+
+        // Map each slot of the submission queue to the submission queue entry at the same index,
+        // so that entries can be submitted in order without touching the array again
+        let array = sq_ring.at::<u32>(p.sq_off.array);
+        for i in 0..p.sq_entries {
+            unsafe {
+                array.add(i as usize).write(i);
+            }
+        }
+
+        Ok(Self {
+            fd,
+            params,
+            sq_ring,
+            cq_ring,
+            sqes,
+        })
+    }
+
+    /// Get the parameters that were returned by [`io_uring_setup()`] when this instance was
+    /// created.
+    #[inline]
+    pub fn params(&self) -> &IoUringParams {
+        &self.params
+    }
+
+    #[inline]
+    fn cq_ring(&self) -> &RingMap {
+        self.cq_ring.as_ref().unwrap_or(&self.sq_ring)
+    }
+
+    /// Get the number of entries that are in the submission queue and have not yet been
+    /// consumed by the kernel.
+    #[inline]
+    pub fn sq_len(&self) -> u32 {
+        let off = &self.params.0.sq_off;
+        let head = self.sq_ring.atomic(off.head).load(Ordering::Acquire);
+        let tail = self.sq_ring.atomic(off.tail).load(Ordering::Relaxed);
+        tail.wrapping_sub(head)
+    }
+
+    /// Add an entry to the submission queue.
+    ///
+    /// The entry is not submitted to the kernel until [`Self::submit()`] or
+    /// [`Self::submit_and_wait()`] is called (unless [`IoUringSetupFlags::SQPOLL`] was
+    /// specified). This fails with `EBUSY` if the submission queue is full.
+    ///
+    /// # Safety
+    ///
+    /// Any memory referenced by the entry (buffers, `iovec`s, addresses, timeouts, etc.) must
+    /// remain valid (and, for buffers that the kernel writes to, must not be accessed) until the
+    /// request has completed.
+    ///
+    /// Since dropping the ring does not wait for outstanding requests, the caller must retrieve
+    /// the completions of all submitted requests (with [`Self::completions()`]) before dropping
+    /// it. Otherwise, the memory must outlive the ring's teardown (for example, by leaking it).
+    pub unsafe fn push(&mut self, sqe: &IoUringSqe) -> Result<()> {
+        let off = &self.params.0.sq_off;
+        let head = self.sq_ring.atomic(off.head).load(Ordering::Acquire);
+        let tail = self.sq_ring.atomic(off.tail).load(Ordering::Relaxed);
+
+        if tail.wrapping_sub(head) >= self.params.0.sq_entries {
+            return Err(Error::from_code(libc::EBUSY));
+        }
+
+        let mask = *self.sq_ring.at::<u32>(off.ring_mask);
+        let slot = self
+            .sqes
+            .at::<sys::io_uring_sqe>(0)
+            .add((tail & mask) as usize);
+        slot.write(sqe.0);
+
+        self.sq_ring
+            .atomic(off.tail)
+            .store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    fn enter(&self, min_complete: u32, mut flags: IoUringEnterFlags) -> Result<u32> {
+        let mut to_submit = self.sq_len();
+
+        if self.params.flags().contains(IoUringSetupFlags::SQPOLL) {
+            // The polling thread submits entries itself; only wake it up if necessary. The fence
+            // orders the tail store in push() before the flags load, so that we can't miss the
+            // polling thread going to sleep after it last checked the tail.
+            fence(Ordering::SeqCst);
+            let sq_flags = self
+                .sq_ring
+                .atomic(self.params.0.sq_off.flags)
+                .load(Ordering::Relaxed);
+            if sq_flags & sys::IORING_SQ_NEED_WAKEUP != 0 {
+                flags |= IoUringEnterFlags::SQ_WAKEUP;
+            } else if !flags.contains(IoUringEnterFlags::GETEVENTS) {
+                return Ok(to_submit);
+            }
+            to_submit = 0;
+        }
+
+        // SAFETY: push() requires that all memory referenced by the entries remains valid
+        unsafe { io_uring_enter(self.fd.fd(), to_submit, min_complete, flags) }
+    }
+
+    /// Submit all pending entries in the submission queue to the kernel.
+    ///
+    /// On success, the number of submitted entries is returned.
+    #[inline]
+    pub fn submit(&mut self) -> Result<u32> {
+        self.enter(0, IoUringEnterFlags::empty())
+    }
+
+    /// Submit all pending entries in the submission queue to the kernel, and then wait until at
+    /// least `want` completions are available.
+    #[inline]
+    pub fn submit_and_wait(&mut self, want: u32) -> Result<u32> {
+        self.enter(want, IoUringEnterFlags::GETEVENTS)
+    }
+
+    /// Get an iterator over the entries that are currently available in the completion queue.
+    ///
+    /// Each entry is removed from the queue as it is yielded.
+    #[inline]
+    pub fn completions(&mut self) -> IoUringCompletions<'_> {
+        IoUringCompletions { ring: self }
+    }
+
+    /// Register the given file descriptors with this instance, so they can be referred to by
+    /// index with [`IoUringSqeFlags::FIXED_FILE`].
+    ///
+    /// Entries of -1 are treated as empty slots.
+    #[inline]
+    pub fn register_files(&self, fds: &[RawFd]) -> Result<()> {
+        unsafe {
+            io_uring_register(
+                self.fd.fd(),
+                IoUringRegisterOp::REGISTER_FILES,
+                fds.as_ptr() as *const _,
+                fds.len().try_into().unwrap_or(u32::MAX),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Unregister all files registered with [`Self::register_files()`].
+    #[inline]
+    pub fn unregister_files(&self) -> Result<()> {
+        unsafe {
+            io_uring_register(
+                self.fd.fd(),
+                IoUringRegisterOp::UNREGISTER_FILES,
+                core::ptr::null(),
+                0,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Register an eventfd that will be signaled when completions are posted.
+    #[inline]
+    pub fn register_eventfd(&self, eventfd: RawFd) -> Result<()> {
+        unsafe {
+            io_uring_register(
+                self.fd.fd(),
+                IoUringRegisterOp::REGISTER_EVENTFD,
+                &eventfd as *const RawFd as *const _,
+                1,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Unregister the eventfd registered with [`Self::register_eventfd()`].
+    #[inline]
+    pub fn unregister_eventfd(&self) -> Result<()> {
+        unsafe {
+            io_uring_register(
+                self.fd.fd(),
+                IoUringRegisterOp::UNREGISTER_EVENTFD,
+                core::ptr::null(),
+                0,
+            )?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn fd(&self) -> RawFd {
+        self.fd.fd()
+    }
+
+    /// Consume this `IoUring`, unmapping its rings and returning the underlying file
+    /// descriptor.
+    #[inline]
+    pub fn into_fd(self) -> RawFd {
+        let Self { fd, .. } = self;
+        fd.into_fd()
+    }
+}
+
+impl fmt::Debug for IoUring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IoUring")
+            .field("fd", &self.fd)
+            .field("params", &self.params)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<IoUring> for FileDesc {
+    #[inline]
+    fn from(r: IoUring) -> Self {
+        let IoUring { fd, .. } = r;
+        fd
+    }
+}
+
+impl AsRef<BorrowedFd> for IoUring {
+    #[inline]
+    fn as_ref(&self) -> &BorrowedFd {
+        self.fd.as_ref()
+    }
+}
+
+#[cfg(feature = "std")]
+impl AsRawFd for IoUring {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+#[cfg(feature = "std")]
+impl IntoRawFd for IoUring {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.into_fd()
+    }
+}
+
+/// An iterator over the available entries in the completion queue of an [`IoUring`].
+///
+/// This can be created with [`IoUring::completions()`].
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+pub struct IoUringCompletions<'a> {
+    ring: &'a mut IoUring,
+}
+
+impl Iterator for IoUringCompletions<'_> {
+    type Item = IoUringCqe;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let off = &self.ring.params.0.cq_off;
+        let cq_ring = self.ring.cq_ring();
+
+        let head = cq_ring.atomic(off.head).load(Ordering::Relaxed);
+        let tail = cq_ring.atomic(off.tail).load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+
+        let cqe = unsafe {
+            let mask = *cq_ring.at::<u32>(off.ring_mask);
+            cq_ring
+                .at::<sys::io_uring_cqe>(off.cqes)
+                .add((head & mask) as usize)
+                .read()
+        };
+
+        cq_ring
+            .atomic(off.head)
+            .store(head.wrapping_add(1), Ordering::Release);
+        Some(IoUringCqe(cqe))
+    }
+}
+
+impl fmt::Debug for IoUringCompletions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IoUringCompletions").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_ring(entries: u32) -> Option<IoUring> {
+        match IoUring::new(entries) {
+            Ok(ring) => Some(ring),
+            Err(e) if e == Errno::ENOSYS || e == Errno::EPERM => None,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test]
+    fn test_nop() {
+        let mut ring = match new_ring(4) {
+            Some(ring) => ring,
+            None => return,
+        };
+        assert!(ring.as_ref().get_cloexec().unwrap());
+        assert_eq!(ring.params().sq_entries(), 4);
+        assert!(ring.params().cq_entries() >= 4);
+
+        for i in 0..4 {
+            unsafe {
+                ring.push(&IoUringSqe::nop().user_data(i)).unwrap();
+            }
+        }
+        assert_eq!(
+            unsafe { ring.push(&IoUringSqe::nop()) }.unwrap_err(),
+            Errno::EBUSY
+        );
+        assert_eq!(ring.sq_len(), 4);
+
+        assert_eq!(ring.submit_and_wait(4).unwrap(), 4);
+        assert_eq!(ring.sq_len(), 0);
+
+        let mut completions = ring.completions();
+        for i in 0..4 {
+            let cqe = completions.next().unwrap();
+            assert_eq!(cqe.user_data(), i);
+            assert_eq!(cqe.result().unwrap(), 0);
+        }
+        assert!(completions.next().is_none());
+
+        // Submitting nothing works
+        assert_eq!(ring.submit().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_pipe_poll_timeout() {
+        let mut ring = match new_ring(8) {
+            Some(ring) => ring,
+            None => return,
+        };
+
+        let (r, w) = crate::pipe_cloexec().unwrap();
+        let ts = KernelTimeSpec::from(Duration::from_millis(10));
+        let mut buf = [0; 5];
+
+        unsafe {
+            ring.push(&IoUringSqe::poll_add(r.fd(), PollEvents::IN).user_data(1))
+                .unwrap();
+            ring.push(&IoUringSqe::timeout(&ts, 0, IoUringTimeoutFlags::empty()).user_data(2))
+                .unwrap();
+        }
+        ring.submit_and_wait(1).unwrap();
+
+        // The timeout expires first
+        let cqe = ring.completions().next().unwrap();
+        assert_eq!(cqe.user_data(), 2);
+        assert_eq!(cqe.result().unwrap_err(), Errno::ETIME);
+
+        unsafe {
+            ring.push(&IoUringSqe::write(w.fd(), b"hello", None).user_data(3))
+                .unwrap();
+        }
+        ring.submit_and_wait(2).unwrap();
+
+        let mut completions = ring.completions();
+        let mut cqes = [completions.next().unwrap(), completions.next().unwrap()];
+        assert!(completions.next().is_none());
+        cqes.sort_by_key(|cqe| cqe.user_data());
+        assert_eq!(cqes[0].user_data(), 1);
+        assert_eq!(
+            PollEvents::from_bits_truncate(cqes[0].result().unwrap() as _),
+            PollEvents::IN
+        );
+        assert_eq!(cqes[1].user_data(), 3);
+        assert_eq!(cqes[1].result().unwrap(), 5);
+
+        unsafe {
+            ring.push(&IoUringSqe::read(r.fd(), &mut buf, None))
+                .unwrap();
+        }
+        ring.submit_and_wait(1).unwrap();
+        assert_eq!(ring.completions().next().unwrap().result().unwrap(), 5);
+        assert_eq!(&buf, b"hello");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_rw() {
+        let mut ring = match new_ring(8) {
+            Some(ring) => ring,
+            None => return,
+        };
+
+        let f = tempfile::tempfile().unwrap();
+        let fd = f.as_raw_fd();
+
+        let iov = [IoVec::new(b"abc"), IoVec::new(b"def")];
+        unsafe {
+            ring.push(
+                &IoUringSqe::writev(fd, &iov, Some(0), RWFlags::empty())
+                    .flags(IoUringSqeFlags::IO_LINK)
+                    .user_data(1),
+            )
+            .unwrap();
+            ring.push(
+                &IoUringSqe::fsync(fd, IoUringFsyncFlags::DATASYNC)
+                    .flags(IoUringSqeFlags::IO_LINK)
+                    .user_data(2),
+            )
+            .unwrap();
+            ring.push(&IoUringSqe::write(fd, b"gh", Some(6)).user_data(3))
+                .unwrap();
+        }
+        assert_eq!(ring.submit_and_wait(3).unwrap(), 3);
+
+        let results = ring
+            .completions()
+            .map(|cqe| (cqe.user_data(), cqe.result().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(results, [(1, 6), (2, 0), (3, 2)]);
+
+        let mut buf1 = [0; 3];
+        let mut buf2 = [0; 5];
+        let mut iov = [IoVecMut::new(&mut buf1), IoVecMut::new(&mut buf2)];
+        unsafe {
+            ring.push(&IoUringSqe::readv(fd, &mut iov, Some(0), RWFlags::empty()))
+                .unwrap();
+        }
+        ring.submit_and_wait(1).unwrap();
+        assert_eq!(ring.completions().next().unwrap().result().unwrap(), 8);
+        assert_eq!(&buf1, b"abc");
+        assert_eq!(&buf2, b"defgh");
+    }
+
+    #[test]
+    fn test_socket() {
+        let mut ring = match new_ring(8) {
+            Some(ring) => ring,
+            None => return,
+        };
+
+        let listener =
+            crate::Socket::new_cloexec(crate::SockDomain::INET, crate::SockType::STREAM, None)
+                .unwrap();
+        listener
+            .bind(&SockAddr::Inet4(crate::Inet4SockAddr::new(
+                crate::Inet4Addr::LOCALHOST,
+                0,
+            )))
+            .unwrap();
+        listener.listen(1).unwrap();
+        let addr = listener.getsockname().unwrap();
+
+        let client =
+            crate::Socket::new_cloexec(crate::SockDomain::INET, crate::SockType::STREAM, None)
+                .unwrap();
+
+        unsafe {
+            ring.push(&IoUringSqe::accept(&listener, SockFlag::CLOEXEC).user_data(1))
+                .unwrap();
+            ring.push(&IoUringSqe::connect(&client, &addr).user_data(2))
+                .unwrap();
+        }
+        ring.submit_and_wait(2).unwrap();
+
+        let mut server = None;
+        for cqe in ring.completions() {
+            match cqe.user_data() {
+                1 => server = Some(unsafe { listener.from_fd(cqe.result().unwrap() as RawFd) }),
+                2 => assert_eq!(cqe.result().unwrap(), 0),
+                _ => unreachable!(),
+            }
+        }
+        let server = server.unwrap();
+        assert!(server.as_ref().get_cloexec().unwrap());
+
+        let mut buf = [0; 5];
+        unsafe {
+            ring.push(
+                &IoUringSqe::send(&client, b"hello", MsgFlag::empty())
+                    .flags(IoUringSqeFlags::IO_LINK)
+                    .user_data(1),
+            )
+            .unwrap();
+            ring.push(&IoUringSqe::recv(&server, &mut buf, MsgFlag::WAITALL).user_data(2))
+                .unwrap();
+        }
+        ring.submit_and_wait(2).unwrap();
+
+        let mut completions = ring.completions();
+        for i in 1..=2 {
+            let cqe = completions.next().unwrap();
+            assert_eq!(cqe.user_data(), i);
+            assert_eq!(cqe.result().unwrap(), 5);
+        }
+        assert!(completions.next().is_none());
+        assert_eq!(&buf, b"hello");
+    }
+}
@@ -0,0 +1,281 @@
+use crate::internal_prelude::*;
+
+bitflags::bitflags! {
+    /// Filesystem access rights that can be restricted by a Landlock ruleset.
+    ///
+    /// Each right was introduced in a particular version of the Landlock ABI; see
+    /// [`Self::for_abi()`] and [`landlock_abi_version()`]. See `landlock(7)` for more information.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct AccessFs: u64 {
+        const EXECUTE = sys::LANDLOCK_ACCESS_FS_EXECUTE;
+        const WRITE_FILE = sys::LANDLOCK_ACCESS_FS_WRITE_FILE;
+        const READ_FILE = sys::LANDLOCK_ACCESS_FS_READ_FILE;
+        const READ_DIR = sys::LANDLOCK_ACCESS_FS_READ_DIR;
+        const REMOVE_DIR = sys::LANDLOCK_ACCESS_FS_REMOVE_DIR;
+        const REMOVE_FILE = sys::LANDLOCK_ACCESS_FS_REMOVE_FILE;
+        const MAKE_CHAR = sys::LANDLOCK_ACCESS_FS_MAKE_CHAR;
+        const MAKE_DIR = sys::LANDLOCK_ACCESS_FS_MAKE_DIR;
+        const MAKE_REG = sys::LANDLOCK_ACCESS_FS_MAKE_REG;
+        const MAKE_SOCK = sys::LANDLOCK_ACCESS_FS_MAKE_SOCK;
+        const MAKE_FIFO = sys::LANDLOCK_ACCESS_FS_MAKE_FIFO;
+        const MAKE_BLOCK = sys::LANDLOCK_ACCESS_FS_MAKE_BLOCK;
+        const MAKE_SYM = sys::LANDLOCK_ACCESS_FS_MAKE_SYM;
+        /// (ABI version 2+)
+        const REFER = sys::LANDLOCK_ACCESS_FS_REFER;
+        /// (ABI version 3+)
+        const TRUNCATE = sys::LANDLOCK_ACCESS_FS_TRUNCATE;
+        /// (ABI version 5+)
+        const IOCTL_DEV = sys::LANDLOCK_ACCESS_FS_IOCTL_DEV;
+    }
+}
+
+impl AccessFs {
+    /// Get the set of access rights supported by the given Landlock ABI version.
+    ///
+    /// The kernel rejects rulesets that refer to unsupported access rights, so this is useful to
+    /// mask out access rights that the running kernel does not know about. For example:
+    ///
+    /// ```no_run
+    /// # use slibc::{landlock_abi_version, AccessFs};
+    /// let handled = AccessFs::all() & AccessFs::for_abi(landlock_abi_version().unwrap());
+    /// ```
+    pub fn for_abi(abi: u32) -> Self {
+        let mut access = Self::empty();
+        if abi >= 1 {
+            access |= Self::EXECUTE
+                | Self::WRITE_FILE
+                | Self::READ_FILE
+                | Self::READ_DIR
+                | Self::REMOVE_DIR
+                | Self::REMOVE_FILE
+                | Self::MAKE_CHAR
+                | Self::MAKE_DIR
+                | Self::MAKE_REG
+                | Self::MAKE_SOCK
+                | Self::MAKE_FIFO
+                | Self::MAKE_BLOCK
+                | Self::MAKE_SYM;
+        }
+        if abi >= 2 {
+            access |= Self::REFER;
+        }
+        if abi >= 3 {
+            access |= Self::TRUNCATE;
+        }
+        if abi >= 5 {
+            access |= Self::IOCTL_DEV;
+        }
+        access
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`landlock_restrict_self()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    pub struct LandlockRestrictFlags: u32 {
+    }
+}
+
+/// Get the highest Landlock ABI version supported by the running kernel.
+///
+/// This fails with `ENOSYS` if the kernel was built without Landlock support, or with
+/// `EOPNOTSUPP` if Landlock is supported but was disabled at boot time.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn landlock_abi_version() -> Result<u32> {
+    let res = Error::unpack(unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            core::ptr::null::<sys::landlock_ruleset_attr>(),
+            0,
+            sys::LANDLOCK_CREATE_RULESET_VERSION,
+        ) as i32
+    })?;
+    Ok(res as u32)
+}
+
+/// Create a new Landlock ruleset that restricts the given filesystem access rights.
+///
+/// Access rights that are not in `handled_access_fs` are not restricted by the ruleset.
+/// Rules can be added with [`landlock_add_rule()`], and the ruleset can then be enforced with
+/// [`landlock_restrict_self()`]. The returned file descriptor will have its close-on-exec flag
+/// set.
+///
+/// This fails with `EINVAL` if `handled_access_fs` contains access rights that are not supported
+/// by the running kernel (see [`AccessFs::for_abi()`]). It fails with `ENOSYS` or `EOPNOTSUPP` if
+/// Landlock is unavailable (see [`landlock_abi_version()`]).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+pub fn landlock_create_ruleset(handled_access_fs: AccessFs) -> Result<FileDesc> {
+    let attr = sys::landlock_ruleset_attr {
+        handled_access_fs: handled_access_fs.bits(),
+        ..Default::default()
+    };
+
+    unsafe {
+        Error::unpack_fdesc(libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            &attr,
+            core::mem::size_of::<sys::landlock_ruleset_attr>(),
+            0,
+        ) as i32)
+    }
+}
+
+/// A rule that can be added to a Landlock ruleset with [`landlock_add_rule()`].
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Copy, Clone, Debug)]
+pub enum LandlockRule<'a> {
+    /// Allow the given access rights for the file hierarchy beneath `parent`.
+    ///
+    /// `parent` should be a file descriptor opened with `O_PATH` (see [`OFlag::O_PATH`]) that
+    /// refers to a directory (or a file, in which case only access rights that apply to files
+    /// may be specified).
+    PathBeneath {
+        parent: &'a BorrowedFd,
+        allowed_access: AccessFs,
+    },
+}
+
+/// Add a rule to the Landlock ruleset referred to by `ruleset`.
+///
+/// The allowed access rights must be a subset of the access rights handled by the ruleset.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+pub fn landlock_add_rule<F: AsRef<BorrowedFd>>(ruleset: &F, rule: &LandlockRule) -> Result<()> {
+    match *rule {
+        LandlockRule::PathBeneath {
+            parent,
+            allowed_access,
+        } => {
+            let attr = sys::landlock_path_beneath_attr {
+                allowed_access: allowed_access.bits(),
+                parent_fd: parent.fd(),
+            };
+
+            Error::unpack_nz(unsafe {
+                libc::syscall(
+                    libc::SYS_landlock_add_rule,
+                    ruleset.as_ref().fd(),
+                    sys::LANDLOCK_RULE_PATH_BENEATH,
+                    &attr,
+                    0,
+                ) as i32
+            })
+        }
+    }
+}
+
+/// Enforce the Landlock ruleset referred to by `ruleset` on the calling thread.
+///
+/// Unless the calling thread has the `CAP_SYS_ADMIN` capability in its user namespace, the
+/// "no new privileges" attribute must be set first (see [`crate::prctl_set_no_new_privs()`]).
+/// The restrictions are inherited by child processes and cannot be removed.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn landlock_restrict_self<F: AsRef<BorrowedFd>>(
+    ruleset: &F,
+    flags: LandlockRestrictFlags,
+) -> Result<()> {
+    Error::unpack_nz(unsafe {
+        libc::syscall(
+            libc::SYS_landlock_restrict_self,
+            ruleset.as_ref().fd(),
+            flags.bits(),
+        ) as i32
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_fs_for_abi() {
+        assert_eq!(AccessFs::for_abi(0), AccessFs::empty());
+        assert!(!AccessFs::for_abi(1).contains(AccessFs::REFER));
+        assert!(AccessFs::for_abi(1).contains(AccessFs::EXECUTE | AccessFs::MAKE_SYM));
+        assert!(AccessFs::for_abi(2).contains(AccessFs::REFER));
+        assert!(!AccessFs::for_abi(2).contains(AccessFs::TRUNCATE));
+        assert_eq!(AccessFs::for_abi(3), AccessFs::for_abi(4));
+        assert_eq!(AccessFs::for_abi(5), AccessFs::all());
+        assert_eq!(AccessFs::for_abi(u32::MAX), AccessFs::all());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_landlock_restrict() {
+        let abi = match landlock_abi_version() {
+            Ok(abi) => abi,
+            Err(e) if e == Errno::ENOSYS || e == Errno::EOPNOTSUPP => return,
+            Err(e) => panic!("{}", e),
+        };
+        assert!(abi >= 1);
+
+        let allowed_dir = tempfile::tempdir().unwrap();
+        let other_dir = tempfile::tempdir().unwrap();
+        std::fs::write(allowed_dir.path().join("file"), b"").unwrap();
+        std::fs::write(other_dir.path().join("file"), b"").unwrap();
+
+        let allowed_file = allowed_dir.path().join("file");
+        let other_file = other_dir.path().join("file");
+
+        let parent = crate::open(
+            allowed_dir.path(),
+            OFlag::O_PATH | OFlag::O_DIRECTORY | OFlag::O_CLOEXEC,
+            0,
+        )
+        .unwrap();
+
+        // Unsupported access rights are rejected
+        if abi < 5 {
+            assert_eq!(
+                landlock_create_ruleset(AccessFs::IOCTL_DEV).unwrap_err(),
+                Errno::EINVAL
+            );
+        }
+
+        let ruleset = landlock_create_ruleset(AccessFs::READ_FILE | AccessFs::WRITE_FILE).unwrap();
+        assert!(ruleset.get_cloexec().unwrap());
+
+        // Can't allow rights that the ruleset doesn't handle
+        assert_eq!(
+            landlock_add_rule(
+                &ruleset,
+                &LandlockRule::PathBeneath {
+                    parent: parent.as_ref(),
+                    allowed_access: AccessFs::EXECUTE,
+                },
+            )
+            .unwrap_err(),
+            Errno::EINVAL
+        );
+
+        landlock_add_rule(
+            &ruleset,
+            &LandlockRule::PathBeneath {
+                parent: parent.as_ref(),
+                allowed_access: AccessFs::READ_FILE,
+            },
+        )
+        .unwrap();
+
+        crate::util::run_in_child(|| {
+            if crate::prctl_set_no_new_privs().is_err()
+                || landlock_restrict_self(&ruleset, LandlockRestrictFlags::empty()).is_err()
+            {
+                return false;
+            }
+
+            let check = |path: &std::path::Path, flags: OFlag| {
+                crate::open(path, flags | OFlag::O_CLOEXEC, 0)
+                    .err()
+                    .map_or(0, |e| e.code())
+            };
+
+            check(&allowed_file, OFlag::O_RDONLY) == 0
+                && check(&allowed_file, OFlag::O_WRONLY) == libc::EACCES
+                && check(&other_file, OFlag::O_RDONLY) == libc::EACCES
+                && check(&other_file, OFlag::O_WRONLY) == libc::EACCES
+        });
+    }
+}
@@ -149,6 +149,7 @@ mod stdio;
 mod stdlib;
 mod string;
 mod swap;
+mod termios;
 mod time;
 mod uio;
 mod unistd;
@@ -175,6 +176,7 @@ pub use stdio::*;
 pub use stdlib::*;
 pub use string::*;
 pub use swap::*;
+pub use termios::*;
 pub use time::*;
 pub use uio::*;
 pub use unistd::*;
@@ -195,28 +197,46 @@ cfg_if::cfg_if! {
 
 cfg_if::cfg_if! {
     if #[cfg(linuxlike)] {
+        mod capability;
         mod epoll;
         mod eventfd;
         mod inotify;
         mod ioprio;
+        mod mount;
+        mod prctl;
         mod signalfd;
         mod sysinfo;
         mod timerfd;
+        mod xattr;
+        pub use capability::*;
         pub use epoll::*;
         pub use eventfd::*;
         pub use inotify::*;
         pub use ioprio::*;
+        pub use mount::*;
+        pub use prctl::*;
         pub use signalfd::*;
         pub use sysinfo::*;
         pub use timerfd::*;
+        pub use xattr::*;
     }
 }
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "linux")] {
+        mod fanotify;
+        mod io_uring;
+        mod landlock;
         mod pidfd;
+        mod ptrace;
+        mod seccomp;
         mod statx;
+        pub use fanotify::*;
+        pub use io_uring::*;
+        pub use landlock::*;
         pub use pidfd::*;
+        pub use ptrace::*;
+        pub use seccomp::*;
         pub use statx::*;
     }
 }
@@ -225,6 +245,18 @@ cfg_if::cfg_if! {
     if #[cfg(not(target_os = "android"))] {
         mod spawn;
         pub use spawn::*;
+
+        #[cfg(feature = "std")]
+        mod command;
+        #[cfg(feature = "std")]
+        pub use command::*;
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(any(linuxlike, target_os = "freebsd", target_os = "netbsd"))] {
+        mod timer;
+        pub use timer::*;
     }
 }
 
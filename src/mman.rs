@@ -251,11 +251,461 @@ pub unsafe fn madvise_raw(addr: *mut u8, length: usize, advice: MemAdvice) -> Re
     Error::unpack_nz(libc::madvise(addr as *mut _, length, advice as _))
 }
 
+bitflags::bitflags! {
+    /// Memory protection flags for [`mmap_raw()`], [`mprotect_raw()`], and [`MemoryMap`].
+    ///
+    /// An empty set of flags is equivalent to `PROT_NONE` (the memory cannot be accessed at all).
+    pub struct ProtFlags: libc::c_int {
+        /// Pages may be read.
+        const READ = libc::PROT_READ;
+        /// Pages may be written.
+        const WRITE = libc::PROT_WRITE;
+        /// Pages may be executed.
+        const EXEC = libc::PROT_EXEC;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`mmap_raw()`] and [`MemoryMap`].
+    ///
+    /// Exactly one of [`Self::SHARED`] or [`Self::PRIVATE`] must be specified.
+    pub struct MapFlags: libc::c_int {
+        /// Share this mapping; updates are visible to other processes mapping the same region, and
+        /// (for file-backed mappings) are carried through to the underlying file.
+        const SHARED = libc::MAP_SHARED;
+        /// Create a private copy-on-write mapping.
+        const PRIVATE = libc::MAP_PRIVATE;
+        /// Place the mapping at exactly the specified address, replacing any existing mappings.
+        ///
+        /// This cannot be passed to the safe [`MemoryMap`] constructors.
+        const FIXED = libc::MAP_FIXED;
+        /// The mapping is not backed by any file; its contents are initialized to zero.
+        ///
+        /// This is added automatically by [`MemoryMap::new_anon()`].
+        const ANON = libc::MAP_ANON;
+
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const NORESERVE = libc::MAP_NORESERVE;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const POPULATE = libc::MAP_POPULATE;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const LOCKED = libc::MAP_LOCKED;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const HUGETLB = libc::MAP_HUGETLB;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const GROWSDOWN = libc::MAP_GROWSDOWN;
+
+        #[cfg_attr(
+            docsrs,
+            doc(cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "dragonfly",
+                target_os = "openbsd",
+                target_os = "netbsd",
+            )))
+        )]
+        #[cfg(any(linuxlike, freebsdlike, netbsdlike))]
+        const STACK = libc::MAP_STACK;
+
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "freebsd", target_os = "dragonfly"))))]
+        #[cfg(freebsdlike)]
+        const NOCORE = libc::MAP_NOCORE;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "freebsd", target_os = "dragonfly"))))]
+        #[cfg(freebsdlike)]
+        const NOSYNC = libc::MAP_NOSYNC;
+    }
+}
+
+#[cfg(target_os = "linux")]
+bitflags::bitflags! {
+    /// Flags for [`mremap_raw()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    pub struct MremapFlags: libc::c_int {
+        /// Allow the kernel to move the mapping to a new address if it cannot be resized in place.
+        const MAYMOVE = libc::MREMAP_MAYMOVE;
+        /// Move the mapping to the address given in `new_addr`. Must be specified with
+        /// [`Self::MAYMOVE`].
+        const FIXED = libc::MREMAP_FIXED;
+    }
+}
+
+/// Create a new mapping in the virtual address space of the calling process.
+///
+/// This is a thin wrapper around `mmap(2)`; see that page for more information. Most users should
+/// use [`MemoryMap`] instead, which unmaps the region automatically when it is dropped.
+///
+/// # Safety
+///
+/// If `flags` includes [`MapFlags::FIXED`], any existing mappings in the given range are
+/// silently replaced. In all cases, the caller is responsible for eventually unmapping the
+/// returned region with [`munmap_raw()`].
+#[inline]
+pub unsafe fn mmap_raw(
+    addr: *mut u8,
+    len: usize,
+    prot: ProtFlags,
+    flags: MapFlags,
+    fd: RawFd,
+    offset: u64,
+) -> Result<*mut u8> {
+    let ptr = libc::mmap(
+        addr as *mut _,
+        len,
+        prot.bits(),
+        flags.bits(),
+        fd,
+        offset as _,
+    );
+
+    if ptr == libc::MAP_FAILED {
+        Err(Error::last())
+    } else {
+        Ok(ptr as *mut u8)
+    }
+}
+
+/// Remove the mappings for the specified region of memory.
+///
+/// See `munmap(2)` for more information.
+///
+/// # Safety
+///
+/// `addr` and `len` must refer to a region that was mapped with [`mmap_raw()`], and nothing may
+/// reference the region after it is unmapped.
+#[inline]
+pub unsafe fn munmap_raw(addr: *mut u8, len: usize) -> Result<()> {
+    Error::unpack_nz(libc::munmap(addr as *mut _, len))
+}
+
+/// Change the memory protection of the pages containing any part of the specified region.
+///
+/// See `mprotect(2)` for more information.
+///
+/// # Safety
+///
+/// `addr` and `len` must refer to a valid region of memory, and removing access to memory that is
+/// still referenced elsewhere (e.g. by a `&[u8]`) will crash the program when it is accessed.
+#[inline]
+pub unsafe fn mprotect_raw(addr: *mut u8, len: usize, prot: ProtFlags) -> Result<()> {
+    Error::unpack_nz(libc::mprotect(addr as *mut _, len, prot.bits()))
+}
+
+/// Expand or shrink an existing memory mapping, possibly moving it.
+///
+/// `new_addr` is only used if `flags` includes [`MremapFlags::FIXED`]. On success, the (possibly
+/// new) address of the mapping is returned.
+///
+/// See `mremap(2)` for more information.
+///
+/// # Safety
+///
+/// `addr` and `old_len` must refer to a region mapped with [`mmap_raw()`]. If the mapping is
+/// moved, nothing may reference the old address afterward.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[inline]
+pub unsafe fn mremap_raw(
+    addr: *mut u8,
+    old_len: usize,
+    new_len: usize,
+    flags: MremapFlags,
+    new_addr: *mut u8,
+) -> Result<*mut u8> {
+    let ptr = libc::mremap(
+        addr as *mut _,
+        old_len,
+        new_len,
+        flags.bits(),
+        new_addr as *mut libc::c_void,
+    );
+
+    if ptr == libc::MAP_FAILED {
+        Err(Error::last())
+    } else {
+        Ok(ptr as *mut u8)
+    }
+}
+
+/// Synchronize a file with a memory map.
+///
+/// See `msync(2)` for more information.
+///
+/// # Safety
+///
+/// `addr` and `len` must refer to a valid region of memory.
+#[inline]
+pub unsafe fn msync_raw(addr: *mut u8, len: usize, flags: MsyncFlags) -> Result<()> {
+    Error::unpack_nz(libc::msync(addr as *mut _, len, flags.bits()))
+}
+
+/// An owned memory mapping, created with `mmap(2)`.
+///
+/// The region is unmapped when this struct is dropped. It dereferences to a `[u8]` slice covering
+/// the entire mapping.
+///
+/// All of the safe constructors require the mapping to be readable (i.e. `prot` must include
+/// [`ProtFlags::READ`]); `DerefMut` will panic if the mapping is not also writable.
+pub struct MemoryMap {
+    ptr: core::ptr::NonNull<u8>,
+    len: usize,
+    prot: ProtFlags,
+}
+
+impl MemoryMap {
+    #[inline]
+    fn new_imp(
+        len: usize,
+        prot: ProtFlags,
+        flags: MapFlags,
+        fd: RawFd,
+        offset: u64,
+    ) -> Result<Self> {
+        if !prot.contains(ProtFlags::READ) || flags.contains(MapFlags::FIXED) {
+            return Err(Error::from_code(libc::EINVAL));
+        }
+
+        let ptr = unsafe { mmap_raw(core::ptr::null_mut(), len, prot, flags, fd, offset)? };
+
+        Ok(Self {
+            ptr: Error::unpack_ptr(ptr)?,
+            len,
+            prot,
+        })
+    }
+
+    /// Create a new anonymous mapping of `len` bytes.
+    ///
+    /// [`MapFlags::ANON`] is added to `flags` automatically; one of [`MapFlags::PRIVATE`] or
+    /// [`MapFlags::SHARED`] must still be specified. The contents of the mapping are initialized
+    /// to zero.
+    ///
+    /// ```
+    /// # use slibc::{MemoryMap, MapFlags, ProtFlags};
+    /// let mut map = MemoryMap::new_anon(
+    ///     4096,
+    ///     ProtFlags::READ | ProtFlags::WRITE,
+    ///     MapFlags::PRIVATE,
+    /// ).unwrap();
+    /// assert!(map.iter().all(|&b| b == 0));
+    /// map[0] = 1;
+    /// assert_eq!(map[0], 1);
+    /// ```
+    #[inline]
+    pub fn new_anon(len: usize, prot: ProtFlags, flags: MapFlags) -> Result<Self> {
+        Self::new_imp(len, prot, flags | MapFlags::ANON, -1, 0)
+    }
+
+    /// Map `len` bytes of the file referred to by `fd`, starting at `offset` (which must be a
+    /// multiple of the page size).
+    ///
+    /// The file descriptor may be closed after the mapping is created.
+    ///
+    /// # Safety
+    ///
+    /// If the file is modified (or truncated) by this or another process while the mapping is in
+    /// use, the contents of the slice this struct dereferences to will change underneath it (or
+    /// accessing it will raise `SIGBUS`). The caller must ensure that this cannot happen.
+    #[inline]
+    pub unsafe fn new_file(
+        fd: &BorrowedFd,
+        offset: u64,
+        len: usize,
+        prot: ProtFlags,
+        flags: MapFlags,
+    ) -> Result<Self> {
+        Self::new_imp(len, prot, flags, fd.fd(), offset)
+    }
+
+    /// Get the memory protection flags that currently apply to this mapping.
+    #[inline]
+    pub fn prot(&self) -> ProtFlags {
+        self.prot
+    }
+
+    /// Get a pointer to the start of this mapping.
+    ///
+    /// The pointer is only valid until the mapping is dropped or moved by [`Self::mremap()`].
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    /// Get a mutable pointer to the start of this mapping.
+    ///
+    /// The pointer is only valid until the mapping is dropped or moved by [`Self::mremap()`].
+    /// Writing through it is only allowed if the mapping is writable (see [`Self::prot()`]).
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr.as_ptr()
+    }
+
+    /// Change the memory protection of this mapping.
+    ///
+    /// # Safety
+    ///
+    /// If `prot` does not include [`ProtFlags::READ`], any attempt to access the contents of the
+    /// mapping (including through `Deref`) will crash the program until access is restored.
+    #[inline]
+    pub unsafe fn mprotect(&mut self, prot: ProtFlags) -> Result<()> {
+        mprotect_raw(self.ptr.as_ptr(), self.len, prot)?;
+        self.prot = prot;
+        Ok(())
+    }
+
+    /// Resize this mapping to `new_len` bytes.
+    ///
+    /// If `may_move` is `true`, the kernel may move the mapping to a new address if it cannot be
+    /// expanded in place (see [`MremapFlags::MAYMOVE`]). If it is `false` and the mapping cannot
+    /// be expanded in place, this fails with `ENOMEM`.
+    ///
+    /// # Safety
+    ///
+    /// If this is a shared or file-backed mapping, the new pages may be backed by data that other
+    /// processes can modify, or by a region past the end of the file (accessing it will raise
+    /// `SIGBUS`). The caller must ensure that this cannot happen (see [`Self::new_file()`]). Any
+    /// pointers previously obtained from [`Self::as_ptr()`] or [`Self::as_mut_ptr()`] must not be
+    /// used after the mapping is moved.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[cfg(target_os = "linux")]
+    #[inline]
+    pub unsafe fn mremap(&mut self, new_len: usize, may_move: bool) -> Result<()> {
+        let flags = if may_move {
+            MremapFlags::MAYMOVE
+        } else {
+            MremapFlags::empty()
+        };
+
+        let ptr = mremap_raw(
+            self.ptr.as_ptr(),
+            self.len,
+            new_len,
+            flags,
+            core::ptr::null_mut(),
+        )?;
+
+        self.ptr = Error::unpack_ptr(ptr)?;
+        self.len = new_len;
+        Ok(())
+    }
+
+    /// Synchronize this mapping with the underlying file.
+    ///
+    /// See [`msync()`].
+    #[inline]
+    pub fn msync(&self, flags: MsyncFlags) -> Result<()> {
+        unsafe { msync_raw(self.ptr.as_ptr(), self.len, flags) }
+    }
+
+    /// See [`madvise()`].
+    ///
+    /// # Safety
+    ///
+    /// See [`madvise()`].
+    #[inline]
+    pub unsafe fn madvise(&mut self, advice: MemAdvice) -> Result<()> {
+        madvise_raw(self.ptr.as_ptr(), self.len, advice)
+    }
+
+    /// See [`posix_madvise()`].
+    #[cfg_attr(docsrs, doc(cfg(not(target_os = "android"))))]
+    #[cfg(not(target_os = "android"))]
+    #[inline]
+    pub fn posix_madvise(&self, advice: PosixMAdvice) -> Result<()> {
+        unsafe { posix_madvise_raw(self.ptr.as_ptr(), self.len, advice) }
+    }
+
+    /// Lock the pages of this mapping into RAM.
+    ///
+    /// See [`mlock()`].
+    #[inline]
+    pub fn mlock(&self) -> Result<()> {
+        mlock(self)
+    }
+
+    /// Lock the pages of this mapping into RAM.
+    ///
+    /// See [`mlock2()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[cfg(target_os = "linux")]
+    #[inline]
+    pub fn mlock2(&self, flags: Mlock2Flags) -> Result<()> {
+        mlock2(self, flags)
+    }
+
+    /// Unlock the pages of this mapping from RAM.
+    ///
+    /// See [`munlock()`].
+    #[inline]
+    pub fn munlock(&self) -> Result<()> {
+        munlock(self)
+    }
+}
+
+impl Drop for MemoryMap {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr.as_ptr() as *mut _, self.len);
+        }
+    }
+}
+
+impl core::ops::Deref for MemoryMap {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl core::ops::DerefMut for MemoryMap {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        assert!(
+            self.prot.contains(ProtFlags::WRITE),
+            "memory map is not writable"
+        );
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl AsRef<[u8]> for MemoryMap {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl core::fmt::Debug for MemoryMap {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("MemoryMap")
+            .field("ptr", &self.ptr)
+            .field("len", &self.len)
+            .field("prot", &self.prot)
+            .finish()
+    }
+}
+
+// SAFETY: MemoryMap owns its region exclusively, just like a Box<[u8]>
+unsafe impl Send for MemoryMap {}
+unsafe impl Sync for MemoryMap {}
+
 #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
 #[cfg(linuxlike)]
 bitflags::bitflags! {
     pub struct MemfdFlags: libc::c_uint {
         const CLOEXEC = libc::MFD_CLOEXEC;
+        /// Allow seals to be added to the file with [`fcntl_add_seals()`].
+        ///
+        /// [`fcntl_add_seals()`]: ./fn.fcntl_add_seals.html
         const ALLOW_SEALING = libc::MFD_ALLOW_SEALING;
         const HUGETLB = libc::MFD_HUGETLB;
     }
@@ -277,6 +727,71 @@ mod tests {
     #[allow(unused_imports)]
     use super::*;
 
+    #[test]
+    fn test_memory_map_anon() {
+        let mut map =
+            MemoryMap::new_anon(4096, ProtFlags::READ | ProtFlags::WRITE, MapFlags::PRIVATE)
+                .unwrap();
+        assert_eq!(map.len(), 4096);
+        assert!(map.iter().all(|&b| b == 0));
+
+        map[..3].copy_from_slice(b"abc");
+        assert_eq!(&map[..3], b"abc");
+
+        map.msync(MsyncFlags::SYNC).unwrap();
+
+        unsafe {
+            map.mprotect(ProtFlags::READ).unwrap();
+        }
+        assert_eq!(map.prot(), ProtFlags::READ);
+        assert_eq!(&map[..3], b"abc");
+
+        #[cfg(target_os = "linux")]
+        {
+            unsafe {
+                map.mremap(8192, true).unwrap();
+            }
+            assert_eq!(map.len(), 8192);
+            assert_eq!(&map[..3], b"abc");
+        }
+
+        assert_eq!(
+            MemoryMap::new_anon(4096, ProtFlags::WRITE, MapFlags::PRIVATE).unwrap_err(),
+            Errno::EINVAL
+        );
+        assert_eq!(
+            MemoryMap::new_anon(4096, ProtFlags::READ, MapFlags::PRIVATE | MapFlags::FIXED)
+                .unwrap_err(),
+            Errno::EINVAL
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_memory_map_file() {
+        let file: FileDesc = tempfile::tempfile().unwrap().into();
+        file.write_all(b"abcdef").unwrap();
+
+        let mut map = unsafe {
+            MemoryMap::new_file(
+                &file,
+                0,
+                6,
+                ProtFlags::READ | ProtFlags::WRITE,
+                MapFlags::SHARED,
+            )
+        }
+        .unwrap();
+        assert_eq!(&map[..], b"abcdef");
+
+        map[0] = b'x';
+        map.msync(MsyncFlags::SYNC).unwrap();
+
+        let mut buf = [0; 6];
+        file.pread(&mut buf, 0).unwrap();
+        assert_eq!(&buf, b"xbcdef");
+    }
+
     #[cfg(all(linuxlike, feature = "alloc"))]
     #[test]
     fn test_memfd_create() {
@@ -0,0 +1,553 @@
+use crate::internal_prelude::*;
+
+bitflags::bitflags! {
+    /// Flags for [`mount()`].
+    ///
+    /// See `mount(2)` for more information.
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[derive(Default)]
+    pub struct MsFlags: libc::c_ulong {
+        /// Mount the filesystem read-only.
+        const RDONLY = libc::MS_RDONLY;
+        /// Ignore set-user-ID and set-group-ID bits (and file capabilities) on files in this
+        /// filesystem.
+        const NOSUID = libc::MS_NOSUID;
+        /// Do not allow access to device special files on this filesystem.
+        const NODEV = libc::MS_NODEV;
+        /// Do not allow programs to be executed from this filesystem.
+        const NOEXEC = libc::MS_NOEXEC;
+        /// Make writes to this filesystem synchronous.
+        const SYNCHRONOUS = libc::MS_SYNCHRONOUS;
+        /// Remount an existing mount, changing the flags and/or data.
+        const REMOUNT = libc::MS_REMOUNT;
+        /// Permit mandatory locking on files in this filesystem.
+        const MANDLOCK = libc::MS_MANDLOCK;
+        /// Make directory changes on this filesystem synchronous.
+        const DIRSYNC = libc::MS_DIRSYNC;
+        /// Do not follow symbolic links when resolving paths in this filesystem (Linux 5.10+).
+        const NOSYMFOLLOW = libc::MS_NOSYMFOLLOW;
+        /// Do not update access times on this filesystem.
+        const NOATIME = libc::MS_NOATIME;
+        /// Do not update access times for directories on this filesystem.
+        const NODIRATIME = libc::MS_NODIRATIME;
+        /// Create a bind mount of `source` at `target`.
+        const BIND = libc::MS_BIND;
+        /// Move the existing mount at `source` to `target`.
+        const MOVE = libc::MS_MOVE;
+        /// Used with [`Self::BIND`] or one of the propagation flags to apply the operation
+        /// recursively.
+        const REC = libc::MS_REC;
+        /// Suppress some kernel warning messages.
+        const SILENT = libc::MS_SILENT;
+        /// Make this mount unbindable.
+        const UNBINDABLE = libc::MS_UNBINDABLE;
+        /// Make this mount private.
+        const PRIVATE = libc::MS_PRIVATE;
+        /// Make this mount a slave mount.
+        const SLAVE = libc::MS_SLAVE;
+        /// Make this mount shared.
+        const SHARED = libc::MS_SHARED;
+        /// Only update access times if they are older than the modification/change times.
+        const RELATIME = libc::MS_RELATIME;
+        /// Always update access times.
+        const STRICTATIME = libc::MS_STRICTATIME;
+        /// Maintain access/modification/change times in memory, only writing them to disk when
+        /// necessary.
+        const LAZYTIME = libc::MS_LAZYTIME;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`umount2()`].
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[derive(Default)]
+    pub struct UmountFlags: libc::c_int {
+        /// Force an unmount even if the filesystem is busy (only supported by some filesystems).
+        const FORCE = libc::MNT_FORCE;
+        /// Perform a "lazy" unmount: detach the mount now, and clean up once it is no longer
+        /// busy.
+        const DETACH = libc::MNT_DETACH;
+        /// Mark the mount as expired. If it is not busy, a second `umount2()` call with this flag
+        /// will unmount it.
+        const EXPIRE = libc::MNT_EXPIRE;
+        /// Do not dereference `target` if it is a symbolic link.
+        const NOFOLLOW = libc::UMOUNT_NOFOLLOW;
+    }
+}
+
+/// Mount a filesystem.
+///
+/// `source` is usually the path to a device (or the name of a filesystem that is not backed by
+/// a device, such as `tmpfs`), and `fstype` is the type of the filesystem. These are ignored for
+/// some operations (such as [`MsFlags::REMOUNT`] or changing the propagation type). `data` is a
+/// filesystem-specific options string.
+///
+/// See `mount(2)` for more information.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+pub fn mount<P: AsPath>(
+    source: Option<&CStr>,
+    target: P,
+    fstype: Option<&CStr>,
+    flags: MsFlags,
+    data: Option<&CStr>,
+) -> Result<()> {
+    target.with_cstr(|target| {
+        Error::unpack_nz(unsafe {
+            libc::mount(
+                source.map_or_else(core::ptr::null, |s| s.as_ptr()),
+                target.as_ptr(),
+                fstype.map_or_else(core::ptr::null, |s| s.as_ptr()),
+                flags.bits(),
+                data.map_or_else(core::ptr::null, |s| s.as_ptr() as *const libc::c_void),
+            )
+        })
+    })
+}
+
+/// Unmount the filesystem mounted at `target`.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn umount<P: AsPath>(target: P) -> Result<()> {
+    target.with_cstr(|target| Error::unpack_nz(unsafe { libc::umount(target.as_ptr()) }))
+}
+
+/// Unmount the filesystem mounted at `target`, with the given `flags`.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn umount2<P: AsPath>(target: P, flags: UmountFlags) -> Result<()> {
+    target.with_cstr(|target| {
+        Error::unpack_nz(unsafe { libc::umount2(target.as_ptr(), flags.bits()) })
+    })
+}
+
+#[cfg(target_os = "linux")]
+bitflags::bitflags! {
+    /// Flags for [`fsopen()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct FsOpenFlags: libc::c_uint {
+        const CLOEXEC = sys::FSOPEN_CLOEXEC;
+    }
+}
+
+#[cfg(target_os = "linux")]
+bitflags::bitflags! {
+    /// Flags for [`fsmount()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct FsMountFlags: libc::c_uint {
+        const CLOEXEC = sys::FSMOUNT_CLOEXEC;
+    }
+}
+
+#[cfg(target_os = "linux")]
+bitflags::bitflags! {
+    /// Mount attributes for [`fsmount()`] and [`mount_setattr()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct MountAttr: u64 {
+        /// Make the mount read-only.
+        const RDONLY = sys::MOUNT_ATTR_RDONLY;
+        /// Ignore set-user-ID and set-group-ID bits (and file capabilities) on files.
+        const NOSUID = sys::MOUNT_ATTR_NOSUID;
+        /// Do not allow access to device special files.
+        const NODEV = sys::MOUNT_ATTR_NODEV;
+        /// Do not allow programs to be executed.
+        const NOEXEC = sys::MOUNT_ATTR_NOEXEC;
+        /// A mask of the bits used to specify the access time behavior.
+        ///
+        /// To change the access time behavior with [`mount_setattr()`], this must be included in
+        /// [`MountSetattrArgs::attr_clr`]. If none of [`Self::NOATIME`] or
+        /// [`Self::STRICTATIME`] is set, the "relatime" behavior is used.
+        const ATIME = sys::MOUNT_ATTR__ATIME;
+        /// Do not update access times.
+        const NOATIME = sys::MOUNT_ATTR_NOATIME;
+        /// Always update access times.
+        const STRICTATIME = sys::MOUNT_ATTR_STRICTATIME;
+        /// Do not update access times for directories.
+        const NODIRATIME = sys::MOUNT_ATTR_NODIRATIME;
+        /// Create an ID-mapped mount (Linux 5.12+; [`mount_setattr()`] only).
+        ///
+        /// This is set automatically if [`MountSetattrArgs::userns`] is not `None`.
+        const IDMAP = sys::MOUNT_ATTR_IDMAP;
+        /// Do not follow symbolic links (Linux 5.14+).
+        const NOSYMFOLLOW = sys::MOUNT_ATTR_NOSYMFOLLOW;
+    }
+}
+
+#[cfg(target_os = "linux")]
+bitflags::bitflags! {
+    /// Flags for [`move_mount()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct MoveMountFlags: libc::c_uint {
+        /// Follow symbolic links in `from_path`.
+        const F_SYMLINKS = sys::MOVE_MOUNT_F_SYMLINKS;
+        /// Trigger automounts in `from_path`.
+        const F_AUTOMOUNTS = sys::MOVE_MOUNT_F_AUTOMOUNTS;
+        /// If `from_path` is empty, operate on `from_dirfd` itself.
+        const F_EMPTY_PATH = sys::MOVE_MOUNT_F_EMPTY_PATH;
+        /// Follow symbolic links in `to_path`.
+        const T_SYMLINKS = sys::MOVE_MOUNT_T_SYMLINKS;
+        /// Trigger automounts in `to_path`.
+        const T_AUTOMOUNTS = sys::MOVE_MOUNT_T_AUTOMOUNTS;
+        /// If `to_path` is empty, operate on `to_dirfd` itself.
+        const T_EMPTY_PATH = sys::MOVE_MOUNT_T_EMPTY_PATH;
+    }
+}
+
+#[cfg(target_os = "linux")]
+bitflags::bitflags! {
+    /// Flags for [`open_tree()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct OpenTreeFlags: libc::c_uint {
+        /// Create a detached clone of the mount at the given path (instead of just opening it).
+        const CLONE = sys::OPEN_TREE_CLONE;
+        /// Set the close-on-exec flag on the returned file descriptor.
+        const CLOEXEC = libc::O_CLOEXEC as _;
+        /// With [`Self::CLONE`], clone the entire mount tree (like `MS_BIND | MS_REC`).
+        const RECURSIVE = sys::AT_RECURSIVE as _;
+        /// If `path` is empty, operate on `dirfd` itself.
+        const EMPTY_PATH = libc::AT_EMPTY_PATH as _;
+        /// Do not follow a symbolic link in the last component of `path`.
+        const SYMLINK_NOFOLLOW = libc::AT_SYMLINK_NOFOLLOW as _;
+        /// Do not trigger automounts in the last component of `path`.
+        const NO_AUTOMOUNT = libc::AT_NO_AUTOMOUNT as _;
+    }
+}
+
+/// Create a new filesystem context for the filesystem type `fsname`.
+///
+/// The returned file descriptor can be configured with [`fsconfig_set_flag()`] and
+/// [`fsconfig_set_string()`], then the filesystem can be created with [`fsconfig_create()`] and
+/// mounted with [`fsmount()`].
+///
+/// This system call was added in Linux 5.2.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[inline]
+pub fn fsopen(fsname: &CStr, flags: FsOpenFlags) -> Result<FileDesc> {
+    unsafe {
+        Error::unpack_fdesc(libc::syscall(libc::SYS_fsopen, fsname.as_ptr(), flags.bits()) as i32)
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[inline]
+fn fsconfig(fd: RawFd, cmd: libc::c_uint, key: Option<&CStr>, value: Option<&CStr>) -> Result<()> {
+    Error::unpack_nz(unsafe {
+        libc::syscall(
+            libc::SYS_fsconfig,
+            fd,
+            cmd,
+            key.map_or_else(core::ptr::null, |s| s.as_ptr()),
+            value.map_or_else(core::ptr::null, |s| s.as_ptr()),
+            0,
+        ) as i32
+    })
+}
+
+/// Set the boolean parameter `key` on the filesystem context `fd` (opened with [`fsopen()`]).
+///
+/// This is equivalent to `fsconfig(fd, FSCONFIG_SET_FLAG, key, NULL, 0)`.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[inline]
+pub fn fsconfig_set_flag(fd: RawFd, key: &CStr) -> Result<()> {
+    fsconfig(fd, sys::FSCONFIG_SET_FLAG, Some(key), None)
+}
+
+/// Set the parameter `key` to the string `value` on the filesystem context `fd` (opened with
+/// [`fsopen()`]).
+///
+/// This is equivalent to `fsconfig(fd, FSCONFIG_SET_STRING, key, value, 0)`.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[inline]
+pub fn fsconfig_set_string(fd: RawFd, key: &CStr, value: &CStr) -> Result<()> {
+    fsconfig(fd, sys::FSCONFIG_SET_STRING, Some(key), Some(value))
+}
+
+/// Create the filesystem configured in the filesystem context `fd` (opened with [`fsopen()`]).
+///
+/// This is equivalent to `fsconfig(fd, FSCONFIG_CMD_CREATE, NULL, NULL, 0)`.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[inline]
+pub fn fsconfig_create(fd: RawFd) -> Result<()> {
+    fsconfig(fd, sys::FSCONFIG_CMD_CREATE, None, None)
+}
+
+/// Apply the parameters set on the filesystem context `fd` to the existing filesystem.
+///
+/// This is equivalent to `fsconfig(fd, FSCONFIG_CMD_RECONFIGURE, NULL, NULL, 0)`.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[inline]
+pub fn fsconfig_reconfigure(fd: RawFd) -> Result<()> {
+    fsconfig(fd, sys::FSCONFIG_CMD_RECONFIGURE, None, None)
+}
+
+/// Create a detached mount object for the filesystem created in the filesystem context `fs_fd`.
+///
+/// The returned file descriptor refers to the root of the new mount; it can be attached to the
+/// filesystem tree with [`move_mount()`] (or used directly, e.g. with [`openat()`](crate::openat)).
+///
+/// This system call was added in Linux 5.2.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[inline]
+pub fn fsmount(fs_fd: RawFd, flags: FsMountFlags, attr: MountAttr) -> Result<FileDesc> {
+    unsafe {
+        Error::unpack_fdesc(libc::syscall(
+            libc::SYS_fsmount,
+            fs_fd,
+            flags.bits(),
+            attr.bits() as libc::c_uint,
+        ) as i32)
+    }
+}
+
+/// Move a mount from one location to another.
+///
+/// This can be used to attach a detached mount (created with [`fsmount()`] or
+/// [`open_tree()`]) to the filesystem tree by passing its file descriptor as `from_dirfd`, an
+/// empty `from_path`, and [`MoveMountFlags::F_EMPTY_PATH`].
+///
+/// This system call was added in Linux 5.2.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+pub fn move_mount<P: AsPath, Q: AsPath>(
+    from_dirfd: RawFd,
+    from_path: P,
+    to_dirfd: RawFd,
+    to_path: Q,
+    flags: MoveMountFlags,
+) -> Result<()> {
+    from_path.with_cstr(|from_path| {
+        to_path.with_cstr(|to_path| {
+            Error::unpack_nz(unsafe {
+                libc::syscall(
+                    libc::SYS_move_mount,
+                    from_dirfd,
+                    from_path.as_ptr(),
+                    to_dirfd,
+                    to_path.as_ptr(),
+                    flags.bits(),
+                ) as i32
+            })
+        })
+    })
+}
+
+/// Open the mount at the given path, or (with [`OpenTreeFlags::CLONE`]) create a detached clone
+/// of it.
+///
+/// This system call was added in Linux 5.2.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+pub fn open_tree<P: AsPath>(dirfd: RawFd, path: P, flags: OpenTreeFlags) -> Result<FileDesc> {
+    path.with_cstr(|path| unsafe {
+        Error::unpack_fdesc(
+            libc::syscall(libc::SYS_open_tree, dirfd, path.as_ptr(), flags.bits()) as i32,
+        )
+    })
+}
+
+/// Arguments for [`mount_setattr()`].
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default)]
+pub struct MountSetattrArgs<'a> {
+    /// Attributes to set on the mount.
+    pub attr_set: MountAttr,
+    /// Attributes to clear on the mount.
+    pub attr_clr: MountAttr,
+    /// The new propagation type of the mount.
+    ///
+    /// This may be empty (leave the propagation type unchanged), or one of
+    /// [`MsFlags::PRIVATE`], [`MsFlags::SHARED`], [`MsFlags::SLAVE`], or
+    /// [`MsFlags::UNBINDABLE`].
+    pub propagation: MsFlags,
+    /// A file descriptor referring to a user namespace; if specified, an ID-mapped mount is
+    /// created using the UID/GID mappings of this user namespace.
+    pub userns: Option<&'a BorrowedFd>,
+}
+
+/// Change the attributes of the mount at the given path.
+///
+/// `flags` may contain [`AtFlag::AT_RECURSIVE`](crate::AtFlag::AT_RECURSIVE) to apply the
+/// changes to the entire mount tree, as well as [`AtFlag::AT_EMPTY_PATH`](crate::AtFlag),
+/// `AT_SYMLINK_NOFOLLOW`, and `AT_NO_AUTOMOUNT`.
+///
+/// This system call was added in Linux 5.12.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+pub fn mount_setattr<P: AsPath>(
+    dirfd: RawFd,
+    path: P,
+    flags: crate::AtFlag,
+    args: &MountSetattrArgs,
+) -> Result<()> {
+    let mut attr = sys::mount_attr {
+        attr_set: args.attr_set.bits(),
+        attr_clr: args.attr_clr.bits(),
+        propagation: args.propagation.bits() as _,
+        userns_fd: 0,
+    };
+
+    if let Some(userns) = args.userns {
+        attr.attr_set |= sys::MOUNT_ATTR_IDMAP;
+        attr.userns_fd = userns.fd() as u64;
+    }
+
+    path.with_cstr(|path| {
+        Error::unpack_nz(unsafe {
+            libc::syscall(
+                libc::SYS_mount_setattr,
+                dirfd,
+                path.as_ptr(),
+                flags.bits() as libc::c_uint,
+                &mut attr as *mut sys::mount_attr,
+                core::mem::size_of::<sys::mount_attr>(),
+            ) as i32
+        })
+    })
+}
+
+#[cfg(all(test, target_os = "linux", feature = "std"))]
+mod tests {
+    use super::*;
+
+    use crate::{CloneFlags, StatfsFlags, AT_FDCWD};
+
+    fn with_mount_ns<F: FnOnce(&CStr) -> Option<()>>(f: F) {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = CString::new(dir.path().as_os_str().as_bytes()).unwrap();
+
+        crate::util::run_in_child(|| {
+            match crate::unshare(CloneFlags::NEWUSER | CloneFlags::NEWNS) {
+                // User namespaces may be disabled
+                Err(e) => matches!(e.code(), libc::EPERM | libc::EINVAL | libc::ENOSPC),
+                Ok(()) => f(&dir).is_some(),
+            }
+        });
+    }
+
+    fn mount_flags(dir: &CStr) -> Option<StatfsFlags> {
+        crate::statfs(dir).map(|s| s.flags()).ok()
+    }
+
+    #[test]
+    fn test_mount_umount() {
+        with_mount_ns(|dir| {
+            mount(
+                Some(CStr::from_bytes_with_nul(b"tmpfs\0").unwrap()),
+                dir,
+                Some(CStr::from_bytes_with_nul(b"tmpfs\0").unwrap()),
+                MsFlags::NOSUID | MsFlags::NODEV,
+                Some(CStr::from_bytes_with_nul(b"size=64k\0").unwrap()),
+            )
+            .ok()?;
+            if mount_flags(dir)? & (StatfsFlags::RDONLY | StatfsFlags::NOSUID | StatfsFlags::NODEV)
+                != StatfsFlags::NOSUID | StatfsFlags::NODEV
+            {
+                return None;
+            }
+
+            mount(
+                None,
+                dir,
+                None,
+                MsFlags::REMOUNT | MsFlags::RDONLY | MsFlags::NOSUID | MsFlags::NODEV,
+                None,
+            )
+            .ok()?;
+            if !mount_flags(dir)?.contains(StatfsFlags::RDONLY) {
+                return None;
+            }
+
+            umount2(dir, UmountFlags::NOFOLLOW).ok()?;
+            // Nothing is mounted there anymore
+            if !matches!(umount(dir), Err(e) if e.code() == libc::EINVAL) {
+                return None;
+            }
+
+            Some(())
+        });
+    }
+
+    #[test]
+    fn test_fsmount() {
+        with_mount_ns(|dir| {
+            let fs_fd = match fsopen(
+                CStr::from_bytes_with_nul(b"tmpfs\0").unwrap(),
+                FsOpenFlags::CLOEXEC,
+            ) {
+                Ok(fd) => fd,
+                // Not supported or blocked
+                Err(e) if matches!(e.code(), libc::ENOSYS | libc::EPERM) => return Some(()),
+                Err(_) => return None,
+            };
+
+            fsconfig_set_string(
+                fs_fd.fd(),
+                CStr::from_bytes_with_nul(b"size\0").unwrap(),
+                CStr::from_bytes_with_nul(b"64k\0").unwrap(),
+            )
+            .ok()?;
+            if !matches!(
+                fsconfig_set_flag(fs_fd.fd(), CStr::from_bytes_with_nul(b"bogus\0").unwrap()),
+                Err(e) if e.code() == libc::EINVAL
+            ) {
+                return None;
+            }
+            fsconfig_create(fs_fd.fd()).ok()?;
+
+            let mnt_fd = fsmount(
+                fs_fd.fd(),
+                FsMountFlags::CLOEXEC,
+                MountAttr::RDONLY | MountAttr::NOSUID | MountAttr::NODEV,
+            )
+            .ok()?;
+            move_mount(
+                mnt_fd.fd(),
+                CStr::from_bytes_with_nul(b"\0").unwrap(),
+                AT_FDCWD,
+                dir,
+                MoveMountFlags::F_EMPTY_PATH,
+            )
+            .ok()?;
+            if !mount_flags(dir)?.contains(StatfsFlags::RDONLY | StatfsFlags::NOSUID) {
+                return None;
+            }
+
+            match mount_setattr(
+                AT_FDCWD,
+                dir,
+                crate::AtFlag::empty(),
+                &MountSetattrArgs {
+                    attr_clr: MountAttr::RDONLY,
+                    ..Default::default()
+                },
+            ) {
+                Ok(()) => {
+                    if mount_flags(dir)?.contains(StatfsFlags::RDONLY) {
+                        return None;
+                    }
+                }
+                Err(e) if e.code() == libc::ENOSYS => (),
+                Err(_) => return None,
+            }
+
+            let tree_fd =
+                open_tree(AT_FDCWD, dir, OpenTreeFlags::CLONE | OpenTreeFlags::CLOEXEC).ok()?;
+            drop(tree_fd);
+
+            umount2(dir, UmountFlags::DETACH).ok()?;
+
+            Some(())
+        });
+    }
+}
@@ -1,6 +1,6 @@
 use crate::internal_prelude::*;
 
-use crate::Signal;
+use crate::{IoVec, IoVecMut, RemoteIoVec, SigInfo, Signal, WaitFlags, WaitidId};
 
 #[cfg(feature = "std")]
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
@@ -54,17 +54,20 @@ pub fn pidfd_getfd(pidfd: RawFd, targetfd: RawFd, flags: PidFdGetfdFlags) -> Res
 
 /// Send a signal to a process specified by a PID file descriptor.
 ///
-/// This calls the `pidfd_send_signal()` system call with a null `info` argument (issues with the
-/// Rust definitions of `siginfo_t` currently make it difficult to initialize a `siginfo_t`
-/// structure properly).
+/// If `info` is not `None`, it is passed to the receiver (see [`SigInfo::new()`]); its `si_signo`
+/// must match `sig`. Otherwise, the receiver sees the same information as if the signal had been
+/// sent with [`kill()`](crate::kill).
 ///
-/// The `pidfd_send_signal()` system call was added in Linux 5.1. See `pidfd_send_signal(2)` for
-/// more information.
+/// If `sig` is `None`, no signal is sent, but error checking is still performed. This can be used
+/// to check whether the process is still alive.
+///
+/// This system call was added in Linux 5.1. See `pidfd_send_signal(2)` for more information.
 #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
 #[inline]
-pub fn pidfd_send_signal_simple<S: Into<Option<Signal>>>(
+pub fn pidfd_send_signal<S: Into<Option<Signal>>>(
     pidfd: RawFd,
     sig: S,
+    info: Option<&SigInfo>,
     flags: PidFdSignalFlags,
 ) -> Result<()> {
     Error::unpack_nz(unsafe {
@@ -72,12 +75,26 @@ pub fn pidfd_send_signal_simple<S: Into<Option<Signal>>>(
             libc::SYS_pidfd_send_signal,
             pidfd,
             sig.into().map_or(0, |s| s.as_i32()),
-            core::ptr::null_mut::<libc::siginfo_t>(),
+            info.map_or_else(core::ptr::null, |info| &info.0 as *const libc::siginfo_t),
             flags.bits(),
         ) as i32
     })
 }
 
+/// Send a signal to a process specified by a PID file descriptor.
+///
+/// This is equivalent to `pidfd_send_signal(pidfd, sig, None, flags)`; see
+/// [`pidfd_send_signal()`].
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn pidfd_send_signal_simple<S: Into<Option<Signal>>>(
+    pidfd: RawFd,
+    sig: S,
+    flags: PidFdSignalFlags,
+) -> Result<()> {
+    pidfd_send_signal(pidfd, sig, None, flags)
+}
+
 /// A wrapper around a PID file descriptor opened with `pidfd_open(2)`.
 #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
 #[derive(Debug)]
@@ -106,6 +123,132 @@ impl PidFd {
         pidfd_send_signal_simple(self.fd(), sig, flags)
     }
 
+    /// See [`pidfd_send_signal()`].
+    #[inline]
+    pub fn send_signal<S: Into<Option<Signal>>>(
+        &self,
+        sig: S,
+        info: Option<&SigInfo>,
+        flags: PidFdSignalFlags,
+    ) -> Result<()> {
+        pidfd_send_signal(self.fd(), sig, info, flags)
+    }
+
+    /// Wait for the process referred to by this PID file descriptor to change state.
+    ///
+    /// This is equivalent to `waitid(WaitidId::PidFd(self.fd()), options)`; see
+    /// [`waitid()`](crate::waitid). The process must be a child of the calling process, and
+    /// `options` should usually include [`WaitFlags::WEXITED`].
+    ///
+    /// If this PID file descriptor was opened with [`PidFdOpenFlags::NONBLOCK`] and the process
+    /// has not yet exited, this fails with `EAGAIN` (on Linux 5.10+) instead of blocking. In that
+    /// case, the file descriptor can be monitored for readability (e.g. with
+    /// [`Epoll`](crate::Epoll)) to wait for the process to exit.
+    #[inline]
+    pub fn wait(&self, options: WaitFlags) -> Result<Option<SigInfo>> {
+        crate::waitid(WaitidId::PidFd(self.fd()), options)
+    }
+
+    /// Read from the memory of the process referred to by this PID file descriptor.
+    ///
+    /// See [`process_vm_readv()`](crate::process_vm_readv). The PID of the process is looked up
+    /// in `/proc/self/fdinfo`, and the process is checked to still be alive after the read, so
+    /// the data is guaranteed not to come from another process that reused the PID. This fails
+    /// with `ESRCH` if the process has exited.
+    #[inline]
+    pub fn process_vm_readv(
+        &self,
+        local_iov: &mut [IoVecMut],
+        remote_iov: &[RemoteIoVec],
+    ) -> Result<usize> {
+        let n = crate::process_vm_readv(self.pid()?, local_iov, remote_iov)?;
+        self.send_signal_simple(None, PidFdSignalFlags::empty())?;
+        Ok(n)
+    }
+
+    /// Write to the memory of the process referred to by this PID file descriptor.
+    ///
+    /// See [`process_vm_writev()`](crate::process_vm_writev). The PID of the process is looked
+    /// up in `/proc/self/fdinfo`, and this fails with `ESRCH` if the process has exited.
+    ///
+    /// Note that if the process exits and its PID is reused by another process between the
+    /// lookup and the write, the data may be written to the other process. The process is
+    /// checked to still be alive after the write, so an `ESRCH` error will be returned in that
+    /// case, but the write cannot be undone. Use this method only on processes that cannot be
+    /// reaped during the call (for example, stopped children of the calling process).
+    ///
+    /// # Safety
+    ///
+    /// If this refers to the current process, this can overwrite arbitrary memory in it.
+    #[inline]
+    pub unsafe fn process_vm_writev(
+        &self,
+        local_iov: &[IoVec],
+        remote_iov: &[RemoteIoVec],
+    ) -> Result<usize> {
+        let n = crate::process_vm_writev(self.pid()?, local_iov, remote_iov)?;
+        self.send_signal_simple(None, PidFdSignalFlags::empty())?;
+        Ok(n)
+    }
+
+    /// Get the PID of the process referred to by this PID file descriptor.
+    ///
+    /// This is parsed from the `Pid:` field in `/proc/self/fdinfo/<fd>`. It fails with `ESRCH` if
+    /// the process has exited (on newer kernels) or is not visible in the PID namespace of the
+    /// calling process.
+    ///
+    /// Note that if the process exits after this returns, the PID may be reused by another
+    /// process.
+    pub fn pid(&self) -> Result<libc::pid_t> {
+        const PREFIX: &[u8] = b"/proc/self/fdinfo/";
+
+        let mut path = [0u8; PREFIX.len() + 11];
+        path[..PREFIX.len()].copy_from_slice(PREFIX);
+
+        let mut digits = [0; 10];
+        let mut i = digits.len();
+        let mut fd = self.fd() as u32;
+        loop {
+            i -= 1;
+            digits[i] = b'0' + (fd % 10) as u8;
+            fd /= 10;
+            if fd == 0 {
+                break;
+            }
+        }
+        path[PREFIX.len()..PREFIX.len() + digits.len() - i].copy_from_slice(&digits[i..]);
+
+        let path = util::cstr_from_buf(&path).unwrap();
+        let file = crate::open(path, OFlag::O_RDONLY | OFlag::O_CLOEXEC, 0)?;
+
+        let mut buf = [0; 1024];
+        let mut len = 0;
+        while len < buf.len() {
+            match file.read(&mut buf[len..])? {
+                0 => break,
+                n => len += n,
+            }
+        }
+
+        let pid = buf[..len]
+            .split(|&c| c == b'\n')
+            .find(|line| line.starts_with(b"Pid:"))
+            .and_then(|line| {
+                let val = &line[4..];
+                let start = val.iter().position(|c| !c.is_ascii_whitespace())?;
+                libc::pid_t::parse_bytes(&val[start..], true).ok()
+            })
+            .ok_or_else(|| Error::from_code(libc::EINVAL))?;
+
+        // -1 indicates that the process has exited, and 0 indicates that it is not visible in
+        // our PID namespace
+        if pid > 0 {
+            Ok(pid)
+        } else {
+            Err(Error::from_code(libc::ESRCH))
+        }
+    }
+
     #[inline]
     pub fn fd(&self) -> RawFd {
         self.0.fd()
@@ -200,4 +343,123 @@ mod tests {
         let pfd = PidFd::open(crate::getpid(), PidFdOpenFlags::NONBLOCK).unwrap();
         assert!(pfd.as_ref().get_nonblocking().unwrap());
     }
+
+    #[test]
+    fn test_pidfd_pid() {
+        if pidfd_open(libc::pid_t::MAX, PidFdOpenFlags::empty()).unwrap_err() != Errno::ESRCH {
+            return;
+        }
+
+        let pfd = PidFd::open(crate::getpid(), PidFdOpenFlags::empty()).unwrap();
+        assert_eq!(pfd.pid().unwrap(), crate::getpid());
+
+        let pid = match unsafe { crate::fork() }.unwrap() {
+            None => loop {
+                crate::pause();
+            },
+            Some(pid) => pid,
+        };
+
+        let pfd = PidFd::open(pid, PidFdOpenFlags::empty()).unwrap();
+        assert_eq!(pfd.pid().unwrap(), pid);
+
+        crate::kill(pid, Signal::SIGKILL).unwrap();
+        assert_eq!(
+            crate::waitpid(pid, WaitFlags::empty()).unwrap(),
+            Some((pid, crate::WaitStatus::Signaled(libc::SIGKILL, false)))
+        );
+
+        // Once the process is gone, its PID can't be looked up (so it can't be reused by mistake)
+        assert_eq!(pfd.pid().unwrap_err(), Errno::ESRCH);
+        let mut buf = [0; 3];
+        assert_eq!(
+            pfd.process_vm_readv(&mut [IoVecMut::new(&mut buf)], &[RemoteIoVec::new(0, 3)])
+                .unwrap_err(),
+            Errno::ESRCH
+        );
+    }
+
+    #[test]
+    fn test_pidfd_send_signal_wait() {
+        if pidfd_open(libc::pid_t::MAX, PidFdOpenFlags::empty()).unwrap_err() != Errno::ESRCH {
+            return;
+        }
+
+        let parent = crate::getpid();
+        let set = crate::sigset!(Signal::SIGUSR1);
+        let old_mask = crate::pthread_sigmask(crate::SigmaskHow::BLOCK, Some(&set)).unwrap();
+
+        let pid = match unsafe { crate::fork() }.unwrap() {
+            None => {
+                let ok = match crate::sigwaitinfo(&set) {
+                    Ok((sig, info)) => {
+                        sig == Signal::SIGUSR1
+                            && info.si_code() == libc::SI_QUEUE
+                            && info.si_pid() == parent
+                    }
+                    Err(_) => false,
+                };
+                unsafe { crate::_exit(if ok { 7 } else { 1 }) }
+            }
+            Some(pid) => pid,
+        };
+        crate::pthread_sigmask(crate::SigmaskHow::SETMASK, Some(&old_mask)).unwrap();
+
+        let pfd = PidFd::open(pid, PidFdOpenFlags::empty()).unwrap();
+        assert_eq!(pfd.pid().unwrap(), pid);
+
+        // si_signo must match the signal
+        let info = SigInfo::new(Signal::SIGUSR2, libc::SI_QUEUE);
+        assert_eq!(
+            pfd.send_signal(Signal::SIGUSR1, Some(&info), PidFdSignalFlags::empty())
+                .unwrap_err(),
+            Errno::EINVAL
+        );
+
+        let info = SigInfo::new(Signal::SIGUSR1, libc::SI_QUEUE);
+        pfd.send_signal(Signal::SIGUSR1, Some(&info), PidFdSignalFlags::empty())
+            .unwrap();
+
+        let info = pfd.wait(WaitFlags::WEXITED).unwrap().unwrap();
+        assert_eq!(info.si_pid(), pid);
+        assert_eq!(info.si_code(), libc::CLD_EXITED);
+        assert_eq!(info.si_status(), 7);
+
+        assert_eq!(pfd.wait(WaitFlags::WEXITED).unwrap_err(), Errno::ECHILD);
+    }
+
+    #[test]
+    fn test_pidfd_wait_nonblock_epoll() {
+        if pidfd_open(libc::pid_t::MAX, PidFdOpenFlags::NONBLOCK).unwrap_err() != Errno::ESRCH {
+            return;
+        }
+
+        let (r, w) = crate::pipe().unwrap();
+        let pid = match unsafe { crate::fork() }.unwrap() {
+            None => {
+                drop(w);
+                let _ = r.read(&mut [0]);
+                unsafe { crate::_exit(3) }
+            }
+            Some(pid) => pid,
+        };
+        drop(r);
+
+        let pfd = PidFd::open(pid, PidFdOpenFlags::NONBLOCK).unwrap();
+        let epoll = crate::Epoll::new(crate::EpollFlags::CLOEXEC).unwrap();
+        epoll.add(pfd.fd(), crate::EpollEvents::IN, 0).unwrap();
+
+        let mut events = [crate::EpollEvent::new(crate::EpollEvents::empty(), 0)];
+        assert_eq!(epoll.wait(&mut events, 0).unwrap(), 0);
+
+        assert_eq!(pfd.wait(WaitFlags::WEXITED).unwrap_err(), Errno::EAGAIN);
+
+        drop(w);
+        assert_eq!(epoll.wait(&mut events, -1).unwrap(), 1);
+        assert!(events[0].events().contains(crate::EpollEvents::IN));
+
+        let info = pfd.wait(WaitFlags::WEXITED).unwrap().unwrap();
+        assert_eq!(info.si_pid(), pid);
+        assert_eq!(info.si_status(), 3);
+    }
 }
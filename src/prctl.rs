@@ -0,0 +1,365 @@
+use crate::internal_prelude::*;
+
+use crate::{Capability, Signal};
+
+#[inline]
+unsafe fn prctl(
+    option: libc::c_int,
+    arg2: libc::c_ulong,
+    arg3: libc::c_ulong,
+    arg4: libc::c_ulong,
+    arg5: libc::c_ulong,
+) -> Result<libc::c_int> {
+    Error::unpack(libc::prctl(option, arg2, arg3, arg4, arg5))
+}
+
+#[inline]
+fn prctl_get_bool(option: libc::c_int) -> Result<bool> {
+    unsafe { prctl(option, 0, 0, 0, 0) }.map(|res| res != 0)
+}
+
+#[inline]
+fn prctl_set_bool(option: libc::c_int, val: bool) -> Result<()> {
+    unsafe {
+        prctl(option, val as _, 0, 0, 0)?;
+    }
+    Ok(())
+}
+
+/// Set the name of the calling thread.
+///
+/// Names longer than 15 bytes are silently truncated. See also [`prctl_get_name()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_set_name(name: &CStr) -> Result<()> {
+    unsafe {
+        prctl(libc::PR_SET_NAME, name.as_ptr() as _, 0, 0, 0)?;
+    }
+    Ok(())
+}
+
+/// Get the name of the calling thread.
+///
+/// The name is stored in `buf` (which is large enough to hold any thread name, including the
+/// terminating nul byte), and a `CStr` referencing the relevant portion of `buf` is returned.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_get_name(buf: &mut [u8; 16]) -> Result<&CStr> {
+    unsafe {
+        prctl(libc::PR_GET_NAME, buf.as_mut_ptr() as _, 0, 0, 0)?;
+    }
+    Ok(util::cstr_from_buf(buf).unwrap())
+}
+
+/// Set the signal that the calling process will receive when its parent terminates.
+///
+/// `None` clears the parent-death signal. Note that the "parent" in this case is actually the
+/// thread that created this process; if that thread exits, the signal will be sent even if other
+/// threads in the parent process are still running.
+///
+/// The parent-death signal is cleared in the child of a `fork()`, and (under some
+/// circumstances) when executing a set-user-ID or set-group-ID program.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_set_pdeathsig<S: Into<Option<Signal>>>(sig: S) -> Result<()> {
+    unsafe {
+        prctl(
+            libc::PR_SET_PDEATHSIG,
+            sig.into().map_or(0, |s| s.as_i32()) as _,
+            0,
+            0,
+            0,
+        )?;
+    }
+    Ok(())
+}
+
+/// Get the parent-death signal of the calling process.
+///
+/// See [`prctl_set_pdeathsig()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_get_pdeathsig() -> Result<Option<Signal>> {
+    let mut sig: libc::c_int = 0;
+    unsafe {
+        prctl(libc::PR_GET_PDEATHSIG, &mut sig as *mut _ as _, 0, 0, 0)?;
+    }
+    Ok(Signal::from_i32(sig))
+}
+
+/// Set the calling thread's "no new privileges" flag.
+///
+/// Once set, this flag cannot be unset. It is inherited by children created by `fork()` and
+/// `clone()`, and preserved across `execve()`. When it is set, `execve()` will not grant any new
+/// privileges (for example, set-user-ID bits and file capabilities will be ignored).
+///
+/// This is required in order to install seccomp filters without the `CAP_SYS_ADMIN` capability.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_set_no_new_privs() -> Result<()> {
+    prctl_set_bool(libc::PR_SET_NO_NEW_PRIVS, true)
+}
+
+/// Get the calling thread's "no new privileges" flag.
+///
+/// See [`prctl_set_no_new_privs()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_get_no_new_privs() -> Result<bool> {
+    prctl_get_bool(libc::PR_GET_NO_NEW_PRIVS)
+}
+
+/// Set the "dumpable" flag of the calling process.
+///
+/// This determines whether core dumps will be produced for the process, and whether it can be
+/// attached to with `ptrace(PTRACE_ATTACH)`. See `prctl(2)` for more information.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_set_dumpable(dumpable: bool) -> Result<()> {
+    prctl_set_bool(libc::PR_SET_DUMPABLE, dumpable)
+}
+
+/// Get the "dumpable" flag of the calling process.
+///
+/// See [`prctl_set_dumpable()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_get_dumpable() -> Result<bool> {
+    prctl_get_bool(libc::PR_GET_DUMPABLE)
+}
+
+/// Set the "child subreaper" attribute of the calling process.
+///
+/// If this is set, the calling process will become the parent of any orphaned descendant
+/// processes (instead of `init` or the nearest ancestor subreaper).
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_set_child_subreaper(subreaper: bool) -> Result<()> {
+    prctl_set_bool(libc::PR_SET_CHILD_SUBREAPER, subreaper)
+}
+
+/// Get the "child subreaper" attribute of the calling process.
+///
+/// See [`prctl_set_child_subreaper()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_get_child_subreaper() -> Result<bool> {
+    let mut val: libc::c_int = 0;
+    unsafe {
+        prctl(
+            libc::PR_GET_CHILD_SUBREAPER,
+            &mut val as *mut _ as _,
+            0,
+            0,
+            0,
+        )?;
+    }
+    Ok(val != 0)
+}
+
+/// Set the "keep capabilities" flag of the calling thread.
+///
+/// If this is set, the thread's permitted capabilities are retained when all of its UIDs are
+/// changed from 0 to nonzero values (normally they would be cleared). This flag is reset when
+/// executing a new program with `execve()`.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_set_keepcaps(keepcaps: bool) -> Result<()> {
+    prctl_set_bool(libc::PR_SET_KEEPCAPS, keepcaps)
+}
+
+/// Get the "keep capabilities" flag of the calling thread.
+///
+/// See [`prctl_set_keepcaps()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_get_keepcaps() -> Result<bool> {
+    prctl_get_bool(libc::PR_GET_KEEPCAPS)
+}
+
+/// Check whether the given capability is in the calling thread's capability bounding set.
+///
+/// This fails with `EINVAL` if the capability is not supported by the running kernel.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_capbset_read(cap: Capability) -> Result<bool> {
+    unsafe { prctl(libc::PR_CAPBSET_READ, cap.as_u32() as _, 0, 0, 0) }.map(|res| res != 0)
+}
+
+/// Remove the given capability from the calling thread's capability bounding set.
+///
+/// This requires the `CAP_SETPCAP` capability.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_capbset_drop(cap: Capability) -> Result<()> {
+    unsafe {
+        prctl(libc::PR_CAPBSET_DROP, cap.as_u32() as _, 0, 0, 0)?;
+    }
+    Ok(())
+}
+
+#[inline]
+fn prctl_cap_ambient(op: libc::c_int, cap: u32) -> Result<libc::c_int> {
+    unsafe { prctl(libc::PR_CAP_AMBIENT, op as _, cap as _, 0, 0) }
+}
+
+/// Check whether the given capability is in the calling thread's ambient capability set.
+///
+/// This fails with `EINVAL` if the capability is not supported by the running kernel.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_cap_ambient_is_set(cap: Capability) -> Result<bool> {
+    prctl_cap_ambient(libc::PR_CAP_AMBIENT_IS_SET, cap.as_u32()).map(|res| res != 0)
+}
+
+/// Add the given capability to the calling thread's ambient capability set.
+///
+/// The capability must already be present in both the permitted and inheritable sets.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_cap_ambient_raise(cap: Capability) -> Result<()> {
+    prctl_cap_ambient(libc::PR_CAP_AMBIENT_RAISE, cap.as_u32())?;
+    Ok(())
+}
+
+/// Remove the given capability from the calling thread's ambient capability set.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_cap_ambient_lower(cap: Capability) -> Result<()> {
+    prctl_cap_ambient(libc::PR_CAP_AMBIENT_LOWER, cap.as_u32())?;
+    Ok(())
+}
+
+/// Remove all capabilities from the calling thread's ambient capability set.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_cap_ambient_clear_all() -> Result<()> {
+    prctl_cap_ambient(libc::PR_CAP_AMBIENT_CLEAR_ALL, 0)?;
+    Ok(())
+}
+
+/// Set the "timer slack" value of the calling thread, in nanoseconds.
+///
+/// This controls how much the kernel may delay timer expirations in order to group them
+/// together. A value of 0 resets the timer slack to the thread's default value (the value
+/// inherited from its parent).
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_set_timerslack(slack_ns: libc::c_ulong) -> Result<()> {
+    unsafe {
+        prctl(libc::PR_SET_TIMERSLACK, slack_ns, 0, 0, 0)?;
+    }
+    Ok(())
+}
+
+/// Get the "timer slack" value of the calling thread, in nanoseconds.
+///
+/// See [`prctl_set_timerslack()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn prctl_get_timerslack() -> Result<libc::c_ulong> {
+    // prctl() returns an int, which would truncate the value, so make the syscall directly
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_prctl,
+            libc::PR_GET_TIMERSLACK,
+            0 as libc::c_ulong,
+            0 as libc::c_ulong,
+            0 as libc::c_ulong,
+            0 as libc::c_ulong,
+        )
+    };
+    if res == -1 {
+        Err(Error::last())
+    } else {
+        Ok(res as libc::c_ulong)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prctl_name() {
+        let mut orig_buf = [0; 16];
+        let orig_name = prctl_get_name(&mut orig_buf).unwrap();
+
+        let mut buf = [0; 16];
+
+        prctl_set_name(CStr::from_bytes_with_nul(b"slibc-test\0").unwrap()).unwrap();
+        assert_eq!(prctl_get_name(&mut buf).unwrap().to_bytes(), b"slibc-test");
+
+        // Truncated to 15 bytes
+        prctl_set_name(CStr::from_bytes_with_nul(b"0123456789abcdefghij\0").unwrap()).unwrap();
+        assert_eq!(
+            prctl_get_name(&mut buf).unwrap().to_bytes(),
+            b"0123456789abcde"
+        );
+
+        prctl_set_name(orig_name).unwrap();
+        assert_eq!(prctl_get_name(&mut buf).unwrap(), orig_name);
+    }
+
+    #[test]
+    fn test_prctl_pdeathsig() {
+        let orig_sig = prctl_get_pdeathsig().unwrap();
+
+        prctl_set_pdeathsig(Signal::SIGUSR1).unwrap();
+        assert_eq!(prctl_get_pdeathsig().unwrap(), Some(Signal::SIGUSR1));
+        prctl_set_pdeathsig(None).unwrap();
+        assert_eq!(prctl_get_pdeathsig().unwrap(), None);
+
+        prctl_set_pdeathsig(orig_sig).unwrap();
+    }
+
+    #[test]
+    fn test_prctl_bools() {
+        assert!(prctl_get_dumpable().unwrap());
+        prctl_set_dumpable(true).unwrap();
+
+        let keepcaps = prctl_get_keepcaps().unwrap();
+        prctl_set_keepcaps(!keepcaps).unwrap();
+        assert_eq!(prctl_get_keepcaps().unwrap(), !keepcaps);
+        prctl_set_keepcaps(keepcaps).unwrap();
+
+        crate::util::run_in_child(|| {
+            !prctl_get_no_new_privs().unwrap()
+                && prctl_set_no_new_privs().is_ok()
+                && prctl_get_no_new_privs().unwrap()
+                && prctl_set_child_subreaper(true).is_ok()
+                && prctl_get_child_subreaper().unwrap()
+                && prctl_set_child_subreaper(false).is_ok()
+                && !prctl_get_child_subreaper().unwrap()
+        });
+    }
+
+    #[test]
+    fn test_prctl_caps() {
+        prctl_capbset_read(Capability::CAP_CHOWN).unwrap();
+        prctl_cap_ambient_is_set(Capability::CAP_CHOWN).unwrap();
+
+        // Lowering a capability that isn't in the ambient set is always allowed
+        if !prctl_cap_ambient_is_set(Capability::CAP_CHOWN).unwrap() {
+            prctl_cap_ambient_lower(Capability::CAP_CHOWN).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_prctl_timerslack() {
+        let orig_slack = prctl_get_timerslack().unwrap();
+
+        prctl_set_timerslack(100_000).unwrap();
+        assert_eq!(prctl_get_timerslack().unwrap(), 100_000);
+
+        // Values that don't fit in an int aren't truncated
+        #[cfg(target_pointer_width = "64")]
+        {
+            prctl_set_timerslack(5_000_000_000).unwrap();
+            assert_eq!(prctl_get_timerslack().unwrap(), 5_000_000_000);
+        }
+
+        prctl_set_timerslack(orig_slack).unwrap();
+        assert_eq!(prctl_get_timerslack().unwrap(), orig_slack);
+    }
+}
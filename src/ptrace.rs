@@ -0,0 +1,423 @@
+use crate::internal_prelude::*;
+
+use crate::{SigInfo, Signal};
+
+bitflags::bitflags! {
+    /// Options for [`ptrace_setoptions()`] and [`ptrace_seize()`].
+    ///
+    /// See `ptrace(2)` for more information.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct PtraceOptions: libc::c_int {
+        const TRACESYSGOOD = libc::PTRACE_O_TRACESYSGOOD;
+        const TRACEFORK = libc::PTRACE_O_TRACEFORK;
+        const TRACEVFORK = libc::PTRACE_O_TRACEVFORK;
+        const TRACECLONE = libc::PTRACE_O_TRACECLONE;
+        const TRACEEXEC = libc::PTRACE_O_TRACEEXEC;
+        const TRACEVFORKDONE = libc::PTRACE_O_TRACEVFORKDONE;
+        const TRACEEXIT = libc::PTRACE_O_TRACEEXIT;
+        const TRACESECCOMP = libc::PTRACE_O_TRACESECCOMP;
+        const EXITKILL = libc::PTRACE_O_EXITKILL;
+        const SUSPEND_SECCOMP = libc::PTRACE_O_SUSPEND_SECCOMP;
+    }
+}
+
+#[inline]
+unsafe fn ptrace(
+    request: libc::c_long,
+    pid: libc::pid_t,
+    addr: *mut libc::c_void,
+    data: *mut libc::c_void,
+) -> Result<()> {
+    // The system call takes `long` arguments, so make sure the upper bits are set properly
+    Error::unpack_nz(
+        libc::syscall(libc::SYS_ptrace, request, pid as libc::c_long, addr, data) as i32,
+    )
+}
+
+#[inline]
+fn ptrace_resume<S: Into<Option<Signal>>>(
+    request: libc::c_long,
+    pid: libc::pid_t,
+    sig: S,
+) -> Result<()> {
+    let sig = sig.into().map_or(0, |s| s.as_i32()) as libc::c_long;
+    unsafe {
+        ptrace(
+            request,
+            pid,
+            core::ptr::null_mut(),
+            sig as *mut libc::c_void,
+        )
+    }
+}
+
+/// Indicate that this process is to be traced by its parent (`PTRACE_TRACEME`).
+///
+/// This is usually called in a child process after `fork()`, before it calls `execve()` or
+/// stops itself with `SIGSTOP`.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_traceme() -> Result<()> {
+    unsafe {
+        ptrace(
+            libc::PTRACE_TRACEME as _,
+            0,
+            core::ptr::null_mut(),
+            core::ptr::null_mut(),
+        )
+    }
+}
+
+/// Attach to the process specified by `pid` (`PTRACE_ATTACH`).
+///
+/// The tracee is sent a `SIGSTOP`; use [`crate::waitpid()`] to wait for it to stop.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_attach(pid: libc::pid_t) -> Result<()> {
+    unsafe {
+        ptrace(
+            libc::PTRACE_ATTACH as _,
+            pid,
+            core::ptr::null_mut(),
+            core::ptr::null_mut(),
+        )
+    }
+}
+
+/// Attach to the process specified by `pid` without stopping it, and set the given tracing
+/// options (`PTRACE_SEIZE`).
+///
+/// This was added in Linux 3.4.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_seize(pid: libc::pid_t, options: PtraceOptions) -> Result<()> {
+    unsafe {
+        ptrace(
+            libc::PTRACE_SEIZE as _,
+            pid,
+            core::ptr::null_mut(),
+            options.bits() as libc::c_long as *mut libc::c_void,
+        )
+    }
+}
+
+/// Detach from the stopped tracee specified by `pid`, restarting it (`PTRACE_DETACH`).
+///
+/// If `sig` is not `None`, the given signal is delivered to the tracee.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_detach<S: Into<Option<Signal>>>(pid: libc::pid_t, sig: S) -> Result<()> {
+    ptrace_resume(libc::PTRACE_DETACH as _, pid, sig)
+}
+
+/// Stop a tracee that was attached with [`ptrace_seize()`] (`PTRACE_INTERRUPT`).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_interrupt(pid: libc::pid_t) -> Result<()> {
+    unsafe {
+        ptrace(
+            libc::PTRACE_INTERRUPT as _,
+            pid,
+            core::ptr::null_mut(),
+            core::ptr::null_mut(),
+        )
+    }
+}
+
+/// Restart the stopped tracee specified by `pid` (`PTRACE_CONT`).
+///
+/// If `sig` is not `None`, the given signal is delivered to the tracee.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_cont<S: Into<Option<Signal>>>(pid: libc::pid_t, sig: S) -> Result<()> {
+    ptrace_resume(libc::PTRACE_CONT as _, pid, sig)
+}
+
+/// Restart the stopped tracee specified by `pid`, arranging for it to be stopped at the next
+/// entry to or exit from a system call (`PTRACE_SYSCALL`).
+///
+/// If `sig` is not `None`, the given signal is delivered to the tracee. If
+/// [`PtraceOptions::TRACESYSGOOD`] is set, system call stops will be reported as
+/// [`WaitStatus::PtraceSyscall`](crate::WaitStatus::PtraceSyscall).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_syscall<S: Into<Option<Signal>>>(pid: libc::pid_t, sig: S) -> Result<()> {
+    ptrace_resume(libc::PTRACE_SYSCALL as _, pid, sig)
+}
+
+/// Restart the stopped tracee specified by `pid`, arranging for it to be stopped after
+/// executing a single instruction (`PTRACE_SINGLESTEP`).
+///
+/// If `sig` is not `None`, the given signal is delivered to the tracee.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_singlestep<S: Into<Option<Signal>>>(pid: libc::pid_t, sig: S) -> Result<()> {
+    ptrace_resume(libc::PTRACE_SINGLESTEP as _, pid, sig)
+}
+
+/// Set the tracing options for the stopped tracee specified by `pid` (`PTRACE_SETOPTIONS`).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_setoptions(pid: libc::pid_t, options: PtraceOptions) -> Result<()> {
+    unsafe {
+        ptrace(
+            libc::PTRACE_SETOPTIONS as _,
+            pid,
+            core::ptr::null_mut(),
+            options.bits() as libc::c_long as *mut libc::c_void,
+        )
+    }
+}
+
+/// Retrieve the message associated with the most recent ptrace event of the stopped tracee
+/// specified by `pid` (`PTRACE_GETEVENTMSG`).
+///
+/// For example, for `PTRACE_EVENT_FORK` this is the PID of the new child, and for
+/// `PTRACE_EVENT_EXIT` it is the tracee's exit status (in the same format as is returned by
+/// `waitpid()`).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_geteventmsg(pid: libc::pid_t) -> Result<libc::c_ulong> {
+    let mut msg = 0;
+    unsafe {
+        ptrace(
+            libc::PTRACE_GETEVENTMSG as _,
+            pid,
+            core::ptr::null_mut(),
+            &mut msg as *mut libc::c_ulong as *mut _,
+        )?;
+    }
+    Ok(msg)
+}
+
+/// Retrieve information about the signal that caused the tracee specified by `pid` to stop
+/// (`PTRACE_GETSIGINFO`).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_getsiginfo(pid: libc::pid_t) -> Result<SigInfo> {
+    let mut info = MaybeUninit::<libc::siginfo_t>::uninit();
+    unsafe {
+        ptrace(
+            libc::PTRACE_GETSIGINFO as _,
+            pid,
+            core::ptr::null_mut(),
+            info.as_mut_ptr() as *mut _,
+        )?;
+        Ok(SigInfo(info.assume_init()))
+    }
+}
+
+/// Read a word at the address `addr` in the memory of the stopped tracee specified by `pid`
+/// (`PTRACE_PEEKDATA`).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_peekdata(pid: libc::pid_t, addr: usize) -> Result<libc::c_long> {
+    // Unlike the glibc wrapper, the raw system call stores the result in `data`, so there's no
+    // ambiguity between errors and a value of -1
+    let mut data = 0;
+    unsafe {
+        ptrace(
+            libc::PTRACE_PEEKDATA as _,
+            pid,
+            addr as *mut _,
+            &mut data as *mut libc::c_long as *mut _,
+        )?;
+    }
+    Ok(data)
+}
+
+/// Write the word `data` to the address `addr` in the memory of the stopped tracee specified by
+/// `pid` (`PTRACE_POKEDATA`).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[inline]
+pub fn ptrace_pokedata(pid: libc::pid_t, addr: usize, data: libc::c_long) -> Result<()> {
+    unsafe {
+        ptrace(
+            libc::PTRACE_POKEDATA as _,
+            pid,
+            addr as *mut _,
+            data as *mut _,
+        )
+    }
+}
+
+/// Retrieve the general-purpose registers of the stopped tracee specified by `pid`
+/// (`PTRACE_GETREGSET` with `NT_PRSTATUS`).
+///
+/// If the kernel returns a register set of a different size (for example, because the tracee is
+/// a 32-bit process), this fails with `EIO`.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))
+)]
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub fn ptrace_getregset(pid: libc::pid_t) -> Result<libc::user_regs_struct> {
+    let mut regs = MaybeUninit::<libc::user_regs_struct>::uninit();
+    let mut iov = libc::iovec {
+        iov_base: regs.as_mut_ptr() as *mut _,
+        iov_len: core::mem::size_of::<libc::user_regs_struct>(),
+    };
+
+    unsafe {
+        ptrace(
+            libc::PTRACE_GETREGSET as _,
+            pid,
+            libc::NT_PRSTATUS as usize as *mut _,
+            &mut iov as *mut libc::iovec as *mut _,
+        )?;
+    }
+
+    // The kernel updates iov_len to the amount of data it wrote; we can't return a partially
+    // initialized structure
+    if iov.iov_len != core::mem::size_of::<libc::user_regs_struct>() {
+        return Err(Error::from_code(libc::EIO));
+    }
+    Ok(unsafe { regs.assume_init() })
+}
+
+/// Set the general-purpose registers of the stopped tracee specified by `pid`
+/// (`PTRACE_SETREGSET` with `NT_PRSTATUS`).
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))
+)]
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub fn ptrace_setregset(pid: libc::pid_t, regs: &libc::user_regs_struct) -> Result<()> {
+    let mut iov = libc::iovec {
+        iov_base: regs as *const libc::user_regs_struct as *mut _,
+        iov_len: core::mem::size_of::<libc::user_regs_struct>(),
+    };
+
+    unsafe {
+        ptrace(
+            libc::PTRACE_SETREGSET as _,
+            pid,
+            libc::NT_PRSTATUS as usize as *mut _,
+            &mut iov as *mut libc::iovec as *mut _,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{WaitFlags, WaitStatus};
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn syscall_nr(regs: &libc::user_regs_struct) -> libc::c_long {
+        #[cfg(target_arch = "x86_64")]
+        return regs.orig_rax as _;
+        #[cfg(target_arch = "aarch64")]
+        return regs.regs[8] as _;
+    }
+
+    #[test]
+    fn test_ptrace_traceme() {
+        let value: libc::c_long = 0x1234;
+        let addr = &value as *const libc::c_long as usize;
+
+        let pid = crate::util::fork_child(|| {
+            if ptrace_traceme().is_err() || crate::raise(Signal::SIGSTOP).is_err() {
+                return false;
+            }
+
+            unsafe {
+                libc::syscall(libc::SYS_getppid);
+            }
+
+            let value = unsafe { core::ptr::read_volatile(&value) };
+            value == 0x5678
+        });
+
+        assert_eq!(
+            crate::waitpid(pid, WaitFlags::empty()).unwrap(),
+            Some((pid, WaitStatus::Stopped(libc::SIGSTOP)))
+        );
+
+        let info = ptrace_getsiginfo(pid).unwrap();
+        assert_eq!(info.signal(), Some(Signal::SIGSTOP));
+
+        ptrace_setoptions(
+            pid,
+            PtraceOptions::TRACESYSGOOD | PtraceOptions::TRACEEXIT | PtraceOptions::EXITKILL,
+        )
+        .unwrap();
+
+        assert_eq!(ptrace_peekdata(pid, addr).unwrap(), 0x1234);
+        ptrace_pokedata(pid, addr, 0x5678).unwrap();
+        assert_eq!(ptrace_peekdata(pid, addr).unwrap(), 0x5678);
+        assert_eq!(ptrace_peekdata(pid, 0).unwrap_err(), Errno::EIO);
+
+        // Step through system calls until we see getppid()
+        #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+        loop {
+            ptrace_syscall(pid, None).unwrap();
+            assert_eq!(
+                crate::waitpid(pid, WaitFlags::empty()).unwrap(),
+                Some((pid, WaitStatus::PtraceSyscall))
+            );
+
+            let regs = ptrace_getregset(pid).unwrap();
+            ptrace_setregset(pid, &regs).unwrap();
+            if syscall_nr(&regs) == libc::SYS_getppid {
+                break;
+            }
+        }
+
+        ptrace_cont(pid, None).unwrap();
+        assert_eq!(
+            crate::waitpid(pid, WaitFlags::empty()).unwrap(),
+            Some((
+                pid,
+                WaitStatus::PtraceEvent(libc::SIGTRAP, libc::PTRACE_EVENT_EXIT)
+            ))
+        );
+        assert_eq!(ptrace_geteventmsg(pid).unwrap(), 0);
+
+        ptrace_cont(pid, None).unwrap();
+        crate::util::wait_child_ok(pid);
+    }
+
+    #[test]
+    fn test_ptrace_attach_seize() {
+        let pid = match unsafe { crate::fork() }.unwrap() {
+            None => loop {
+                crate::pause();
+            },
+            Some(pid) => pid,
+        };
+
+        ptrace_attach(pid).unwrap();
+        assert_eq!(
+            crate::waitpid(pid, WaitFlags::empty()).unwrap(),
+            Some((pid, WaitStatus::Stopped(libc::SIGSTOP)))
+        );
+        ptrace_detach(pid, None).unwrap();
+
+        ptrace_seize(pid, PtraceOptions::EXITKILL).unwrap();
+        ptrace_interrupt(pid).unwrap();
+        assert_eq!(
+            crate::waitpid(pid, WaitFlags::empty()).unwrap(),
+            Some((
+                pid,
+                WaitStatus::PtraceEvent(libc::SIGTRAP, libc::PTRACE_EVENT_STOP)
+            ))
+        );
+        ptrace_detach(pid, None).unwrap();
+
+        crate::kill(pid, Signal::SIGKILL).unwrap();
+
+        assert_eq!(
+            crate::waitpid(pid, WaitFlags::empty()).unwrap(),
+            Some((pid, WaitStatus::Signaled(libc::SIGKILL, false)))
+        );
+    }
+}
@@ -2,6 +2,9 @@ use crate::internal_prelude::*;
 
 /// Open a pseudoterminal.
 ///
+/// If `termios` or `winsize` is not `None`, the terminal attributes or window size of the slave
+/// will be set accordingly.
+///
 /// On success, this returns a tuple of the `(master, slave)` file descriptors.
 ///
 /// # Safety
@@ -10,17 +13,23 @@ use crate::internal_prelude::*;
 /// [`ptsname()`](./fn.ptsname.html) internally. It should not be called concurrently from multiple
 /// threads, or concurrently with `ptsname()`.
 #[inline]
-pub unsafe fn openpty(winsize: Option<&crate::Winsize>) -> Result<(FileDesc, FileDesc)> {
+pub unsafe fn openpty(
+    termios: Option<&crate::Termios>,
+    winsize: Option<&crate::Winsize>,
+) -> Result<(FileDesc, FileDesc)> {
     let mut master = -1;
     let mut slave = -1;
 
+    let mut termios = termios.copied();
     let mut winsize = winsize.copied();
 
     Error::unpack_nz(libc::openpty(
         &mut master,
         &mut slave,
         core::ptr::null_mut(),
-        core::ptr::null_mut(),
+        termios
+            .as_mut()
+            .map_or_else(core::ptr::null_mut, |t| t as *mut _ as *mut _),
         winsize
             .as_mut()
             .map_or_else(core::ptr::null_mut, |w| w as *mut _ as *mut _),
@@ -426,7 +426,7 @@ pub enum RusageWho {
 }
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Rusage(libc::rusage);
+pub struct Rusage(pub(crate) libc::rusage);
 
 macro_rules! rusage_getters {
     ($($(#[doc = $doc:literal])* $name:ident, $field_name:ident;)*) => {
@@ -259,6 +259,185 @@ pub fn getcpu(cpu: Option<&mut u32>, node: Option<&mut u32>) -> Result<()> {
     } as i32)
 }
 
+#[cfg(target_os = "linux")]
+bitflags::bitflags! {
+    /// Flags for [`unshare()`], [`setns()`], and [`clone3()`].
+    ///
+    /// Note that some flags have different meanings depending on which function they are passed
+    /// to. For example, [`Self::FILES`] *unshares* the file descriptor table when passed to
+    /// [`unshare()`], but causes the child to *share* the parent's file descriptor table when
+    /// passed to [`clone3()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[derive(Default)]
+    pub struct CloneFlags: u64 {
+        /// Use a new mount namespace.
+        const NEWNS = libc::CLONE_NEWNS as u64;
+        /// Use a new user namespace.
+        ///
+        /// Unlike most of the other namespace types, this does not require any privileges.
+        const NEWUSER = libc::CLONE_NEWUSER as u64;
+        /// Use a new PID namespace.
+        ///
+        /// With [`unshare()`], the calling process is not moved into the new namespace; its first
+        /// child will become PID 1 in the new namespace.
+        const NEWPID = libc::CLONE_NEWPID as u64;
+        /// Use a new network namespace.
+        const NEWNET = libc::CLONE_NEWNET as u64;
+        /// Use a new UTS namespace (hostname and NIS domain name).
+        const NEWUTS = libc::CLONE_NEWUTS as u64;
+        /// Use a new IPC namespace.
+        const NEWIPC = libc::CLONE_NEWIPC as u64;
+        /// Use a new cgroup namespace (Linux 4.6+).
+        const NEWCGROUP = libc::CLONE_NEWCGROUP as u64;
+        /// Use a new time namespace (Linux 5.6+).
+        ///
+        /// With [`unshare()`], the calling process is not moved into the new namespace; its
+        /// children will be.
+        const NEWTIME = sys::CLONE_NEWTIME;
+
+        /// Share (with [`clone3()`]) or unshare (with [`unshare()`]) the file descriptor table.
+        const FILES = libc::CLONE_FILES as u64;
+        /// Share (with [`clone3()`]) or unshare (with [`unshare()`]) filesystem attributes (root
+        /// directory, current directory, and umask).
+        const FS = libc::CLONE_FS as u64;
+        /// Share (with [`clone3()`]) or unshare (with [`unshare()`]) System V semaphore
+        /// adjustment values.
+        const SYSVSEM = libc::CLONE_SYSVSEM as u64;
+
+        /// Make the new child's parent the same as the caller's parent ([`clone3()`] only).
+        const PARENT = libc::CLONE_PARENT as u64;
+        /// Return a PID file descriptor referring to the child ([`clone3()`] only; Linux 5.2+).
+        const PIDFD = libc::CLONE_PIDFD as u64;
+        /// Reset all signal handlers in the child to their default dispositions ([`clone3()`]
+        /// only; Linux 5.5+).
+        const CLEAR_SIGHAND = sys::CLONE_CLEAR_SIGHAND;
+        /// Place the child in the cgroup specified by [`CloneArgs::cgroup`] ([`clone3()`] only;
+        /// Linux 5.7+).
+        ///
+        /// This is set automatically if [`CloneArgs::cgroup`] is not `None`.
+        const INTO_CGROUP = sys::CLONE_INTO_CGROUP;
+    }
+}
+
+/// Disassociate parts of the current process's execution context (such as namespaces) from
+/// other processes.
+///
+/// See `unshare(2)` for more information.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[inline]
+pub fn unshare(flags: CloneFlags) -> Result<()> {
+    Error::unpack_nz(unsafe { libc::unshare(flags.bits() as _) })
+}
+
+/// Move the current thread into the namespace(s) referred to by the given file descriptor.
+///
+/// `fd` may either be a namespace file descriptor (e.g. opened from `/proc/<pid>/ns/<type>`) or
+/// (on Linux 5.8+) a [`PidFd`](crate::PidFd) referring to a process whose namespaces should be
+/// joined.
+///
+/// For a namespace file descriptor, `nstype` may be empty (allowing any namespace type) or a
+/// single `NEW*` flag specifying the type of namespace that `fd` must refer to. For a
+/// [`PidFd`](crate::PidFd), `nstype` specifies which of the process's namespaces to join.
+///
+/// See `setns(2)` for more information.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[inline]
+pub fn setns<F: AsRef<BorrowedFd>>(fd: &F, nstype: CloneFlags) -> Result<()> {
+    Error::unpack_nz(unsafe { libc::setns(fd.as_ref().fd(), nstype.bits() as _) })
+}
+
+/// Arguments for [`clone3()`].
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct CloneArgs<'a> {
+    /// Flags controlling the behavior of the new child.
+    pub flags: CloneFlags,
+    /// The signal to send to the parent when the child terminates.
+    ///
+    /// If this is `None`, no signal is sent, and the parent must pass `__WALL` or `__WCLONE` to
+    /// `waitpid()` to wait for the child.
+    pub exit_signal: Option<crate::Signal>,
+    /// A file descriptor referring to a cgroup v2 directory that the child should be placed in.
+    pub cgroup: Option<&'a BorrowedFd>,
+}
+
+#[cfg(target_os = "linux")]
+impl CloneArgs<'_> {
+    /// Create a new set of arguments with the given flags.
+    ///
+    /// `exit_signal` is set to `SIGCHLD` (as with `fork()`), and `cgroup` is set to `None`.
+    #[inline]
+    pub fn new(flags: CloneFlags) -> Self {
+        Self {
+            flags,
+            exit_signal: Some(crate::Signal::SIGCHLD),
+            cgroup: None,
+        }
+    }
+}
+
+/// Create a new child process with the `clone3()` system call.
+///
+/// This behaves similarly to [`fork()`](crate::fork) (the child receives a copy of the parent's
+/// memory and continues executing from the same point), but allows more control over the
+/// child's execution context. For example, it can be used to create a child in new namespaces.
+///
+/// On success, this returns `Ok(Some((pid, pidfd)))` in the parent and `Ok(None)` in the child.
+/// `pidfd` is only `Some` if [`CloneFlags::PIDFD`] was specified; in that case, it will have its
+/// close-on-exec flag set.
+///
+/// This system call was added in Linux 5.3; on older kernels it fails with `ENOSYS`.
+///
+/// # Safety
+///
+/// The same caveats that apply to [`fork()`](crate::fork) apply to this function. In addition,
+/// since this bypasses the C library's `fork()` wrapper, the C library's internal state in the
+/// child is not updated to reflect the new process: for example, the cached thread ID and the
+/// `pthread` data for the current thread still describe the parent. As a result, until it calls
+/// `execve()` or `_exit()`, the child may only make raw system calls or call async-signal-safe
+/// functions that do not depend on that state (so not, for example, `pthread_self()` or
+/// `raise()`, or `getpid()` on C libraries that cache the PID).
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[cfg(target_os = "linux")]
+pub unsafe fn clone3(args: &CloneArgs) -> Result<Option<(libc::pid_t, Option<crate::PidFd>)>> {
+    let mut pidfd: RawFd = -1;
+
+    let mut raw_args = sys::clone_args {
+        flags: args.flags.bits(),
+        exit_signal: args.exit_signal.map_or(0, |s| s.as_i32() as u64),
+        ..Default::default()
+    };
+
+    if args.flags.contains(CloneFlags::PIDFD) {
+        raw_args.pidfd = &mut pidfd as *mut RawFd as u64;
+    }
+
+    if let Some(cgroup) = args.cgroup {
+        raw_args.flags |= sys::CLONE_INTO_CGROUP;
+        raw_args.cgroup = cgroup.fd() as u64;
+    }
+
+    match libc::syscall(
+        libc::SYS_clone3,
+        &mut raw_args as *mut sys::clone_args,
+        core::mem::size_of::<sys::clone_args>(),
+    ) {
+        0 => Ok(None),
+        -1 => Err(Error::last()),
+        pid => Ok(Some((
+            pid as libc::pid_t,
+            if pidfd >= 0 {
+                Some(crate::PidFd::from_fd(pidfd))
+            } else {
+                None
+            },
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -377,4 +556,61 @@ mod tests {
         }
         // XXX: Can't validate node1/node2
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_unshare_setns() {
+        crate::util::run_in_child(|| match unshare(CloneFlags::NEWUSER | CloneFlags::NEWUTS) {
+            // User namespaces may be disabled
+            Err(e) => matches!(e.code(), libc::EPERM | libc::EINVAL | libc::ENOSPC),
+            Ok(()) => {
+                let fd = crate::open(
+                    CStr::from_bytes_with_nul(b"/proc/self/ns/uts\0").unwrap(),
+                    crate::OFlag::O_RDONLY | crate::OFlag::O_CLOEXEC,
+                    0,
+                )
+                .unwrap();
+
+                setns(&fd, CloneFlags::NEWUTS).is_ok()
+                    && setns(&fd, CloneFlags::NEWNET).unwrap_err() == Errno::EINVAL
+            }
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_clone3() {
+        let args = CloneArgs::new(CloneFlags::PIDFD);
+        assert_eq!(args.exit_signal, Some(crate::Signal::SIGCHLD));
+
+        match unsafe { clone3(&args) } {
+            // clone3() not supported or blocked
+            Err(e) if matches!(e.code(), libc::ENOSYS | libc::EPERM) => (),
+            Err(e) => panic!("{}", e),
+
+            Ok(None) => unsafe { crate::_exit(3) },
+
+            Ok(Some((pid, pidfd))) => {
+                let pidfd = pidfd.unwrap();
+                assert!(pidfd.as_ref().get_cloexec().unwrap());
+
+                assert_eq!(
+                    crate::waitpid(pid, crate::WaitFlags::empty()).unwrap(),
+                    Some((pid, crate::WaitStatus::Exited(3)))
+                );
+            }
+        }
+
+        match unsafe { clone3(&CloneArgs::new(CloneFlags::empty())) } {
+            Err(e) if matches!(e.code(), libc::ENOSYS | libc::EPERM) => (),
+            Err(e) => panic!("{}", e),
+
+            Ok(None) => unsafe { crate::_exit(0) },
+
+            Ok(Some((pid, pidfd))) => {
+                assert!(pidfd.is_none());
+                crate::util::wait_child_ok(pid);
+            }
+        }
+    }
 }
@@ -0,0 +1,396 @@
+use crate::internal_prelude::*;
+
+/// The action that a seccomp filter specifies for a system call.
+///
+/// See `seccomp(2)` for a full description of each action.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SeccompAction {
+    /// Kill the entire process (Linux 4.14+).
+    KillProcess,
+    /// Kill the thread that made the system call.
+    KillThread,
+    /// Send a `SIGSYS` signal to the thread that made the system call.
+    Trap,
+    /// Fail the system call with the given error number, without executing it.
+    Errno(u16),
+    /// Notify a `ptrace()` tracer, passing the given value as the event message.
+    Trace(u16),
+    /// Log the system call and then allow it (Linux 4.14+).
+    Log,
+    /// Allow the system call.
+    Allow,
+    /// Notify the user-space supervisor listening on the file descriptor returned by
+    /// installing a filter with [`SeccompFilterFlags::NEW_LISTENER`] (Linux 5.0+).
+    UserNotif,
+}
+
+impl SeccompAction {
+    /// Get the raw `SECCOMP_RET_*` value that a filter program returns to request this action.
+    #[inline]
+    pub fn as_u32(self) -> u32 {
+        match self {
+            Self::KillProcess => libc::SECCOMP_RET_KILL_PROCESS,
+            Self::KillThread => libc::SECCOMP_RET_KILL_THREAD,
+            Self::Trap => libc::SECCOMP_RET_TRAP,
+            Self::Errno(eno) => libc::SECCOMP_RET_ERRNO | eno as u32,
+            Self::Trace(msg) => libc::SECCOMP_RET_TRACE | msg as u32,
+            Self::Log => libc::SECCOMP_RET_LOG,
+            Self::Allow => libc::SECCOMP_RET_ALLOW,
+            Self::UserNotif => libc::SECCOMP_RET_USER_NOTIF,
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`seccomp_set_mode_filter()`].
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    pub struct SeccompFilterFlags: libc::c_ulong {
+        /// Synchronize all other threads of the calling process to the same filter tree.
+        const TSYNC = libc::SECCOMP_FILTER_FLAG_TSYNC;
+        /// Log all actions except `SECCOMP_RET_ALLOW` (Linux 4.14+).
+        const LOG = libc::SECCOMP_FILTER_FLAG_LOG;
+        /// Disable Speculative Store Bypass mitigation (Linux 4.17+).
+        const SPEC_ALLOW = libc::SECCOMP_FILTER_FLAG_SPEC_ALLOW;
+        /// Return a file descriptor that can be used to receive user notifications
+        /// (Linux 5.0+).
+        const NEW_LISTENER = libc::SECCOMP_FILTER_FLAG_NEW_LISTENER;
+        /// Fail with `ESRCH` (instead of returning a thread ID) if thread synchronization with
+        /// [`Self::TSYNC`] fails (Linux 5.7+).
+        const TSYNC_ESRCH = libc::SECCOMP_FILTER_FLAG_TSYNC_ESRCH;
+        /// Make user notification waits killable once the notification has been received by
+        /// the supervisor (Linux 5.19+).
+        const WAIT_KILLABLE_RECV = libc::SECCOMP_FILTER_FLAG_WAIT_KILLABLE_RECV;
+    }
+}
+
+/// Install a seccomp filter for the calling thread.
+///
+/// `filter` is a classic BPF program that operates on a `struct seccomp_data`; see
+/// [`SeccompFilterBuilder`] for a simple way to construct one.
+///
+/// Unless the calling thread has the `CAP_SYS_ADMIN` capability in its user namespace, the
+/// "no new privileges" attribute must be set first (see [`crate::prctl_set_no_new_privs()`]).
+///
+/// If `flags` contains [`SeccompFilterFlags::NEW_LISTENER`], this returns a file descriptor
+/// (with its close-on-exec flag set) that can be used to receive notifications for system
+/// calls that the filter handles with [`SeccompAction::UserNotif`]. Otherwise, it returns
+/// `None`.
+///
+/// If `flags` contains [`SeccompFilterFlags::TSYNC`] but not
+/// [`SeccompFilterFlags::TSYNC_ESRCH`], and one of the other threads could not be synchronized,
+/// the kernel returns the ID of that thread; this function reports that case as an `ESRCH`
+/// error.
+///
+/// See `seccomp(2)` for more information.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+pub fn seccomp_set_mode_filter(
+    filter: &[libc::sock_filter],
+    flags: SeccompFilterFlags,
+) -> Result<Option<FileDesc>> {
+    if filter.len() > libc::c_ushort::MAX as usize {
+        return Err(Error::from_code(libc::EINVAL));
+    }
+
+    let prog = libc::sock_fprog {
+        len: filter.len() as _,
+        filter: filter.as_ptr() as *mut _,
+    };
+
+    let res = Error::unpack(unsafe {
+        libc::syscall(
+            libc::SYS_seccomp,
+            libc::SECCOMP_SET_MODE_FILTER,
+            flags.bits(),
+            &prog,
+        ) as i32
+    })?;
+
+    if flags.contains(SeccompFilterFlags::NEW_LISTENER) {
+        Ok(Some(unsafe { FileDesc::new(res) }))
+    } else if res != 0 {
+        Err(Error::from_code(libc::ESRCH))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Check whether the running kernel supports the given seccomp action.
+///
+/// This uses `SECCOMP_GET_ACTION_AVAIL`, which was added in Linux 4.14.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+pub fn seccomp_get_action_avail(action: SeccompAction) -> Result<bool> {
+    // The kernel checks the full action, so strip any associated data
+    let action = action.as_u32() & libc::SECCOMP_RET_ACTION_FULL;
+
+    match Error::unpack_nz(unsafe {
+        libc::syscall(
+            libc::SYS_seccomp,
+            libc::SECCOMP_GET_ACTION_AVAIL,
+            0,
+            &action,
+        ) as i32
+    }) {
+        Ok(()) => Ok(true),
+        Err(e) if e.code() == libc::EOPNOTSUPP => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+// Offsets of the fields of `struct seccomp_data`
+#[cfg(feature = "alloc")]
+const SECCOMP_DATA_NR_OFFSET: u32 = 0;
+#[cfg(feature = "alloc")]
+const SECCOMP_DATA_ARCH_OFFSET: u32 = 4;
+
+#[cfg(feature = "alloc")]
+#[inline]
+const fn bpf_stmt(code: u32, k: u32) -> libc::sock_filter {
+    libc::sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[inline]
+const fn bpf_jump(code: u32, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+    libc::sock_filter {
+        code: code as u16,
+        jt,
+        jf,
+        k,
+    }
+}
+
+/// A builder for simple seccomp filters that match on system call numbers.
+///
+/// The generated program first checks that the system call was made using the native
+/// architecture's calling convention (handling it with the "bad architecture" action if not,
+/// which defaults to [`SeccompAction::KillProcess`]). It then compares the system call number
+/// against each rule in the order they were added, and returns the action from the first
+/// matching rule. If no rules match, the default action is returned.
+///
+/// # Example
+///
+/// ```no_run
+/// # use slibc::{SeccompAction, SeccompFilterBuilder, SeccompFilterFlags};
+/// let filter = SeccompFilterBuilder::new(SeccompAction::Allow)
+///     .rule(libc::SYS_ptrace, SeccompAction::Errno(libc::EPERM as u16))
+///     .rule(libc::SYS_reboot, SeccompAction::KillProcess)
+///     .build()
+///     .unwrap();
+///
+/// slibc::prctl_set_no_new_privs().unwrap();
+/// filter.install(SeccompFilterFlags::empty()).unwrap();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "alloc"))))]
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct SeccompFilterBuilder {
+    default_action: SeccompAction,
+    bad_arch_action: SeccompAction,
+    rules: Vec<(libc::c_long, SeccompAction)>,
+}
+
+#[cfg(feature = "alloc")]
+impl SeccompFilterBuilder {
+    /// Create a new builder that will return `default_action` for system calls that do not match
+    /// any rules.
+    #[inline]
+    pub fn new(default_action: SeccompAction) -> Self {
+        Self {
+            default_action,
+            bad_arch_action: SeccompAction::KillProcess,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Set the action taken for system calls made with a non-native calling convention.
+    #[inline]
+    pub fn bad_arch_action(&mut self, action: SeccompAction) -> &mut Self {
+        self.bad_arch_action = action;
+        self
+    }
+
+    /// Add a rule that returns `action` for the system call with number `nr` (e.g.
+    /// `libc::SYS_openat`).
+    #[inline]
+    pub fn rule(&mut self, nr: libc::c_long, action: SeccompAction) -> &mut Self {
+        self.rules.push((nr, action));
+        self
+    }
+
+    /// Generate the filter program.
+    ///
+    /// This fails with `ENOSYS` if the audit architecture of the current target is not known to
+    /// this library, or with `EINVAL` if the program would exceed the kernel's limit of
+    /// `BPF_MAXINSNS` instructions.
+    pub fn build(&self) -> Result<SeccompFilter> {
+        let arch = sys::AUDIT_ARCH_NATIVE.ok_or_else(|| Error::from_code(libc::ENOSYS))?;
+
+        let mut prog = Vec::with_capacity(self.rules.len() * 2 + 7);
+
+        prog.push(bpf_stmt(
+            libc::BPF_LD | libc::BPF_W | libc::BPF_ABS,
+            SECCOMP_DATA_ARCH_OFFSET,
+        ));
+        prog.push(bpf_jump(
+            libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+            arch,
+            1,
+            0,
+        ));
+        prog.push(bpf_stmt(
+            libc::BPF_RET | libc::BPF_K,
+            self.bad_arch_action.as_u32(),
+        ));
+
+        prog.push(bpf_stmt(
+            libc::BPF_LD | libc::BPF_W | libc::BPF_ABS,
+            SECCOMP_DATA_NR_OFFSET,
+        ));
+
+        // x32 system calls report the same architecture as x86_64 ones, but have
+        // __X32_SYSCALL_BIT set in the system call number
+        #[cfg(all(target_arch = "x86_64", target_pointer_width = "64"))]
+        {
+            prog.push(bpf_jump(
+                libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K,
+                0x4000_0000,
+                0,
+                1,
+            ));
+            prog.push(bpf_stmt(
+                libc::BPF_RET | libc::BPF_K,
+                self.bad_arch_action.as_u32(),
+            ));
+        }
+
+        for &(nr, action) in self.rules.iter() {
+            prog.push(bpf_jump(
+                libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K,
+                nr as u32,
+                0,
+                1,
+            ));
+            prog.push(bpf_stmt(libc::BPF_RET | libc::BPF_K, action.as_u32()));
+        }
+
+        prog.push(bpf_stmt(
+            libc::BPF_RET | libc::BPF_K,
+            self.default_action.as_u32(),
+        ));
+
+        if prog.len() > libc::BPF_MAXINSNS as usize {
+            return Err(Error::from_code(libc::EINVAL));
+        }
+
+        Ok(SeccompFilter(prog))
+    }
+}
+
+/// A seccomp filter program generated by [`SeccompFilterBuilder`].
+#[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "alloc"))))]
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct SeccompFilter(Vec<libc::sock_filter>);
+
+#[cfg(feature = "alloc")]
+impl SeccompFilter {
+    /// Get the BPF instructions that make up this program.
+    #[inline]
+    pub fn as_slice(&self) -> &[libc::sock_filter] {
+        &self.0
+    }
+
+    /// Install this filter for the calling thread.
+    ///
+    /// See [`seccomp_set_mode_filter()`].
+    #[inline]
+    pub fn install(&self, flags: SeccompFilterFlags) -> Result<Option<FileDesc>> {
+        seccomp_set_mode_filter(&self.0, flags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seccomp_get_action_avail() {
+        assert!(seccomp_get_action_avail(SeccompAction::Allow).unwrap());
+        assert!(seccomp_get_action_avail(SeccompAction::Errno(libc::EPERM as u16)).unwrap());
+        assert!(seccomp_get_action_avail(SeccompAction::KillThread).unwrap());
+    }
+
+    #[test]
+    fn test_seccomp_action_raw() {
+        assert_eq!(SeccompAction::Allow.as_u32(), libc::SECCOMP_RET_ALLOW);
+        assert_eq!(
+            SeccompAction::Errno(libc::EPERM as u16).as_u32(),
+            libc::SECCOMP_RET_ERRNO | libc::EPERM as u32
+        );
+        assert_eq!(
+            SeccompAction::Trace(0x1234).as_u32(),
+            libc::SECCOMP_RET_TRACE | 0x1234
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_seccomp_filter_errno() {
+        let filter = SeccompFilterBuilder::new(SeccompAction::Allow)
+            .rule(libc::SYS_getppid, SeccompAction::Errno(libc::EPERM as u16))
+            .build()
+            .unwrap();
+        let ppid = crate::getpid();
+
+        crate::util::run_in_child(|| {
+            if crate::prctl_set_no_new_privs().is_err()
+                || !matches!(filter.install(SeccompFilterFlags::empty()), Ok(None))
+            {
+                return false;
+            }
+
+            let res = unsafe { libc::syscall(libc::SYS_getppid) };
+            // Other system calls still work
+            res == -1 && crate::errno_get() == libc::EPERM && unsafe { libc::getpid() } != ppid
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_seccomp_filter_listener() {
+        let filter = SeccompFilterBuilder::new(SeccompAction::Allow)
+            .rule(libc::SYS_getppid, SeccompAction::UserNotif)
+            .build()
+            .unwrap();
+
+        crate::util::run_in_child(|| {
+            crate::prctl_set_no_new_privs().is_ok()
+                && match filter.install(SeccompFilterFlags::NEW_LISTENER) {
+                    Ok(Some(fd)) => fd.get_cloexec().unwrap_or(false),
+                    _ => false,
+                }
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_seccomp_filter_too_long() {
+        let mut builder = SeccompFilterBuilder::new(SeccompAction::Allow);
+        for _ in 0..libc::BPF_MAXINSNS / 2 {
+            builder.rule(libc::SYS_getppid, SeccompAction::Trap);
+        }
+        assert_eq!(builder.build().unwrap_err(), Errno::EINVAL);
+
+        let filter = SeccompFilterBuilder::new(SeccompAction::Allow)
+            .rule(libc::SYS_getppid, SeccompAction::Trap)
+            .build()
+            .unwrap();
+        assert_eq!(filter.as_slice().last().unwrap().k, libc::SECCOMP_RET_ALLOW);
+    }
+}
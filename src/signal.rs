@@ -17,6 +17,7 @@ macro_rules! define_signal {
         /// Real-time signals can be listed with [`Self::rt_signals()`] on platforms where they are
         /// available.
         #[derive(Copy, Clone, Eq, Hash, PartialEq)]
+        #[repr(transparent)]
         pub struct Signal(i32);
 
         impl Signal {
@@ -501,6 +502,45 @@ pub fn tgkill<S: Into<Option<Signal>>>(tgid: libc::pid_t, tid: libc::pid_t, sig:
     })
 }
 
+/// Send a signal, along with an accompanying value, to the process specified by `pid`.
+///
+/// The value can be retrieved by the receiver with [`SigInfo::si_value()`] (or
+/// `SigFdSigInfo::ssi_value()` on Linux). Unlike [`kill()`], real-time signals sent with this
+/// function are queued instead of being merged.
+///
+/// If `sig` is `None`, no signal is sent, but error checking is still performed.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "openbsd",
+        target_os = "dragonfly"
+    ))))
+)]
+#[cfg(not(any(apple, target_os = "openbsd", target_os = "dragonfly")))]
+#[inline]
+pub fn sigqueue<S: Into<Option<Signal>>>(pid: libc::pid_t, sig: S, value: SigVal) -> Result<()> {
+    Error::unpack_nz(unsafe { libc::sigqueue(pid, sig.into().map_or(0, |s| s.0), value.0) })
+}
+
+/// Send a signal, along with an accompanying value, to the specified thread in the current
+/// process.
+///
+/// This is a glibc extension; see [`sigqueue()`] for more information.
+#[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", target_env = "gnu"))))]
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+#[inline]
+pub fn pthread_sigqueue<S: Into<Option<Signal>>>(
+    thread: libc::pthread_t,
+    sig: S,
+    value: SigVal,
+) -> Result<()> {
+    Error::unpack_eno(unsafe {
+        libc::pthread_sigqueue(thread, sig.into().map_or(0, |s| s.0), value.0)
+    })
+}
+
 /// Represents a POSIX signal set (i.e. `sigset_t`).
 #[derive(Copy, Clone)]
 pub struct SigSet(libc::sigset_t);
@@ -871,6 +911,148 @@ macro_rules! sigset {
     }};
 }
 
+/// A value that can be sent along with a signal (a `union sigval`).
+///
+/// This can hold either an integer or a pointer. See [`sigqueue()`].
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+pub struct SigVal(pub(crate) libc::sigval);
+
+impl SigVal {
+    /// Create a `SigVal` holding the given integer (`sival_int`).
+    #[inline]
+    pub fn from_int(val: i32) -> Self {
+        let mut sv = libc::sigval {
+            sival_ptr: core::ptr::null_mut(),
+        };
+        // The integer is stored at the start of the union, regardless of endianness
+        unsafe {
+            (&mut sv as *mut libc::sigval as *mut libc::c_int).write(val);
+        }
+        Self(sv)
+    }
+
+    /// Create a `SigVal` holding the given pointer (`sival_ptr`).
+    #[inline]
+    pub fn from_ptr(ptr: *mut libc::c_void) -> Self {
+        Self(libc::sigval { sival_ptr: ptr })
+    }
+
+    /// Interpret this value as an integer (`sival_int`).
+    #[inline]
+    pub fn as_int(&self) -> i32 {
+        unsafe { (&self.0 as *const libc::sigval as *const libc::c_int).read() }
+    }
+
+    /// Interpret this value as a pointer (`sival_ptr`).
+    #[inline]
+    pub fn as_ptr(&self) -> *mut libc::c_void {
+        self.0.sival_ptr
+    }
+}
+
+impl Default for SigVal {
+    #[inline]
+    fn default() -> Self {
+        Self::from_ptr(core::ptr::null_mut())
+    }
+}
+
+impl fmt::Debug for SigVal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SigVal")
+            .field("sival_int", &self.as_int())
+            .field("sival_ptr", &self.as_ptr())
+            .finish()
+    }
+}
+
+/// A decoded `si_code` value, indicating why a signal was sent.
+///
+/// See [`SigInfo::code()`].
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "macos",
+        target_os = "ios",
+    )))
+)]
+#[cfg(any(linuxlike, target_os = "freebsd", apple))]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SigCode {
+    /// Sent by [`kill()`] or [`raise()`] (`SI_USER`).
+    User,
+    /// Sent by [`sigqueue()`] (`SI_QUEUE`).
+    Queue,
+    /// Sent by the expiration of a POSIX timer (`SI_TIMER`).
+    Timer,
+    /// Sent by the arrival of a message on an empty POSIX message queue (`SI_MESGQ`).
+    Mesgq,
+    /// Sent by the completion of an asynchronous I/O request (`SI_ASYNCIO`).
+    AsyncIo,
+    /// Sent by queued `SIGIO` (`SI_SIGIO`).
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    SigIo,
+    /// Sent by [`tgkill()`] (`SI_TKILL`).
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    Tkill,
+    /// Sent by the kernel (`SI_KERNEL`).
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    Kernel,
+    /// Any other code.
+    ///
+    /// The meaning of these codes generally depends on the signal (e.g. `CLD_EXITED` for
+    /// `SIGCHLD` or `SEGV_MAPERR` for `SIGSEGV`).
+    Other(i32),
+}
+
+#[cfg(any(linuxlike, target_os = "freebsd", apple))]
+impl SigCode {
+    /// Decode the given raw `si_code` value.
+    #[inline]
+    pub fn from_i32(code: i32) -> Self {
+        match code {
+            sys::SI_USER => Self::User,
+            sys::SI_QUEUE => Self::Queue,
+            sys::SI_TIMER => Self::Timer,
+            sys::SI_MESGQ => Self::Mesgq,
+            sys::SI_ASYNCIO => Self::AsyncIo,
+            #[cfg(linuxlike)]
+            libc::SI_SIGIO => Self::SigIo,
+            #[cfg(linuxlike)]
+            libc::SI_TKILL => Self::Tkill,
+            #[cfg(linuxlike)]
+            libc::SI_KERNEL => Self::Kernel,
+            _ => Self::Other(code),
+        }
+    }
+
+    /// Get the raw `si_code` value that this `SigCode` represents.
+    #[inline]
+    pub fn as_i32(self) -> i32 {
+        match self {
+            Self::User => sys::SI_USER,
+            Self::Queue => sys::SI_QUEUE,
+            Self::Timer => sys::SI_TIMER,
+            Self::Mesgq => sys::SI_MESGQ,
+            Self::AsyncIo => sys::SI_ASYNCIO,
+            #[cfg(linuxlike)]
+            Self::SigIo => libc::SI_SIGIO,
+            #[cfg(linuxlike)]
+            Self::Tkill => libc::SI_TKILL,
+            #[cfg(linuxlike)]
+            Self::Kernel => libc::SI_KERNEL,
+            Self::Other(code) => code,
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 #[repr(transparent)]
 pub struct SigInfo(pub(crate) libc::siginfo_t);
@@ -914,10 +1096,74 @@ impl SigInfo {
         si_status -> u32,
     }
 
+    /// Get the value sent along with the signal (e.g. by [`sigqueue()`]).
+    ///
+    /// This is only meaningful if the signal was sent with a value; check [`Self::code()`].
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(not(any(target_os = "netbsd", target_os = "openbsd"))))
+    )]
+    #[cfg(not(netbsdlike))]
+    #[inline]
+    pub fn si_value(&self) -> SigVal {
+        SigVal(unsafe { self.0.si_value() })
+    }
+
+    /// Decode the `si_code` field.
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "macos",
+            target_os = "ios",
+        )))
+    )]
+    #[cfg(any(linuxlike, target_os = "freebsd", apple))]
+    #[inline]
+    pub fn code(&self) -> SigCode {
+        SigCode::from_i32(self.si_code())
+    }
+
     #[inline]
     pub fn signal(&self) -> Option<Signal> {
         Signal::from_i32(self.si_signo())
     }
+
+    /// Set the value to be sent along with the signal.
+    ///
+    /// This is only useful for `SigInfo`s created with [`Self::new()`] (usually with an `si_code`
+    /// of `SI_QUEUE`).
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[cfg(target_os = "linux")]
+    #[inline]
+    pub fn set_si_value(&mut self, value: SigVal) {
+        let rt = unsafe { &mut *(&mut self.0 as *mut libc::siginfo_t as *mut sys::siginfo_rt) };
+        rt.rt.si_value = value.0;
+    }
+
+    /// Create a new `SigInfo` for sending the signal `sig` with the given `si_code`.
+    ///
+    /// The `si_pid` and `si_uid` fields are set to the PID and real UID of the current process,
+    /// and all other fields are zeroed; use [`Self::set_si_value()`] to attach a value. The
+    /// result can be sent with [`pidfd_send_signal()`](crate::pidfd_send_signal).
+    ///
+    /// Unless a process is sending a signal to itself, the kernel only accepts negative `si_code`
+    /// values (such as `SI_QUEUE`), to prevent processes from impersonating the kernel.
+    #[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+    #[cfg(target_os = "linux")]
+    pub fn new(sig: Signal, code: i32) -> Self {
+        let mut info = unsafe { core::mem::zeroed::<libc::siginfo_t>() };
+
+        let rt = unsafe { &mut *(&mut info as *mut libc::siginfo_t as *mut sys::siginfo_rt) };
+        rt.si_signo = sig.as_i32();
+        rt.si_code = code;
+        rt.rt.si_pid = crate::getpid();
+        rt.rt.si_uid = crate::getuid();
+
+        Self(info)
+    }
 }
 
 impl fmt::Debug for SigInfo {
@@ -1027,6 +1273,185 @@ pub fn raise(sig: Signal) -> Result<()> {
     Error::unpack_nz(unsafe { libc::raise(sig.as_i32()) })
 }
 
+bitflags::bitflags! {
+    /// Flags that can be passed to [`SigAction::new()`].
+    #[derive(Default)]
+    pub struct SaFlags: libc::c_int {
+        const NOCLDSTOP = libc::SA_NOCLDSTOP as _;
+        const NOCLDWAIT = libc::SA_NOCLDWAIT as _;
+        const NODEFER = libc::SA_NODEFER as _;
+        const ONSTACK = libc::SA_ONSTACK as _;
+        const RESETHAND = libc::SA_RESETHAND as _;
+        const RESTART = libc::SA_RESTART as _;
+        /// This flag is set automatically based on the [`SigHandler`] passed to
+        /// [`SigAction::new()`]; specifying it manually has no effect.
+        const SIGINFO = libc::SA_SIGINFO as _;
+    }
+}
+
+/// The disposition of a signal, as stored in a [`SigAction`].
+#[derive(Copy, Clone, Debug)]
+pub enum SigHandler {
+    /// The default action for the signal (`SIG_DFL`).
+    Default,
+    /// Ignore the signal (`SIG_IGN`).
+    Ignore,
+    /// A simple handler that only receives the signal number.
+    Handler(extern "C" fn(Signal)),
+    /// An extended handler (`SA_SIGINFO`) that also receives a [`SigInfo`] and a pointer to the
+    /// `ucontext_t` of the interrupted thread.
+    SigAction(extern "C" fn(Signal, &SigInfo, *mut libc::c_void)),
+}
+
+/// An action to take upon receipt of a signal (i.e. `struct sigaction`).
+///
+/// See [`sigaction()`] and [`SigActionGuard`].
+#[derive(Copy, Clone)]
+pub struct SigAction(libc::sigaction);
+
+impl SigAction {
+    /// Create a new signal action.
+    ///
+    /// `SaFlags::SIGINFO` is added or removed from `flags` depending on whether `handler` is a
+    /// [`SigHandler::SigAction`].
+    pub fn new(handler: SigHandler, flags: SaFlags, mask: SigSet) -> Self {
+        let mut act: libc::sigaction = unsafe { core::mem::zeroed() };
+
+        let mut flags = flags - SaFlags::SIGINFO;
+        act.sa_sigaction = match handler {
+            SigHandler::Default => libc::SIG_DFL,
+            SigHandler::Ignore => libc::SIG_IGN,
+            SigHandler::Handler(f) => f as libc::sighandler_t,
+            SigHandler::SigAction(f) => {
+                flags |= SaFlags::SIGINFO;
+                f as libc::sighandler_t
+            }
+        };
+        act.sa_flags = flags.bits() as _;
+        act.sa_mask = mask.0;
+
+        Self(act)
+    }
+
+    /// Get the handler specified by this action.
+    #[inline]
+    pub fn handler(&self) -> SigHandler {
+        match self.0.sa_sigaction {
+            libc::SIG_DFL => SigHandler::Default,
+            libc::SIG_IGN => SigHandler::Ignore,
+            f if self.flags().contains(SaFlags::SIGINFO) => SigHandler::SigAction(unsafe {
+                core::mem::transmute::<
+                    libc::sighandler_t,
+                    extern "C" fn(Signal, &SigInfo, *mut libc::c_void),
+                >(f)
+            }),
+            f => SigHandler::Handler(unsafe {
+                core::mem::transmute::<libc::sighandler_t, extern "C" fn(Signal)>(f)
+            }),
+        }
+    }
+
+    /// Get the flags specified by this action.
+    #[inline]
+    pub fn flags(&self) -> SaFlags {
+        SaFlags::from_bits_truncate(self.0.sa_flags as _)
+    }
+
+    /// Get the set of signals that will be blocked while the handler is running.
+    #[inline]
+    pub fn mask(&self) -> SigSet {
+        SigSet(self.0.sa_mask)
+    }
+}
+
+impl fmt::Debug for SigAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SigAction")
+            .field("handler", &self.handler())
+            .field("flags", &self.flags())
+            .field("mask", &self.mask())
+            .finish()
+    }
+}
+
+impl From<libc::sigaction> for SigAction {
+    #[inline]
+    fn from(a: libc::sigaction) -> Self {
+        Self(a)
+    }
+}
+
+impl AsRef<libc::sigaction> for SigAction {
+    #[inline]
+    fn as_ref(&self) -> &libc::sigaction {
+        &self.0
+    }
+}
+
+/// Examine and (if `act` is not `None`) change the action taken upon receipt of the given signal.
+///
+/// The previous action is returned.
+///
+/// # Safety
+///
+/// Signal handlers are severely restricted in what they can do (for example, they may only call
+/// async-signal-safe functions). Installing a handler that violates these restrictions, or changing
+/// the disposition of a signal that some other code relies on, can cause undefined behavior.
+#[inline]
+pub unsafe fn sigaction(sig: Signal, act: Option<&SigAction>) -> Result<SigAction> {
+    let mut oldact = MaybeUninit::uninit();
+    Error::unpack_nz(libc::sigaction(
+        sig.0,
+        act.map_or_else(core::ptr::null, |a| &a.0),
+        oldact.as_mut_ptr(),
+    ))?;
+    Ok(SigAction(oldact.assume_init()))
+}
+
+/// A guard that installs a signal action and restores the previous action when it is dropped.
+///
+/// Errors restoring the previous action are ignored.
+#[derive(Debug)]
+pub struct SigActionGuard {
+    sig: Signal,
+    oldact: SigAction,
+}
+
+impl SigActionGuard {
+    /// Install `act` as the action for `sig`, returning a guard that will restore the previous
+    /// action when dropped.
+    ///
+    /// # Safety
+    ///
+    /// See [`sigaction()`].
+    #[inline]
+    pub unsafe fn new(sig: Signal, act: &SigAction) -> Result<Self> {
+        let oldact = sigaction(sig, Some(act))?;
+        Ok(Self { sig, oldact })
+    }
+
+    /// Get the signal whose action this guard will restore.
+    #[inline]
+    pub fn signal(&self) -> Signal {
+        self.sig
+    }
+
+    /// Get the action that will be restored when this guard is dropped.
+    #[inline]
+    pub fn old_action(&self) -> &SigAction {
+        &self.oldact
+    }
+}
+
+impl Drop for SigActionGuard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            libc::sigaction(self.sig.0, &self.oldact.0, core::ptr::null_mut());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1391,6 +1816,48 @@ mod tests {
         assert_eq!(format!("{:?}", sigset!(Signal::SIGINT)), "{SIGINT}");
     }
 
+    #[test]
+    fn test_sigaction_new() {
+        extern "C" fn handler(_sig: Signal) {}
+        extern "C" fn handler_info(_sig: Signal, _info: &SigInfo, _ctx: *mut libc::c_void) {}
+
+        let act = SigAction::new(SigHandler::Default, SaFlags::SIGINFO, SigSet::empty());
+        assert!(matches!(act.handler(), SigHandler::Default));
+        assert_eq!(act.flags(), SaFlags::empty());
+        assert_eq!(act.mask(), SigSet::empty());
+
+        let act = SigAction::new(
+            SigHandler::Ignore,
+            SaFlags::RESTART,
+            sigset!(Signal::SIGINT),
+        );
+        assert!(matches!(act.handler(), SigHandler::Ignore));
+        assert_eq!(act.flags(), SaFlags::RESTART);
+        assert_eq!(act.mask(), sigset!(Signal::SIGINT));
+
+        let act = SigAction::new(
+            SigHandler::Handler(handler),
+            SaFlags::SIGINFO,
+            SigSet::full(),
+        );
+        assert!(
+            matches!(act.handler(), SigHandler::Handler(f) if f as usize == handler as *const () as usize)
+        );
+        assert_eq!(act.flags(), SaFlags::empty());
+        assert_eq!(act.mask(), SigSet::full());
+
+        let act = SigAction::new(
+            SigHandler::SigAction(handler_info),
+            SaFlags::NODEFER,
+            SigSet::empty(),
+        );
+        assert!(matches!(
+            act.handler(),
+            SigHandler::SigAction(f) if f as usize == handler_info as *const () as usize
+        ));
+        assert_eq!(act.flags(), SaFlags::NODEFER | SaFlags::SIGINFO);
+    }
+
     #[test]
     fn test_signal_aliases() {
         for &sig in Signal::ALL_POSIX_SIGNALS.iter() {
@@ -1413,4 +1880,86 @@ mod tests {
             assert_eq!(sigs, sigs2);
         }
     }
+
+    #[test]
+    fn test_sigval() {
+        for &val in [0, 1, -1, i32::MIN, i32::MAX].iter() {
+            assert_eq!(SigVal::from_int(val).as_int(), val);
+        }
+
+        let mut x = 0;
+        let ptr = &mut x as *mut i32 as *mut libc::c_void;
+        assert_eq!(SigVal::from_ptr(ptr).as_ptr(), ptr);
+        assert!(SigVal::default().as_ptr().is_null());
+        assert_eq!(SigVal::default().as_int(), 0);
+    }
+
+    #[cfg(any(linuxlike, target_os = "freebsd", apple))]
+    #[test]
+    fn test_sigcode() {
+        for &code in [
+            SigCode::User,
+            SigCode::Queue,
+            SigCode::Timer,
+            SigCode::Mesgq,
+            SigCode::AsyncIo,
+            #[cfg(linuxlike)]
+            SigCode::SigIo,
+            #[cfg(linuxlike)]
+            SigCode::Tkill,
+            #[cfg(linuxlike)]
+            SigCode::Kernel,
+            SigCode::Other(1),
+        ]
+        .iter()
+        {
+            assert_eq!(SigCode::from_i32(code.as_i32()), code);
+        }
+
+        assert_eq!(SigCode::from_i32(libc::SI_QUEUE), SigCode::Queue);
+        assert_eq!(SigCode::Other(12345).as_i32(), 12345);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sigqueue() {
+        let sig = Signal::sigrtmin();
+        let set = sigset!(sig);
+        let old_mask = pthread_sigmask(SigmaskHow::BLOCK, Some(&set)).unwrap();
+
+        let pid = crate::util::fork_child(|| {
+            // Real-time signals are queued, and delivered in order
+            [1, 2].iter().all(|&val| match sigwaitinfo(&set) {
+                Ok((s, info)) => {
+                    s == sig
+                        && info.code() == SigCode::Queue
+                        && info.si_pid() == crate::getppid()
+                        && info.si_value().as_int() == val
+                }
+                Err(_) => false,
+            })
+        });
+        pthread_sigmask(SigmaskHow::SETMASK, Some(&old_mask)).unwrap();
+
+        sigqueue(pid, None, SigVal::from_int(0)).unwrap();
+        sigqueue(pid, sig, SigVal::from_int(1)).unwrap();
+        sigqueue(pid, sig, SigVal::from_int(2)).unwrap();
+
+        crate::util::wait_child_ok(pid);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_siginfo_new_value() {
+        let mut info = SigInfo::new(Signal::SIGUSR1, libc::SI_QUEUE);
+        assert_eq!(info.signal(), Some(Signal::SIGUSR1));
+        assert_eq!(info.code(), SigCode::Queue);
+        assert_eq!(info.si_pid(), crate::getpid());
+        assert_eq!(info.si_uid(), crate::getuid());
+        assert_eq!(info.si_value().as_int(), 0);
+
+        info.set_si_value(SigVal::from_int(42));
+        assert_eq!(info.si_value().as_int(), 42);
+        assert_eq!(info.si_pid(), crate::getpid());
+    }
 }
@@ -1,5 +1,5 @@
 use crate::internal_prelude::*;
-use crate::{SigSet, Signal};
+use crate::{SigCode, SigSet, SigVal, Signal};
 
 #[cfg(feature = "std")]
 use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
@@ -167,6 +167,19 @@ impl SigFdSigInfo {
         ssi_addr_lsb -> u16,
     }
 
+    /// Get the value sent along with the signal (e.g. by [`sigqueue()`](crate::sigqueue)).
+    #[inline]
+    pub fn ssi_value(&self) -> SigVal {
+        // The kernel copies the entire `sival_ptr` field into `ssi_ptr`
+        SigVal::from_ptr(self.0.ssi_ptr as usize as *mut libc::c_void)
+    }
+
+    /// Decode the `ssi_code` field.
+    #[inline]
+    pub fn code(&self) -> SigCode {
+        SigCode::from_i32(self.ssi_code())
+    }
+
     #[inline]
     pub fn signal(&self) -> Option<Signal> {
         Signal::from_i32(self.ssi_signo())
@@ -196,4 +209,32 @@ mod tests {
         assert!(sfd.as_ref().get_cloexec().unwrap());
         assert!(sfd.as_ref().get_nonblocking().unwrap());
     }
+
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    #[test]
+    fn test_pthread_sigqueue() {
+        let sig = Signal::sigrtmin();
+        let set = crate::sigset!(sig);
+        let old_mask = crate::pthread_sigmask(crate::SigmaskHow::BLOCK, Some(&set)).unwrap();
+
+        let sfd = SignalFd::new(&set, SigFdFlags::CLOEXEC | SigFdFlags::NONBLOCK).unwrap();
+
+        let mut x = 0;
+        let ptr = &mut x as *mut i32 as *mut libc::c_void;
+        let thread = unsafe { libc::pthread_self() };
+        crate::pthread_sigqueue(thread, sig, SigVal::from_int(-5)).unwrap();
+        crate::pthread_sigqueue(thread, sig, SigVal::from_ptr(ptr)).unwrap();
+
+        let mut infos = [SigFdSigInfo::zeroed(), SigFdSigInfo::zeroed()];
+        assert_eq!(sfd.read_siginfos(&mut infos).unwrap(), 2);
+        crate::pthread_sigmask(crate::SigmaskHow::SETMASK, Some(&old_mask)).unwrap();
+
+        for info in infos.iter() {
+            assert_eq!(info.signal(), Some(sig));
+            assert_eq!(info.code(), SigCode::Queue);
+            assert_eq!(info.ssi_pid(), crate::getpid());
+        }
+        assert_eq!(infos[0].ssi_value().as_int(), -5);
+        assert_eq!(infos[1].ssi_value().as_ptr(), ptr);
+    }
 }
@@ -0,0 +1,432 @@
+use core::fmt;
+use core::ptr::NonNull;
+
+use crate::internal_prelude::*;
+
+use super::SockAddr;
+
+bitflags::bitflags! {
+    /// Network interface flags (see [`IfAddr::flags()`]).
+    pub struct IfFlags: libc::c_uint {
+        /// The interface is up.
+        const UP = libc::IFF_UP as _;
+        /// A valid broadcast address is set.
+        const BROADCAST = libc::IFF_BROADCAST as _;
+        /// Internal debugging is enabled.
+        const DEBUG = libc::IFF_DEBUG as _;
+        /// This is a loopback interface.
+        const LOOPBACK = libc::IFF_LOOPBACK as _;
+        /// This is a point-to-point link.
+        const POINTOPOINT = libc::IFF_POINTOPOINT as _;
+        /// Resources are allocated for the interface.
+        const RUNNING = libc::IFF_RUNNING as _;
+        /// No ARP protocol is used on this interface.
+        const NOARP = libc::IFF_NOARP as _;
+        /// The interface is in promiscuous mode.
+        const PROMISC = libc::IFF_PROMISC as _;
+        /// The interface receives all multicast packets.
+        const ALLMULTI = libc::IFF_ALLMULTI as _;
+        /// The interface supports multicast.
+        const MULTICAST = libc::IFF_MULTICAST as _;
+
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const NOTRAILERS = libc::IFF_NOTRAILERS as _;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const MASTER = libc::IFF_MASTER as _;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const SLAVE = libc::IFF_SLAVE as _;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const PORTSEL = libc::IFF_PORTSEL as _;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const AUTOMEDIA = libc::IFF_AUTOMEDIA as _;
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        const DYNAMIC = libc::IFF_DYNAMIC as _;
+    }
+}
+
+/// Get a list of the network interface addresses on the system.
+///
+/// Note that an interface may appear multiple times in the returned list (for example, once for
+/// each address assigned to it).
+#[inline]
+pub fn getifaddrs() -> Result<IfAddrs> {
+    let mut ifap = core::ptr::null_mut();
+    Error::unpack_nz(unsafe { libc::getifaddrs(&mut ifap) })?;
+    Ok(IfAddrs {
+        head: NonNull::new(ifap),
+    })
+}
+
+/// A list of network interface addresses returned by [`getifaddrs()`].
+///
+/// The list is freed with `freeifaddrs()` when this struct is dropped.
+#[derive(Debug)]
+pub struct IfAddrs {
+    head: Option<NonNull<libc::ifaddrs>>,
+}
+
+impl IfAddrs {
+    /// Iterate over the entries in this list.
+    #[inline]
+    pub fn iter(&self) -> IfAddrsIter<'_> {
+        IfAddrsIter {
+            cur: self.head.map(|p| unsafe { &*p.as_ptr() }),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a IfAddrs {
+    type Item = IfAddr<'a>;
+    type IntoIter = IfAddrsIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Drop for IfAddrs {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(head) = self.head {
+            unsafe {
+                libc::freeifaddrs(head.as_ptr());
+            }
+        }
+    }
+}
+
+unsafe impl Send for IfAddrs {}
+unsafe impl Sync for IfAddrs {}
+
+/// An iterator over the entries in an [`IfAddrs`] list.
+#[derive(Clone, Debug)]
+pub struct IfAddrsIter<'a> {
+    cur: Option<&'a libc::ifaddrs>,
+}
+
+impl<'a> Iterator for IfAddrsIter<'a> {
+    type Item = IfAddr<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let ifa = self.cur.take()?;
+        self.cur = unsafe { ifa.ifa_next.as_ref() };
+        Some(IfAddr(ifa))
+    }
+}
+
+impl core::iter::FusedIterator for IfAddrsIter<'_> {}
+
+/// A single entry returned by [`getifaddrs()`].
+#[derive(Copy, Clone)]
+pub struct IfAddr<'a>(&'a libc::ifaddrs);
+
+impl<'a> IfAddr<'a> {
+    /// The name of the interface.
+    #[inline]
+    pub fn name(&self) -> &'a CStr {
+        unsafe { CStr::from_ptr(self.0.ifa_name) }
+    }
+
+    /// The flags of the interface.
+    #[inline]
+    pub fn flags(&self) -> IfFlags {
+        IfFlags::from_bits_truncate(self.0.ifa_flags)
+    }
+
+    /// The address of the interface.
+    ///
+    /// This is `None` if the interface has no address, or if the address is not of a family
+    /// supported by [`SockAddr`] (for example, link-layer `AF_PACKET`/`AF_LINK` entries).
+    #[inline]
+    pub fn addr(&self) -> Option<SockAddr> {
+        unsafe { sockaddr_from_ptr(self.0.ifa_addr, None) }
+    }
+
+    /// The netmask of the interface.
+    #[inline]
+    pub fn netmask(&self) -> Option<SockAddr> {
+        unsafe { sockaddr_from_ptr(self.0.ifa_netmask, self.addr_family()) }
+    }
+
+    /// The broadcast address of the interface.
+    ///
+    /// This is only present if [`IfFlags::BROADCAST`] is set.
+    #[inline]
+    pub fn broadaddr(&self) -> Option<SockAddr> {
+        if self.flags().contains(IfFlags::BROADCAST) {
+            unsafe { sockaddr_from_ptr(self.dst_ptr(), self.addr_family()) }
+        } else {
+            None
+        }
+    }
+
+    /// The destination address of the interface.
+    ///
+    /// This is only present if [`IfFlags::POINTOPOINT`] is set.
+    #[inline]
+    pub fn dstaddr(&self) -> Option<SockAddr> {
+        if self.flags().contains(IfFlags::POINTOPOINT) {
+            unsafe { sockaddr_from_ptr(self.dst_ptr(), self.addr_family()) }
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn addr_family(&self) -> Option<libc::sa_family_t> {
+        unsafe { self.0.ifa_addr.as_ref() }.map(|sa| sa.sa_family)
+    }
+
+    #[inline]
+    fn dst_ptr(&self) -> *const libc::sockaddr {
+        cfg_if::cfg_if! {
+            if #[cfg(linuxlike)] {
+                self.0.ifa_ifu
+            } else {
+                self.0.ifa_dstaddr
+            }
+        }
+    }
+}
+
+impl fmt::Debug for IfAddr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IfAddr")
+            .field("name", &self.name())
+            .field("flags", &self.flags())
+            .field("addr", &self.addr())
+            .field("netmask", &self.netmask())
+            .field("broadaddr", &self.broadaddr())
+            .field("dstaddr", &self.dstaddr())
+            .finish()
+    }
+}
+
+unsafe fn sockaddr_from_ptr(
+    ptr: *const libc::sockaddr,
+    family_hint: Option<libc::sa_family_t>,
+) -> Option<SockAddr> {
+    let sa = ptr.as_ref()?;
+
+    // On the BSDs, netmasks may be truncated after the last nonzero byte (so sa_len may be too
+    // short to even include the family), and their family may be AF_UNSPEC
+    #[cfg(bsd)]
+    let (avail, family) = (
+        sa.sa_len as usize,
+        if sa.sa_len >= 2 {
+            sa.sa_family
+        } else {
+            libc::AF_UNSPEC as _
+        },
+    );
+    #[cfg(not(bsd))]
+    let (avail, family) = (usize::MAX, sa.sa_family);
+
+    let family = if family == libc::AF_UNSPEC as _ {
+        family_hint?
+    } else {
+        family
+    };
+
+    // The length isn't always provided, so infer it from the address family (only IPv4 and IPv6
+    // addresses are reported here)
+    let len = match family as _ {
+        libc::AF_INET => core::mem::size_of::<libc::sockaddr_in>(),
+        libc::AF_INET6 => core::mem::size_of::<libc::sockaddr_in6>(),
+        _ => return None,
+    };
+
+    // Copy into a zeroed buffer so that any truncated bytes read as zero
+    let mut storage = core::mem::zeroed::<libc::sockaddr_storage>();
+    core::ptr::copy_nonoverlapping(
+        ptr as *const u8,
+        &mut storage as *mut _ as *mut u8,
+        core::cmp::min(avail, len),
+    );
+    storage.ss_family = family;
+    #[cfg(bsd)]
+    {
+        storage.ss_len = len as u8;
+    }
+
+    SockAddr::from_raw(storage, len as _).ok()
+}
+
+/// Get the index of the network interface with the given name.
+#[inline]
+pub fn if_nametoindex(name: &CStr) -> Result<u32> {
+    match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+        0 => Err(Error::last()),
+        idx => Ok(idx),
+    }
+}
+
+/// Get the name of the network interface with the given index.
+///
+/// The name is stored in `buf`, which must be at least `IF_NAMESIZE` (16) bytes long; if it is
+/// shorter, this fails with `ERANGE`.
+#[inline]
+pub fn if_indextoname(index: u32, buf: &mut [u8]) -> Result<&CStr> {
+    if buf.len() < libc::IF_NAMESIZE {
+        return Err(Error::from_code(libc::ERANGE));
+    }
+
+    Error::unpack_ptr(unsafe { libc::if_indextoname(index, buf.as_mut_ptr() as *mut _) })?;
+    Ok(util::cstr_from_buf(buf).unwrap())
+}
+
+/// Get a list of all network interfaces on the system, as (index, name) pairs.
+#[inline]
+pub fn if_nameindex() -> Result<IfNameIndex> {
+    Ok(IfNameIndex(Error::unpack_ptr(unsafe {
+        libc::if_nameindex()
+    })?))
+}
+
+/// A list of network interfaces returned by [`if_nameindex()`].
+///
+/// The list is freed with `if_freenameindex()` when this struct is dropped.
+#[derive(Debug)]
+pub struct IfNameIndex(NonNull<libc::if_nameindex>);
+
+impl IfNameIndex {
+    /// Iterate over the (index, name) pairs in this list.
+    #[inline]
+    pub fn iter(&self) -> IfNameIndexIter<'_> {
+        IfNameIndexIter {
+            cur: self.0.as_ptr(),
+            phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a IfNameIndex {
+    type Item = (u32, &'a CStr);
+    type IntoIter = IfNameIndexIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Drop for IfNameIndex {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            libc::if_freenameindex(self.0.as_ptr());
+        }
+    }
+}
+
+unsafe impl Send for IfNameIndex {}
+unsafe impl Sync for IfNameIndex {}
+
+/// An iterator over the entries in an [`IfNameIndex`] list.
+#[derive(Clone, Debug)]
+pub struct IfNameIndexIter<'a> {
+    cur: *const libc::if_nameindex,
+    phantom: core::marker::PhantomData<&'a libc::if_nameindex>,
+}
+
+impl<'a> Iterator for IfNameIndexIter<'a> {
+    type Item = (u32, &'a CStr);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        // The list is terminated by an entry with an index of 0 and a NULL name
+        let ent = unsafe { &*self.cur };
+        if ent.if_index == 0 && ent.if_name.is_null() {
+            return None;
+        }
+
+        self.cur = unsafe { self.cur.add(1) };
+        Some((ent.if_index, unsafe { CStr::from_ptr(ent.if_name) }))
+    }
+}
+
+impl core::iter::FusedIterator for IfNameIndexIter<'_> {}
+
+unsafe impl Send for IfNameIndexIter<'_> {}
+unsafe impl Sync for IfNameIndexIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Inet4Addr, Inet6Addr};
+
+    fn lo_name() -> &'static CStr {
+        CStr::from_bytes_with_nul(if cfg!(linuxlike) { b"lo\0" } else { b"lo0\0" }).unwrap()
+    }
+
+    #[test]
+    fn test_getifaddrs() {
+        let ifaddrs = getifaddrs().unwrap();
+
+        let mut found_lo4 = false;
+        for ifa in &ifaddrs {
+            if ifa.name() != lo_name() {
+                continue;
+            }
+
+            assert!(ifa.flags().contains(IfFlags::LOOPBACK | IfFlags::UP));
+            assert_eq!(ifa.dstaddr(), None);
+
+            match ifa.addr() {
+                Some(SockAddr::Inet4(addr)) => {
+                    found_lo4 = true;
+                    assert_eq!(addr.ip(), Inet4Addr::LOCALHOST);
+                    assert_eq!(
+                        ifa.netmask().unwrap().unwrap_inet4().ip(),
+                        Inet4Addr::new(255, 0, 0, 0)
+                    );
+                }
+                Some(SockAddr::Inet6(addr)) => {
+                    assert_eq!(addr.ip(), Inet6Addr::LOCALHOST);
+                }
+                _ => (),
+            }
+        }
+
+        assert!(found_lo4);
+        #[cfg(feature = "alloc")]
+        assert!(format!("{:?}", ifaddrs.iter().next().unwrap()).starts_with("IfAddr {"));
+    }
+
+    #[test]
+    fn test_if_nametoindex() {
+        let idx = if_nametoindex(lo_name()).unwrap();
+        assert_ne!(idx, 0);
+
+        let mut buf = [0; libc::IF_NAMESIZE];
+        assert_eq!(if_indextoname(idx, &mut buf).unwrap(), lo_name());
+        assert_eq!(
+            if_indextoname(idx, &mut buf[..2]).unwrap_err(),
+            Errno::ERANGE
+        );
+
+        if_nametoindex(CStr::from_bytes_with_nul(b"nonexistent-if\0").unwrap()).unwrap_err();
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            if_indextoname(u32::MAX, &mut buf).unwrap_err(),
+            Errno::ENXIO
+        );
+
+        let nameindex = if_nameindex().unwrap();
+        assert!(nameindex
+            .iter()
+            .any(|(i, name)| i == idx && name == lo_name()));
+        for (i, name) in &nameindex {
+            assert_eq!(if_nametoindex(name).unwrap(), i);
+        }
+    }
+}
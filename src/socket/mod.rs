@@ -2,9 +2,16 @@ use crate::internal_prelude::*;
 
 use core::fmt;
 
+mod ifaddrs;
 mod inaddr;
+mod msg;
+mod netdb;
 mod sockaddr;
+mod sockopt;
+pub use ifaddrs::*;
 pub use inaddr::*;
+pub use msg::*;
+pub use netdb::*;
 pub use sockaddr::*;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -45,13 +52,13 @@ macro_rules! define_enum {
 
         impl $ename {
             #[allow(dead_code)]
-            fn from_raw(raw: $ty) -> Self {
+            fn from_raw(raw: $ty) -> Option<Self> {
                 match raw as _ {
                     $($(
                         #[cfg($cfg)]
-                        libc::$libc_name => Self::$name,
+                        libc::$libc_name => Some(Self::$name),
                     )+)+
-                    _ => unreachable!(),
+                    _ => None,
                 }
             }
         }
@@ -126,9 +133,27 @@ bitflags::bitflags! {
     pub struct MsgFlag: libc::c_int {
         const EOR = libc::MSG_EOR;
         const OOB = libc::MSG_OOB;
+        const PEEK = libc::MSG_PEEK;
+        const WAITALL = libc::MSG_WAITALL;
+        const DONTWAIT = libc::MSG_DONTWAIT;
+        const TRUNC = libc::MSG_TRUNC;
+        const CTRUNC = libc::MSG_CTRUNC;
         #[cfg_attr(docsrs, doc(cfg(not(any(target_os = "macos", target_os = "ios")))))]
         #[cfg(not(apple))]
         const NOSIGNAL = libc::MSG_NOSIGNAL;
+        #[cfg_attr(
+            docsrs,
+            doc(cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "dragonfly",
+                target_os = "openbsd",
+                target_os = "netbsd",
+            )))
+        )]
+        #[cfg(any(linuxlike, freebsdlike, netbsdlike))]
+        const CMSG_CLOEXEC = libc::MSG_CMSG_CLOEXEC;
     }
 }
 
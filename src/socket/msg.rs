@@ -0,0 +1,475 @@
+use crate::internal_prelude::*;
+
+use core::fmt;
+
+use super::{MsgFlag, SockAddr, Socket};
+use crate::{IoVec, IoVecMut};
+
+/// Get the number of bytes that a control message with `len` bytes of data will occupy in a
+/// control message buffer, including padding (i.e. `CMSG_SPACE()`).
+///
+/// This can be used to size the buffers passed to [`CmsgBuilder::new()`] and
+/// [`Socket::recvmsg()`].
+#[inline]
+pub const fn cmsg_space(len: usize) -> usize {
+    unsafe { libc::CMSG_SPACE(len as _) as usize }
+}
+
+#[inline]
+const fn cmsg_len(len: usize) -> usize {
+    unsafe { libc::CMSG_LEN(len as _) as usize }
+}
+
+/// Credentials of a process, as sent or received in an `SCM_CREDENTIALS` control message.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[cfg(linuxlike)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UCred {
+    pub pid: libc::pid_t,
+    pub uid: libc::uid_t,
+    pub gid: libc::gid_t,
+}
+
+#[cfg(linuxlike)]
+impl UCred {
+    /// Get the credentials of the current process (using the effective UID and GID).
+    ///
+    /// Unless the process is privileged, these are the only credentials that can be sent with
+    /// [`CmsgBuilder::credentials()`].
+    #[inline]
+    pub fn current() -> Self {
+        Self {
+            pid: crate::getpid(),
+            uid: crate::geteuid(),
+            gid: crate::getegid(),
+        }
+    }
+}
+
+#[cfg(linuxlike)]
+impl From<libc::ucred> for UCred {
+    #[inline]
+    fn from(c: libc::ucred) -> Self {
+        Self {
+            pid: c.pid,
+            uid: c.uid,
+            gid: c.gid,
+        }
+    }
+}
+
+#[cfg(linuxlike)]
+impl From<UCred> for libc::ucred {
+    #[inline]
+    fn from(c: UCred) -> Self {
+        Self {
+            pid: c.pid,
+            uid: c.uid,
+            gid: c.gid,
+        }
+    }
+}
+
+/// A helper to construct a buffer of control messages to send with [`Socket::sendmsg()`].
+///
+/// Each message occupies [`cmsg_space()`] bytes of the buffer (where the argument is the length
+/// of the message data). If the buffer is too small, the methods that add messages fail with
+/// `ERANGE` (and the buffer is left unchanged).
+///
+/// # Example
+///
+/// ```
+/// # use slibc::{cmsg_space, CmsgBuilder, IoVec, MsgFlag, Socket, SockDomain, SockType};
+/// let (a, b) = Socket::pair(SockDomain::UNIX, SockType::STREAM, None).unwrap();
+///
+/// let mut buf = [0; cmsg_space(core::mem::size_of::<i32>())];
+/// let mut cmsgs = CmsgBuilder::new(&mut buf);
+/// cmsgs.rights(&[0]).unwrap();
+///
+/// a.sendmsg(&[IoVec::new(b"a")], Some(&cmsgs), MsgFlag::empty(), None).unwrap();
+/// ```
+pub struct CmsgBuilder<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> CmsgBuilder<'a> {
+    /// Create a new builder that will store control messages in `buf`.
+    #[inline]
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// Add a control message with the given `level`, `ty`, and `data`.
+    pub fn push_raw(&mut self, level: i32, ty: i32, data: &[u8]) -> Result<()> {
+        let space = cmsg_space(data.len());
+        if self.buf.len() - self.len < space {
+            return Err(Error::from_code(libc::ERANGE));
+        }
+
+        let msgbuf = &mut self.buf[self.len..self.len + space];
+        for b in msgbuf.iter_mut() {
+            *b = 0;
+        }
+
+        let mut hdr: libc::cmsghdr = unsafe { core::mem::zeroed() };
+        hdr.cmsg_len = cmsg_len(data.len()) as _;
+        hdr.cmsg_level = level;
+        hdr.cmsg_type = ty;
+        unsafe {
+            core::ptr::write_unaligned(msgbuf.as_mut_ptr() as *mut libc::cmsghdr, hdr);
+        }
+
+        msgbuf[cmsg_len(0)..cmsg_len(data.len())].copy_from_slice(data);
+
+        self.len += space;
+        Ok(())
+    }
+
+    /// Add an `SCM_RIGHTS` message to send the given file descriptors.
+    #[inline]
+    pub fn rights(&mut self, fds: &[RawFd]) -> Result<()> {
+        self.push_raw(libc::SOL_SOCKET, libc::SCM_RIGHTS, unsafe {
+            core::slice::from_raw_parts(fds.as_ptr() as *const u8, core::mem::size_of_val(fds))
+        })
+    }
+
+    /// Add an `SCM_CREDENTIALS` message to send the given credentials.
+    ///
+    /// See [`UCred::current()`].
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    #[inline]
+    pub fn credentials(&mut self, cred: &UCred) -> Result<()> {
+        let cred = libc::ucred::from(*cred);
+        self.push_raw(libc::SOL_SOCKET, libc::SCM_CREDENTIALS, unsafe {
+            core::slice::from_raw_parts(
+                &cred as *const _ as *const u8,
+                core::mem::size_of_val(&cred),
+            )
+        })
+    }
+
+    /// Get the number of bytes of the buffer that have been filled with control messages.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether any control messages have been added.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all control messages that have been added.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl fmt::Debug for CmsgBuilder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CmsgBuilder")
+            .field("len", &self.len)
+            .field("capacity", &self.buf.len())
+            .finish()
+    }
+}
+
+/// A control message received with [`Socket::recvmsg()`].
+#[derive(Debug)]
+pub enum ControlMessage<'a> {
+    /// File descriptors received in an `SCM_RIGHTS` message.
+    Rights(CmsgFds<'a>),
+    /// Credentials received in an `SCM_CREDENTIALS` message.
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    Credentials(UCred),
+    /// Any other control message.
+    Other { level: i32, ty: i32, data: &'a [u8] },
+}
+
+/// An iterator over the file descriptors received in an `SCM_RIGHTS` message.
+///
+/// Each file descriptor is wrapped in a [`FileDesc`] as it is yielded. Any file descriptors that
+/// are not consumed are closed when the [`RecvMsg`] they came from is dropped.
+pub struct CmsgFds<'a>(&'a mut [u8]);
+
+impl Iterator for CmsgFds<'_> {
+    type Item = FileDesc;
+
+    fn next(&mut self) -> Option<FileDesc> {
+        while self.0.len() >= core::mem::size_of::<RawFd>() {
+            let (cur, rest) =
+                core::mem::take(&mut self.0).split_at_mut(core::mem::size_of::<RawFd>());
+            self.0 = rest;
+
+            let fd = unsafe { core::ptr::read_unaligned(cur.as_ptr() as *const RawFd) };
+            if fd >= 0 {
+                // Mark it as consumed so RecvMsg doesn't close it
+                unsafe {
+                    core::ptr::write_unaligned(cur.as_mut_ptr() as *mut RawFd, -1);
+                }
+                return Some(unsafe { FileDesc::new(fd) });
+            }
+        }
+
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.0.len() / core::mem::size_of::<RawFd>()))
+    }
+}
+
+impl fmt::Debug for CmsgFds<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(
+                self.0
+                    .chunks_exact(core::mem::size_of::<RawFd>())
+                    .map(|c| unsafe { core::ptr::read_unaligned(c.as_ptr() as *const RawFd) })
+                    .filter(|&fd| fd >= 0),
+            )
+            .finish()
+    }
+}
+
+/// An iterator over the control messages received with [`Socket::recvmsg()`].
+///
+/// This can be created with [`RecvMsg::cmsgs()`].
+pub struct CmsgIter<'a>(&'a mut [u8]);
+
+impl<'a> Iterator for CmsgIter<'a> {
+    type Item = ControlMessage<'a>;
+
+    fn next(&mut self) -> Option<ControlMessage<'a>> {
+        let hdrlen = cmsg_len(0);
+
+        if self.0.len() < core::mem::size_of::<libc::cmsghdr>() {
+            return None;
+        }
+
+        let hdr = unsafe { core::ptr::read_unaligned(self.0.as_ptr() as *const libc::cmsghdr) };
+        let len = hdr.cmsg_len as usize;
+        if len < hdrlen || len > self.0.len() {
+            self.0 = &mut [];
+            return None;
+        }
+
+        let buf = core::mem::take(&mut self.0);
+        let space = core::cmp::min(cmsg_space(len - hdrlen), buf.len());
+        let (cur, rest) = buf.split_at_mut(space);
+        self.0 = rest;
+
+        let data = &mut cur[hdrlen..len];
+
+        Some(match (hdr.cmsg_level, hdr.cmsg_type) {
+            (libc::SOL_SOCKET, libc::SCM_RIGHTS) => ControlMessage::Rights(CmsgFds(data)),
+
+            #[cfg(linuxlike)]
+            (libc::SOL_SOCKET, libc::SCM_CREDENTIALS)
+                if data.len() >= core::mem::size_of::<libc::ucred>() =>
+            {
+                ControlMessage::Credentials(UCred::from(unsafe {
+                    core::ptr::read_unaligned(data.as_ptr() as *const libc::ucred)
+                }))
+            }
+
+            (level, ty) => ControlMessage::Other { level, ty, data },
+        })
+    }
+}
+
+impl fmt::Debug for CmsgIter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CmsgIter")
+            .field("remaining", &self.0.len())
+            .finish()
+    }
+}
+
+/// The result of a [`Socket::recvmsg()`] call.
+///
+/// File descriptors received in `SCM_RIGHTS` messages that are not consumed via
+/// [`Self::cmsgs()`] are closed when this struct is dropped.
+pub struct RecvMsg<'a> {
+    nbytes: usize,
+    addr: Option<SockAddr>,
+    flags: MsgFlag,
+    cmsgs: &'a mut [u8],
+}
+
+impl RecvMsg<'_> {
+    /// The number of bytes of data that were received.
+    #[inline]
+    pub fn bytes(&self) -> usize {
+        self.nbytes
+    }
+
+    /// The address of the sender (if it is available).
+    #[inline]
+    pub fn addr(&self) -> Option<&SockAddr> {
+        self.addr.as_ref()
+    }
+
+    /// The flags set on the received message.
+    ///
+    /// [`MsgFlag::TRUNC`] indicates that the data was truncated because the buffers were too
+    /// small, and [`MsgFlag::CTRUNC`] indicates that some control data was discarded because the
+    /// control message buffer was too small.
+    #[inline]
+    pub fn flags(&self) -> MsgFlag {
+        self.flags
+    }
+
+    /// Create an iterator over the control messages that were received.
+    #[inline]
+    pub fn cmsgs(&mut self) -> CmsgIter<'_> {
+        CmsgIter(self.cmsgs)
+    }
+}
+
+impl Drop for RecvMsg<'_> {
+    fn drop(&mut self) {
+        for cmsg in self.cmsgs() {
+            if let ControlMessage::Rights(fds) = cmsg {
+                fds.for_each(drop);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RecvMsg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RecvMsg")
+            .field("bytes", &self.nbytes)
+            .field("addr", &self.addr)
+            .field("flags", &self.flags)
+            .finish()
+    }
+}
+
+impl Socket {
+    /// Send a message on this socket, optionally with control messages (see [`CmsgBuilder`]).
+    ///
+    /// If `addr` is not `None`, the message is sent to the given address (like with
+    /// [`Self::sendto()`]).
+    pub fn sendmsg(
+        &self,
+        iov: &[IoVec],
+        cmsgs: Option<&CmsgBuilder>,
+        flags: MsgFlag,
+        addr: Option<&SockAddr>,
+    ) -> Result<usize> {
+        let mut msg: libc::msghdr = unsafe { core::mem::zeroed() };
+
+        if let Some(addr) = addr {
+            let (addr, addrlen) = addr.as_raw();
+            msg.msg_name = addr as *mut _;
+            msg.msg_namelen = addrlen;
+        }
+
+        msg.msg_iov = iov.as_ptr() as *mut _;
+        msg.msg_iovlen = iov.len() as _;
+
+        if let Some(cmsgs) = cmsgs.filter(|c| !c.is_empty()) {
+            msg.msg_control = cmsgs.buf.as_ptr() as *mut _;
+            msg.msg_controllen = cmsgs.len as _;
+        }
+
+        Error::unpack_size(unsafe { libc::sendmsg(self.0.fd(), &msg, flags.bits()) })
+    }
+
+    /// Receive a message on this socket, along with any control messages.
+    ///
+    /// Control messages are stored in `cmsg_buf` and can be examined with [`RecvMsg::cmsgs()`].
+    /// See [`cmsg_space()`] for help sizing this buffer. Pass [`MsgFlag::CMSG_CLOEXEC`] (where
+    /// supported) to set the close-on-exec flag on file descriptors received via `SCM_RIGHTS`.
+    pub fn recvmsg<'a>(
+        &self,
+        iov: &mut [IoVecMut],
+        cmsg_buf: &'a mut [u8],
+        flags: MsgFlag,
+    ) -> Result<RecvMsg<'a>> {
+        let mut addr = MaybeUninit::<libc::sockaddr_storage>::zeroed();
+
+        let mut msg: libc::msghdr = unsafe { core::mem::zeroed() };
+        msg.msg_name = addr.as_mut_ptr() as *mut _;
+        msg.msg_namelen = core::mem::size_of::<libc::sockaddr_storage>() as _;
+        msg.msg_iov = iov.as_mut_ptr() as *mut _;
+        msg.msg_iovlen = iov.len() as _;
+
+        if !cmsg_buf.is_empty() {
+            msg.msg_control = cmsg_buf.as_mut_ptr() as *mut _;
+            msg.msg_controllen = cmsg_buf.len() as _;
+        }
+
+        let nbytes =
+            Error::unpack_size(unsafe { libc::recvmsg(self.0.fd(), &mut msg, flags.bits()) })?;
+
+        let controllen = core::cmp::min(msg.msg_controllen as usize, cmsg_buf.len());
+
+        // Construct this first so any file descriptors will be closed if parsing the address fails
+        let mut res = RecvMsg {
+            nbytes,
+            addr: None,
+            flags: MsgFlag::from_bits_truncate(msg.msg_flags),
+            cmsgs: &mut cmsg_buf[..controllen],
+        };
+
+        if msg.msg_namelen != 0 {
+            res.addr = Some(SockAddr::from_raw(
+                unsafe { addr.assume_init() },
+                msg.msg_namelen,
+            )?);
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cmsg_builder_parse() {
+        let mut buf = [0; cmsg_space(3) + cmsg_space(1)];
+
+        let mut builder = CmsgBuilder::new(&mut buf);
+        assert!(builder.is_empty());
+        builder.push_raw(1, 2, b"abc").unwrap();
+        assert_eq!(builder.len(), cmsg_space(3));
+        assert_eq!(
+            builder.push_raw(3, 4, b"defghijkl").unwrap_err(),
+            Errno::ERANGE
+        );
+        builder.push_raw(3, 4, b"d").unwrap();
+        assert_eq!(builder.len(), cmsg_space(3) + cmsg_space(1));
+        assert_eq!(builder.push_raw(5, 6, b"").unwrap_err(), Errno::ERANGE);
+
+        let mut it = CmsgIter(&mut buf);
+        match it.next().unwrap() {
+            ControlMessage::Other { level, ty, data } => {
+                assert_eq!((level, ty, data), (1, 2, &b"abc"[..]));
+            }
+            m => panic!("{:?}", m),
+        }
+        match it.next().unwrap() {
+            ControlMessage::Other { level, ty, data } => {
+                assert_eq!((level, ty, data), (3, 4, &b"d"[..]));
+            }
+            m => panic!("{:?}", m),
+        }
+        assert!(it.next().is_none());
+
+        let mut builder = CmsgBuilder::new(&mut buf);
+        builder.rights(&[-1]).unwrap();
+        builder.clear();
+        assert!(builder.is_empty());
+    }
+}
@@ -0,0 +1,505 @@
+use core::fmt;
+use core::ptr::NonNull;
+
+use crate::internal_prelude::*;
+
+use super::{SockAddr, SockDomain, SockProto, SockType};
+
+bitflags::bitflags! {
+    /// Flags for [`AddrInfoHints`].
+    #[derive(Default)]
+    pub struct AddrInfoFlags: libc::c_int {
+        /// Return addresses suitable for `bind()`ing a listening socket.
+        ///
+        /// If this is specified and `node` is `None`, the returned addresses will be the wildcard
+        /// address.
+        const PASSIVE = libc::AI_PASSIVE;
+        /// Request the canonical name of the host (see [`AddrInfo::canonname()`]).
+        const CANONNAME = libc::AI_CANONNAME;
+        /// `node` must be a numeric address; no name resolution will be performed.
+        const NUMERICHOST = libc::AI_NUMERICHOST;
+        /// `service` must be a numeric port number; no service name lookup will be performed.
+        const NUMERICSERV = libc::AI_NUMERICSERV;
+        /// Only return IPv4/IPv6 addresses if the system has at least one address of that
+        /// family configured.
+        const ADDRCONFIG = libc::AI_ADDRCONFIG;
+        /// If IPv6 addresses were requested and none were found, return IPv4-mapped IPv6
+        /// addresses.
+        #[cfg_attr(docsrs, doc(cfg(not(any(target_os = "netbsd", target_os = "openbsd")))))]
+        #[cfg(not(netbsdlike))]
+        const V4MAPPED = libc::AI_V4MAPPED;
+        /// If specified with `V4MAPPED`, return both IPv6 and IPv4-mapped IPv6 addresses.
+        #[cfg_attr(docsrs, doc(cfg(not(any(target_os = "netbsd", target_os = "openbsd")))))]
+        #[cfg(not(netbsdlike))]
+        const ALL = libc::AI_ALL;
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags for [`getnameinfo()`].
+    #[derive(Default)]
+    pub struct NameInfoFlags: libc::c_int {
+        /// Only return the hostname part of the FQDN for local hosts.
+        const NOFQDN = libc::NI_NOFQDN;
+        /// Return the numeric form of the host address instead of its name.
+        const NUMERICHOST = libc::NI_NUMERICHOST;
+        /// Fail with `EAI_NONAME` if the hostname cannot be determined.
+        const NAMEREQD = libc::NI_NAMEREQD;
+        /// Return the numeric form of the port number instead of the service name.
+        const NUMERICSERV = libc::NI_NUMERICSERV;
+        /// Look up the service name for a datagram (UDP) service instead of a stream (TCP)
+        /// service.
+        const DGRAM = libc::NI_DGRAM;
+    }
+}
+
+/// Represents an error returned by [`getaddrinfo()`] or [`getnameinfo()`].
+///
+/// These functions report errors using a separate set of `EAI_*` error codes (see
+/// `gai_strerror(3)`). If the error code is `EAI_SYSTEM`, the underlying OS error can be retrieved
+/// with [`Self::os_error()`].
+#[derive(Clone, Eq, PartialEq)]
+pub struct GaiError {
+    code: i32,
+    eno: i32,
+}
+
+impl GaiError {
+    #[inline]
+    fn new(code: i32) -> Self {
+        Self {
+            code,
+            eno: if code == libc::EAI_SYSTEM {
+                errno_get()
+            } else {
+                0
+            },
+        }
+    }
+
+    /// Get the `EAI_*` error code represented by this `GaiError` object.
+    #[inline]
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// If the error code is `EAI_SYSTEM`, get the OS error that caused the failure.
+    #[inline]
+    pub fn os_error(&self) -> Option<Error> {
+        if self.code == libc::EAI_SYSTEM {
+            Some(Error::from_code(self.eno))
+        } else {
+            None
+        }
+    }
+
+    fn strerror(&self) -> &'static str {
+        let msg = unsafe { util::bytes_from_ptr(libc::gai_strerror(self.code)) };
+        core::str::from_utf8(msg).unwrap_or("Unknown error")
+    }
+}
+
+impl fmt::Display for GaiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.os_error() {
+            Some(e) => fmt::Display::fmt(&e, f),
+            None => {
+                f.write_str(self.strerror())?;
+                write!(f, " (code {})", self.code)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for GaiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut ds = f.debug_struct("GaiError");
+        ds.field("code", &self.code)
+            .field("message", &self.strerror());
+        if let Some(e) = self.os_error() {
+            ds.field("os_error", &e);
+        }
+        ds.finish()
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+impl std::error::Error for GaiError {}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(feature = "std")]
+impl From<GaiError> for std::io::Error {
+    #[inline]
+    fn from(e: GaiError) -> Self {
+        match e.os_error() {
+            Some(e) => e.into(),
+            None => Self::other(e),
+        }
+    }
+}
+
+/// Hints used to restrict the addresses returned by [`getaddrinfo()`].
+///
+/// A value of `None` for any of `family`, `socktype`, or `protocol` indicates that any value is
+/// acceptable.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct AddrInfoHints {
+    pub flags: AddrInfoFlags,
+    pub family: Option<SockDomain>,
+    pub socktype: Option<SockType>,
+    pub protocol: Option<SockProto>,
+}
+
+/// Resolve the given host and/or service names into a list of socket addresses.
+///
+/// At least one of `node` and `service` must be specified. `node` may be a hostname or a numeric
+/// IPv4/IPv6 address; `service` may be a service name (e.g. `http`) or a numeric port number.
+///
+/// Entries whose address family is not supported by [`SockAddr`] are skipped when iterating over
+/// the returned list.
+pub fn getaddrinfo(
+    node: Option<&CStr>,
+    service: Option<&CStr>,
+    hints: Option<&AddrInfoHints>,
+) -> core::result::Result<AddrInfoList, GaiError> {
+    let mut raw_hints: libc::addrinfo = unsafe { core::mem::zeroed() };
+    if let Some(hints) = hints {
+        raw_hints.ai_flags = hints.flags.bits();
+        raw_hints.ai_family = hints.family.map_or(libc::AF_UNSPEC, |f| f as _);
+        raw_hints.ai_socktype = hints.socktype.map_or(0, |t| t as _);
+        raw_hints.ai_protocol = hints.protocol.map_or(0, |p| p as _);
+    }
+
+    let mut res = core::ptr::null_mut();
+
+    match unsafe {
+        libc::getaddrinfo(
+            node.map_or_else(core::ptr::null, |s| s.as_ptr()),
+            service.map_or_else(core::ptr::null, |s| s.as_ptr()),
+            if hints.is_some() {
+                &raw_hints
+            } else {
+                core::ptr::null()
+            },
+            &mut res,
+        )
+    } {
+        0 => Ok(AddrInfoList {
+            // Some implementations may return success with an empty list
+            head: NonNull::new(res),
+        }),
+        code => Err(GaiError::new(code)),
+    }
+}
+
+/// A list of addresses returned by [`getaddrinfo()`].
+///
+/// The list is freed with `freeaddrinfo()` when this struct is dropped.
+#[derive(Debug)]
+pub struct AddrInfoList {
+    head: Option<NonNull<libc::addrinfo>>,
+}
+
+impl AddrInfoList {
+    /// Iterate over the entries in this list.
+    #[inline]
+    pub fn iter(&self) -> AddrInfoIter<'_> {
+        AddrInfoIter {
+            cur: self.head.map(|p| unsafe { &*p.as_ptr() }),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a AddrInfoList {
+    type Item = AddrInfo<'a>;
+    type IntoIter = AddrInfoIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Drop for AddrInfoList {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(head) = self.head {
+            unsafe {
+                libc::freeaddrinfo(head.as_ptr());
+            }
+        }
+    }
+}
+
+unsafe impl Send for AddrInfoList {}
+unsafe impl Sync for AddrInfoList {}
+
+/// An iterator over the entries in an [`AddrInfoList`].
+#[derive(Clone, Debug)]
+pub struct AddrInfoIter<'a> {
+    cur: Option<&'a libc::addrinfo>,
+}
+
+impl<'a> Iterator for AddrInfoIter<'a> {
+    type Item = AddrInfo<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(ai) = self.cur.take() {
+            self.cur = unsafe { ai.ai_next.as_ref() };
+
+            if ai.ai_addr.is_null() {
+                continue;
+            }
+
+            if let Ok(addr) = unsafe { SockAddr::from_ptr(ai.ai_addr, ai.ai_addrlen as _) } {
+                return Some(AddrInfo { ai, addr });
+            }
+        }
+
+        None
+    }
+}
+
+impl core::iter::FusedIterator for AddrInfoIter<'_> {}
+
+/// A single entry returned by [`getaddrinfo()`].
+#[derive(Clone)]
+pub struct AddrInfo<'a> {
+    ai: &'a libc::addrinfo,
+    addr: SockAddr,
+}
+
+impl<'a> AddrInfo<'a> {
+    /// The socket address of this entry.
+    #[inline]
+    pub fn addr(&self) -> &SockAddr {
+        &self.addr
+    }
+
+    /// The address family of this entry.
+    #[inline]
+    pub fn family(&self) -> SockDomain {
+        self.addr.family()
+    }
+
+    /// The socket type of this entry, if it is one of the types defined in [`SockType`].
+    #[inline]
+    pub fn socktype(&self) -> Option<SockType> {
+        SockType::from_raw(self.ai.ai_socktype)
+    }
+
+    /// The protocol of this entry, if it is one of the protocols defined in [`SockProto`].
+    #[inline]
+    pub fn protocol(&self) -> Option<SockProto> {
+        SockProto::from_raw(self.ai.ai_protocol)
+    }
+
+    /// The canonical name of the host.
+    ///
+    /// This is only set on the first entry, and only if [`AddrInfoFlags::CANONNAME`] was
+    /// specified.
+    #[inline]
+    pub fn canonname(&self) -> Option<&'a CStr> {
+        if self.ai.ai_canonname.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(self.ai.ai_canonname) })
+        }
+    }
+}
+
+impl fmt::Debug for AddrInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AddrInfo")
+            .field("addr", &self.addr)
+            .field("socktype", &self.socktype())
+            .field("protocol", &self.protocol())
+            .field("canonname", &self.canonname())
+            .finish()
+    }
+}
+
+/// Translate the given socket address into a host name and a service name.
+///
+/// The host name is stored in `host`, and the service name is stored in `serv`. If either buffer
+/// is empty, the corresponding name is not looked up (and an empty string is returned in its
+/// place). If a buffer is too small to hold the name, this fails with `EAI_OVERFLOW`.
+///
+/// `NI_MAXHOST` (1025) and `NI_MAXSERV` (32) are the traditional buffer sizes used for `host` and
+/// `serv`.
+pub fn getnameinfo<'a, 'b>(
+    addr: &SockAddr,
+    host: &'a mut [u8],
+    serv: &'b mut [u8],
+    flags: NameInfoFlags,
+) -> core::result::Result<(&'a CStr, &'b CStr), GaiError> {
+    let (addr, addrlen) = addr.as_raw();
+
+    fn buf_args(buf: &mut [u8]) -> (*mut libc::c_char, usize) {
+        if buf.is_empty() {
+            (core::ptr::null_mut(), 0)
+        } else {
+            (buf.as_mut_ptr() as *mut _, buf.len())
+        }
+    }
+
+    let (host_ptr, host_len) = buf_args(host);
+    let (serv_ptr, serv_len) = buf_args(serv);
+
+    match unsafe {
+        libc::getnameinfo(
+            addr,
+            addrlen,
+            host_ptr,
+            host_len as _,
+            serv_ptr,
+            serv_len as _,
+            flags.bits(),
+        )
+    } {
+        0 => {
+            let empty = unsafe { CStr::from_bytes_with_nul_unchecked(b"\0") };
+            Ok((
+                util::cstr_from_buf(host).unwrap_or(empty),
+                util::cstr_from_buf(serv).unwrap_or(empty),
+            ))
+        }
+        code => Err(GaiError::new(code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Inet4Addr, Inet4SockAddr, Inet6Addr, Inet6SockAddr};
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_getaddrinfo_numeric() {
+        let hints = AddrInfoHints {
+            flags: AddrInfoFlags::NUMERICHOST | AddrInfoFlags::NUMERICSERV,
+            socktype: Some(SockType::STREAM),
+            ..Default::default()
+        };
+
+        let list = getaddrinfo(
+            Some(CStr::from_bytes_with_nul(b"127.0.0.1\0").unwrap()),
+            Some(CStr::from_bytes_with_nul(b"8080\0").unwrap()),
+            Some(&hints),
+        )
+        .unwrap();
+        let entries: Vec<_> = list.iter().collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].addr(),
+            &SockAddr::Inet4(Inet4SockAddr::new(Inet4Addr::LOCALHOST, 8080))
+        );
+        assert_eq!(entries[0].family(), SockDomain::INET);
+        assert_eq!(entries[0].socktype(), Some(SockType::STREAM));
+        assert!(entries[0].canonname().is_none());
+
+        let list = getaddrinfo(
+            Some(CStr::from_bytes_with_nul(b"::1\0").unwrap()),
+            Some(CStr::from_bytes_with_nul(b"53\0").unwrap()),
+            Some(&AddrInfoHints {
+                socktype: Some(SockType::DGRAM),
+                ..hints
+            }),
+        )
+        .unwrap();
+        let entries: Vec<_> = (&list).into_iter().collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            entries[0].addr(),
+            &SockAddr::Inet6(Inet6SockAddr::new(Inet6Addr::LOCALHOST, 53, 0, 0))
+        );
+        assert_eq!(entries[0].socktype(), Some(SockType::DGRAM));
+
+        // Passive with no node -> wildcard address
+        let list = getaddrinfo(
+            None,
+            Some(CStr::from_bytes_with_nul(b"1234\0").unwrap()),
+            Some(&AddrInfoHints {
+                flags: AddrInfoFlags::PASSIVE | AddrInfoFlags::NUMERICSERV,
+                family: Some(SockDomain::INET),
+                socktype: Some(SockType::STREAM),
+                protocol: None,
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            list.iter().next().unwrap().addr(),
+            &SockAddr::Inet4(Inet4SockAddr::new(Inet4Addr::UNSPECIFIED, 1234))
+        );
+
+        // Not numeric
+        let err = getaddrinfo(
+            Some(CStr::from_bytes_with_nul(b"localhost\0").unwrap()),
+            None,
+            Some(&hints),
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), libc::EAI_NONAME);
+        assert!(err.os_error().is_none());
+        assert!(!err.to_string().is_empty());
+
+        // Neither node nor service
+        assert_eq!(
+            getaddrinfo(None, None, None).unwrap_err().code(),
+            libc::EAI_NONAME
+        );
+    }
+
+    #[test]
+    fn test_getaddrinfo_localhost() {
+        let list = getaddrinfo(
+            Some(CStr::from_bytes_with_nul(b"localhost\0").unwrap()),
+            None,
+            Some(&AddrInfoHints {
+                family: Some(SockDomain::INET),
+                socktype: Some(SockType::STREAM),
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+
+        for entry in &list {
+            assert_eq!(entry.family(), SockDomain::INET);
+            assert!(entry.addr().unwrap_inet4().ip().is_loopback());
+        }
+    }
+
+    #[test]
+    fn test_getnameinfo() {
+        let addr = SockAddr::Inet4(Inet4SockAddr::new(Inet4Addr::LOCALHOST, 8080));
+
+        let mut host = [0; 1025];
+        let mut serv = [0; 32];
+        let (h, s) = getnameinfo(
+            &addr,
+            &mut host,
+            &mut serv,
+            NameInfoFlags::NUMERICHOST | NameInfoFlags::NUMERICSERV,
+        )
+        .unwrap();
+        assert_eq!(h.to_bytes(), b"127.0.0.1");
+        assert_eq!(s.to_bytes(), b"8080");
+
+        let (h, s) = getnameinfo(&addr, &mut [], &mut serv, NameInfoFlags::NUMERICSERV).unwrap();
+        assert_eq!(h.to_bytes(), b"");
+        assert_eq!(s.to_bytes(), b"8080");
+
+        let addr = SockAddr::Inet6(Inet6SockAddr::new(Inet6Addr::LOCALHOST, 22, 0, 0));
+        let (h, _) = getnameinfo(&addr, &mut host, &mut [], NameInfoFlags::NUMERICHOST).unwrap();
+        assert_eq!(h.to_bytes(), b"::1");
+
+        assert_eq!(
+            getnameinfo(&addr, &mut [0; 2], &mut [], NameInfoFlags::NUMERICHOST)
+                .unwrap_err()
+                .code(),
+            libc::EAI_OVERFLOW
+        );
+    }
+}
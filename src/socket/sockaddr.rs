@@ -33,7 +33,7 @@ impl Inet4SockAddr {
     pub fn new(ip: Inet4Addr, port: u16) -> Self {
         Self(libc::sockaddr_in {
             sin_family: libc::AF_INET as _,
-            sin_port: port,
+            sin_port: port.to_be(),
             sin_zero: [0; 8],
             sin_addr: ip.0,
             #[cfg(bsd)]
@@ -56,13 +56,13 @@ impl Inet4SockAddr {
     /// Get the port number associated with this address.
     #[inline]
     pub fn port(&self) -> u16 {
-        self.0.sin_port
+        u16::from_be(self.0.sin_port)
     }
 
     /// Set the port number associated with this address.
     #[inline]
     pub fn set_port(&mut self, port: u16) {
-        self.0.sin_port = port;
+        self.0.sin_port = port.to_be();
     }
 
     #[inline]
@@ -169,7 +169,7 @@ impl Inet6SockAddr {
     pub const fn new(ip: Inet6Addr, port: u16, flowinfo: u32, scope_id: u32) -> Self {
         Self(libc::sockaddr_in6 {
             sin6_family: libc::AF_INET6 as _,
-            sin6_port: port,
+            sin6_port: port.to_be(),
             sin6_flowinfo: flowinfo,
             sin6_scope_id: scope_id,
             sin6_addr: ip.0,
@@ -181,13 +181,13 @@ impl Inet6SockAddr {
     /// Get the port number associated with this address.
     #[inline]
     pub fn port(&self) -> u16 {
-        self.0.sin6_port
+        u16::from_be(self.0.sin6_port)
     }
 
     /// Set the port number associated with this address.
     #[inline]
     pub fn set_port(&mut self, port: u16) {
-        self.0.sin6_port = port;
+        self.0.sin6_port = port.to_be();
     }
 
     /// Get the IP address associated with this socket address.
@@ -562,6 +562,18 @@ impl SockAddr {
         }
     }
 
+    /// Construct a `SockAddr` by copying `len` bytes from the socket address at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid socket address at least `len` bytes long.
+    pub(crate) unsafe fn from_ptr(ptr: *const libc::sockaddr, len: usize) -> Result<Self> {
+        let mut storage = core::mem::zeroed::<libc::sockaddr_storage>();
+        let len = core::cmp::min(len, core::mem::size_of::<libc::sockaddr_storage>());
+        core::ptr::copy_nonoverlapping(ptr as *const u8, &mut storage as *mut _ as *mut u8, len);
+        Self::from_raw(storage, len as _)
+    }
+
     #[inline]
     pub fn as_raw(&self) -> (*const libc::sockaddr, libc::socklen_t) {
         match self {
@@ -734,6 +746,7 @@ mod tests {
         let addr = Inet4SockAddr::new(Inet4Addr::new(1, 2, 3, 4), 80);
         assert_eq!(addr.ip(), Inet4Addr::new(1, 2, 3, 4));
         assert_eq!(addr.port(), 80);
+        assert_eq!(addr.0.sin_port, 80u16.to_be());
 
         let mut addr2 = Inet4SockAddr::new(Inet4Addr::LOCALHOST, 0);
         addr2.set_ip(Inet4Addr::new(1, 2, 3, 4));
@@ -782,6 +795,7 @@ mod tests {
         let addr = Inet6SockAddr::new(Inet6Addr::new(8193, 3512, 0, 0, 0, 0, 0, 1), 80, 0, 0);
         assert_eq!(addr.ip(), Inet6Addr::new(8193, 3512, 0, 0, 0, 0, 0, 1));
         assert_eq!(addr.port(), 80);
+        assert_eq!(addr.0.sin6_port, 80u16.to_be());
 
         let mut addr2 = Inet6SockAddr::new(Inet6Addr::LOCALHOST, 0, 0, 0);
         addr2.set_ip(Inet6Addr::new(8193, 3512, 0, 0, 0, 0, 0, 1));
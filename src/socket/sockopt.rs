@@ -0,0 +1,503 @@
+use crate::internal_prelude::*;
+
+use core::convert::TryInto;
+
+use super::{Inet4Addr, Inet6Addr, SockType, Socket};
+use crate::Timeval;
+
+#[cfg(linuxlike)]
+use super::UCred;
+
+macro_rules! sockopt_bool {
+    ($($(#[$attr:meta])* $get:ident, $set:ident, $level:expr, $name:expr;)*) => {
+        $(
+            $(#[$attr])*
+            #[inline]
+            pub fn $get(&self) -> Result<bool> {
+                Ok(self.getsockopt_int($level, $name)? != 0)
+            }
+
+            $(#[$attr])*
+            #[inline]
+            pub fn $set(&self, val: bool) -> Result<()> {
+                self.setsockopt_int($level, $name, val as _)
+            }
+        )*
+    };
+}
+
+macro_rules! sockopt_int {
+    ($($(#[$attr:meta])* $get:ident, $set:ident, $level:expr, $name:expr, $type:ty;)*) => {
+        $(
+            $(#[$attr])*
+            #[inline]
+            pub fn $get(&self) -> Result<$type> {
+                Ok(self.getsockopt_int($level, $name)? as _)
+            }
+
+            $(#[$attr])*
+            #[inline]
+            pub fn $set(&self, val: $type) -> Result<()> {
+                self.setsockopt_int($level, $name, val.try_into().unwrap_or(libc::c_int::MAX))
+            }
+        )*
+    };
+}
+
+impl Socket {
+    #[inline]
+    fn getsockopt_int(&self, level: i32, name: i32) -> Result<libc::c_int> {
+        let mut val = 0;
+        unsafe {
+            self.getsockopt_raw(level, name, core::slice::from_mut(&mut val))?;
+        }
+        Ok(val)
+    }
+
+    #[inline]
+    fn setsockopt_int(&self, level: i32, name: i32, val: libc::c_int) -> Result<()> {
+        unsafe { self.setsockopt_raw(level, name, &[val]) }
+    }
+
+    #[inline]
+    fn getsockopt_timeval(&self, name: i32) -> Result<Option<Timeval>> {
+        let mut tv = Timeval {
+            tv_sec: 0,
+            tv_usec: 0,
+        };
+        unsafe {
+            self.getsockopt_raw(libc::SOL_SOCKET, name, core::slice::from_mut(&mut tv))?;
+        }
+
+        Ok(if tv.tv_sec == 0 && tv.tv_usec == 0 {
+            None
+        } else {
+            Some(tv)
+        })
+    }
+
+    #[inline]
+    fn setsockopt_timeval(&self, name: i32, tv: Option<Timeval>) -> Result<()> {
+        let tv = tv.unwrap_or(Timeval {
+            tv_sec: 0,
+            tv_usec: 0,
+        });
+        unsafe { self.setsockopt_raw(libc::SOL_SOCKET, name, &[tv]) }
+    }
+
+    sockopt_bool! {
+        /// The `SO_REUSEADDR` option.
+        reuseaddr, set_reuseaddr, libc::SOL_SOCKET, libc::SO_REUSEADDR;
+        /// The `SO_REUSEPORT` option.
+        reuseport, set_reuseport, libc::SOL_SOCKET, libc::SO_REUSEPORT;
+        /// The `SO_KEEPALIVE` option.
+        keepalive, set_keepalive, libc::SOL_SOCKET, libc::SO_KEEPALIVE;
+        /// The `SO_PASSCRED` option.
+        ///
+        /// If this is enabled, `SCM_CREDENTIALS` messages can be received with
+        /// [`Self::recvmsg()`].
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        passcred, set_passcred, libc::SOL_SOCKET, libc::SO_PASSCRED;
+        /// The `TCP_NODELAY` option.
+        tcp_nodelay, set_tcp_nodelay, libc::IPPROTO_TCP, libc::TCP_NODELAY;
+        /// The `IPV6_V6ONLY` option.
+        ipv6_v6only, set_ipv6_v6only, libc::IPPROTO_IPV6, libc::IPV6_V6ONLY;
+    }
+
+    sockopt_int! {
+        /// The `SO_RCVBUF` option.
+        ///
+        /// Note that on Linux, the kernel doubles the value set with [`Self::set_rcvbuf()`] (to
+        /// allow space for bookkeeping), and this doubled value is returned by [`Self::rcvbuf()`].
+        rcvbuf, set_rcvbuf, libc::SOL_SOCKET, libc::SO_RCVBUF, usize;
+        /// The `SO_SNDBUF` option.
+        ///
+        /// See [`Self::rcvbuf()`] for some caveats.
+        sndbuf, set_sndbuf, libc::SOL_SOCKET, libc::SO_SNDBUF, usize;
+        /// The `TCP_KEEPINTVL` option (the interval, in seconds, between keepalive probes).
+        #[cfg_attr(
+            docsrs,
+            doc(cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "dragonfly",
+                target_os = "netbsd",
+                target_os = "macos",
+                target_os = "ios",
+            )))
+        )]
+        #[cfg(any(linuxlike, freebsdlike, target_os = "netbsd", apple))]
+        tcp_keepintvl, set_tcp_keepintvl, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL, u32;
+        /// The `TCP_KEEPCNT` option (the number of keepalive probes to send before dropping
+        /// the connection).
+        #[cfg_attr(
+            docsrs,
+            doc(cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "dragonfly",
+                target_os = "netbsd",
+                target_os = "macos",
+                target_os = "ios",
+            )))
+        )]
+        #[cfg(any(linuxlike, freebsdlike, target_os = "netbsd", apple))]
+        tcp_keepcnt, set_tcp_keepcnt, libc::IPPROTO_TCP, libc::TCP_KEEPCNT, u32;
+        /// The `IP_TTL` option.
+        ip_ttl, set_ip_ttl, libc::IPPROTO_IP, libc::IP_TTL, u32;
+    }
+
+    /// The `TCP_KEEPIDLE` option (the time, in seconds, that the connection must be idle before
+    /// keepalive probes are sent).
+    ///
+    /// On macOS, this uses the `TCP_KEEPALIVE` option.
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "macos",
+            target_os = "ios",
+        )))
+    )]
+    #[cfg(any(linuxlike, freebsdlike, target_os = "netbsd", apple))]
+    #[inline]
+    pub fn tcp_keepidle(&self) -> Result<u32> {
+        Ok(self.getsockopt_int(libc::IPPROTO_TCP, sys::TCP_KEEPIDLE)? as _)
+    }
+
+    /// Set the `TCP_KEEPIDLE` option.
+    ///
+    /// See [`Self::tcp_keepidle()`].
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "macos",
+            target_os = "ios",
+        )))
+    )]
+    #[cfg(any(linuxlike, freebsdlike, target_os = "netbsd", apple))]
+    #[inline]
+    pub fn set_tcp_keepidle(&self, secs: u32) -> Result<()> {
+        self.setsockopt_int(
+            libc::IPPROTO_TCP,
+            sys::TCP_KEEPIDLE,
+            secs.try_into().unwrap_or(libc::c_int::MAX),
+        )
+    }
+
+    /// The `SO_RCVTIMEO` option.
+    ///
+    /// `None` indicates that receive operations will never time out.
+    #[inline]
+    pub fn rcvtimeo(&self) -> Result<Option<Timeval>> {
+        self.getsockopt_timeval(libc::SO_RCVTIMEO)
+    }
+
+    /// Set the `SO_RCVTIMEO` option.
+    ///
+    /// If `timeout` is `None` (or a zero `Timeval`), receive operations will never time out.
+    #[inline]
+    pub fn set_rcvtimeo(&self, timeout: Option<Timeval>) -> Result<()> {
+        self.setsockopt_timeval(libc::SO_RCVTIMEO, timeout)
+    }
+
+    /// The `SO_SNDTIMEO` option.
+    ///
+    /// `None` indicates that send operations will never time out.
+    #[inline]
+    pub fn sndtimeo(&self) -> Result<Option<Timeval>> {
+        self.getsockopt_timeval(libc::SO_SNDTIMEO)
+    }
+
+    /// Set the `SO_SNDTIMEO` option.
+    ///
+    /// If `timeout` is `None` (or a zero `Timeval`), send operations will never time out.
+    #[inline]
+    pub fn set_sndtimeo(&self, timeout: Option<Timeval>) -> Result<()> {
+        self.setsockopt_timeval(libc::SO_SNDTIMEO, timeout)
+    }
+
+    /// The `SO_LINGER` option.
+    ///
+    /// If this is `Some(secs)`, then `close()` and `shutdown()` will block for up to `secs`
+    /// seconds until all queued data has been sent.
+    #[inline]
+    pub fn linger(&self) -> Result<Option<u32>> {
+        let mut linger = libc::linger {
+            l_onoff: 0,
+            l_linger: 0,
+        };
+        unsafe {
+            self.getsockopt_raw(
+                libc::SOL_SOCKET,
+                libc::SO_LINGER,
+                core::slice::from_mut(&mut linger),
+            )?;
+        }
+
+        Ok(if linger.l_onoff != 0 {
+            Some(linger.l_linger as _)
+        } else {
+            None
+        })
+    }
+
+    /// Set the `SO_LINGER` option.
+    ///
+    /// See [`Self::linger()`].
+    #[inline]
+    pub fn set_linger(&self, secs: Option<u32>) -> Result<()> {
+        let linger = libc::linger {
+            l_onoff: secs.is_some() as _,
+            l_linger: secs.unwrap_or(0).try_into().unwrap_or(libc::c_int::MAX),
+        };
+        unsafe { self.setsockopt_raw(libc::SOL_SOCKET, libc::SO_LINGER, &[linger]) }
+    }
+
+    /// Get and clear the pending socket error (`SO_ERROR`).
+    #[inline]
+    pub fn take_error(&self) -> Result<Option<Error>> {
+        let eno = self.getsockopt_int(libc::SOL_SOCKET, libc::SO_ERROR)?;
+        Ok(if eno == 0 {
+            None
+        } else {
+            Some(Error::from_code(eno))
+        })
+    }
+
+    /// Get the type of this socket (`SO_TYPE`).
+    ///
+    /// This fails with `EINVAL` if the socket's type is not one of the types defined in
+    /// [`SockType`].
+    #[inline]
+    pub fn sock_type(&self) -> Result<SockType> {
+        SockType::from_raw(self.getsockopt_int(libc::SOL_SOCKET, libc::SO_TYPE)?)
+            .ok_or_else(|| Error::from_code(libc::EINVAL))
+    }
+
+    /// Get the credentials of the peer connected to this socket (`SO_PEERCRED`).
+    ///
+    /// The credentials returned are the credentials from the time that `connect()` or
+    /// `socketpair()` was called (or `listen()`, for the peer of an accepted socket).
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    #[inline]
+    pub fn peercred(&self) -> Result<UCred> {
+        let mut cred = libc::ucred {
+            pid: 0,
+            uid: 0,
+            gid: 0,
+        };
+        unsafe {
+            self.getsockopt_raw(
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                core::slice::from_mut(&mut cred),
+            )?;
+        }
+        Ok(cred.into())
+    }
+
+    #[inline]
+    fn ip_membership(&self, name: i32, group: &Inet4Addr, iface: &Inet4Addr) -> Result<()> {
+        let mreq = libc::ip_mreq {
+            imr_multiaddr: group.0,
+            imr_interface: iface.0,
+        };
+        unsafe { self.setsockopt_raw(libc::IPPROTO_IP, name, &[mreq]) }
+    }
+
+    /// Join the given IPv4 multicast group (`IP_ADD_MEMBERSHIP`).
+    ///
+    /// `iface` is the address of the local interface to join the group on; if it is
+    /// [`Inet4Addr::UNSPECIFIED`], an appropriate interface is chosen by the system.
+    #[inline]
+    pub fn ip_add_membership(&self, group: &Inet4Addr, iface: &Inet4Addr) -> Result<()> {
+        self.ip_membership(libc::IP_ADD_MEMBERSHIP, group, iface)
+    }
+
+    /// Leave the given IPv4 multicast group (`IP_DROP_MEMBERSHIP`).
+    #[inline]
+    pub fn ip_drop_membership(&self, group: &Inet4Addr, iface: &Inet4Addr) -> Result<()> {
+        self.ip_membership(libc::IP_DROP_MEMBERSHIP, group, iface)
+    }
+
+    #[inline]
+    fn ipv6_membership(&self, name: i32, group: &Inet6Addr, ifindex: u32) -> Result<()> {
+        let mreq = libc::ipv6_mreq {
+            ipv6mr_multiaddr: group.0,
+            ipv6mr_interface: ifindex as _,
+        };
+        unsafe { self.setsockopt_raw(libc::IPPROTO_IPV6, name, &[mreq]) }
+    }
+
+    /// Join the given IPv6 multicast group (`IPV6_ADD_MEMBERSHIP`/`IPV6_JOIN_GROUP`).
+    ///
+    /// `ifindex` is the index of the local interface to join the group on; if it is 0, an
+    /// appropriate interface is chosen by the system.
+    #[inline]
+    pub fn ipv6_add_membership(&self, group: &Inet6Addr, ifindex: u32) -> Result<()> {
+        self.ipv6_membership(sys::IPV6_ADD_MEMBERSHIP, group, ifindex)
+    }
+
+    /// Leave the given IPv6 multicast group (`IPV6_DROP_MEMBERSHIP`/`IPV6_LEAVE_GROUP`).
+    #[inline]
+    pub fn ipv6_drop_membership(&self, group: &Inet6Addr, ifindex: u32) -> Result<()> {
+        self.ipv6_membership(sys::IPV6_DROP_MEMBERSHIP, group, ifindex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{SockDomain, SockProto};
+
+    #[test]
+    fn test_sockopt_bool() {
+        let sock = Socket::new(SockDomain::INET, SockType::STREAM, None).unwrap();
+
+        for &(get, set) in [
+            (
+                Socket::reuseaddr as fn(&Socket) -> Result<bool>,
+                Socket::set_reuseaddr as fn(&Socket, bool) -> Result<()>,
+            ),
+            (Socket::reuseport, Socket::set_reuseport),
+            (Socket::keepalive, Socket::set_keepalive),
+            (Socket::tcp_nodelay, Socket::set_tcp_nodelay),
+        ]
+        .iter()
+        {
+            assert!(!get(&sock).unwrap());
+            set(&sock, true).unwrap();
+            assert!(get(&sock).unwrap());
+            set(&sock, false).unwrap();
+            assert!(!get(&sock).unwrap());
+        }
+
+        let sock6 = Socket::new(SockDomain::INET6, SockType::STREAM, None).unwrap();
+        sock6.set_ipv6_v6only(true).unwrap();
+        assert!(sock6.ipv6_v6only().unwrap());
+        sock6.set_ipv6_v6only(false).unwrap();
+        assert!(!sock6.ipv6_v6only().unwrap());
+
+        #[cfg(linuxlike)]
+        {
+            let (a, _b) = Socket::pair(SockDomain::UNIX, SockType::STREAM, None).unwrap();
+            assert!(!a.passcred().unwrap());
+            a.set_passcred(true).unwrap();
+            assert!(a.passcred().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_sockopt_int() {
+        let sock = Socket::new(SockDomain::INET, SockType::STREAM, None).unwrap();
+
+        sock.set_rcvbuf(8192).unwrap();
+        assert!(sock.rcvbuf().unwrap() >= 8192);
+        sock.set_sndbuf(8192).unwrap();
+        assert!(sock.sndbuf().unwrap() >= 8192);
+
+        sock.set_ip_ttl(37).unwrap();
+        assert_eq!(sock.ip_ttl().unwrap(), 37);
+
+        #[cfg(any(linuxlike, freebsdlike, target_os = "netbsd", apple))]
+        {
+            sock.set_tcp_keepidle(120).unwrap();
+            assert_eq!(sock.tcp_keepidle().unwrap(), 120);
+            sock.set_tcp_keepintvl(15).unwrap();
+            assert_eq!(sock.tcp_keepintvl().unwrap(), 15);
+            sock.set_tcp_keepcnt(4).unwrap();
+            assert_eq!(sock.tcp_keepcnt().unwrap(), 4);
+        }
+    }
+
+    #[test]
+    fn test_sockopt_timeo_linger() {
+        let sock = Socket::new(SockDomain::INET, SockType::STREAM, None).unwrap();
+
+        let tv = Timeval {
+            tv_sec: 1,
+            tv_usec: 500_000,
+        };
+
+        assert_eq!(sock.rcvtimeo().unwrap(), None);
+        sock.set_rcvtimeo(Some(tv)).unwrap();
+        assert_eq!(sock.rcvtimeo().unwrap(), Some(tv));
+        sock.set_rcvtimeo(None).unwrap();
+        assert_eq!(sock.rcvtimeo().unwrap(), None);
+
+        assert_eq!(sock.sndtimeo().unwrap(), None);
+        sock.set_sndtimeo(Some(tv)).unwrap();
+        assert_eq!(sock.sndtimeo().unwrap(), Some(tv));
+        sock.set_sndtimeo(None).unwrap();
+        assert_eq!(sock.sndtimeo().unwrap(), None);
+
+        assert_eq!(sock.linger().unwrap(), None);
+        sock.set_linger(Some(5)).unwrap();
+        assert_eq!(sock.linger().unwrap(), Some(5));
+        sock.set_linger(None).unwrap();
+        assert_eq!(sock.linger().unwrap(), None);
+    }
+
+    #[test]
+    fn test_sockopt_type_error() {
+        let sock = Socket::new(SockDomain::INET, SockType::DGRAM, Some(SockProto::UDP)).unwrap();
+        assert_eq!(sock.sock_type().unwrap(), SockType::DGRAM);
+        assert!(sock.take_error().unwrap().is_none());
+
+        let (a, b) = Socket::pair(SockDomain::UNIX, SockType::STREAM, None).unwrap();
+        assert_eq!(a.sock_type().unwrap(), SockType::STREAM);
+
+        #[cfg(linuxlike)]
+        {
+            let cred = b.peercred().unwrap();
+            assert_eq!(cred.pid, crate::getpid());
+            assert_eq!(cred.uid, crate::geteuid());
+            assert_eq!(cred.gid, crate::getegid());
+        }
+        drop(b);
+    }
+
+    #[test]
+    fn test_sockopt_multicast() {
+        let sock = Socket::new(SockDomain::INET, SockType::DGRAM, None).unwrap();
+        let group = Inet4Addr::new(239, 255, 0, 1);
+
+        match sock.ip_add_membership(&group, &Inet4Addr::LOCALHOST) {
+            // Probably no multicast-capable interfaces
+            Err(e) if e == Errno::ENODEV || e == Errno::EADDRNOTAVAIL => return,
+            res => res.unwrap(),
+        }
+        sock.ip_drop_membership(&group, &Inet4Addr::LOCALHOST)
+            .unwrap();
+        assert_eq!(
+            sock.ip_drop_membership(&group, &Inet4Addr::LOCALHOST)
+                .unwrap_err(),
+            Errno::EADDRNOTAVAIL
+        );
+
+        let sock6 = Socket::new(SockDomain::INET6, SockType::DGRAM, None).unwrap();
+        let group6 = Inet6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0x1234);
+        match sock6.ipv6_add_membership(&group6, 0) {
+            Err(e) if e == Errno::ENODEV || e == Errno::EADDRNOTAVAIL => (),
+            res => {
+                res.unwrap();
+                sock6.ipv6_drop_membership(&group6, 0).unwrap();
+            }
+        }
+    }
+}
@@ -502,11 +502,13 @@ pub unsafe fn posix_spawnp_raw<P: AsPath>(
 /// This is identical to [`posix_spawn_raw()`], except that it accepts
 /// [`CStringVec`](./struct.CStringVec.html)s instead of raw pointers, which allows it to be safe.
 ///
-/// # Panics
+/// # Errors
 ///
-/// Panics if `envp` is `None`. This would normally translate to passing NULL, which would preserve
-/// the current environment. However, it cannot currently be done safely. See item (1) in
-/// [`execvp()`](./fn.execvp.html)'s [safety section](./fn.execvp.html#safety).
+/// Fails with `EINVAL` if `envp` is `None`. This would normally translate to passing NULL, which
+/// would preserve the current environment. However, it cannot currently be done safely. See item
+/// (1) in [`execvp()`](./fn.execvp.html)'s [safety section](./fn.execvp.html#safety). Use
+/// [`posix_spawn_raw()`] to preserve the current environment, or (with the `std` feature) see
+/// [`Command`](crate::Command) for a safe alternative.
 #[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", not(target_os = "android")))))]
 #[cfg(feature = "alloc")]
 #[inline]
@@ -517,15 +519,8 @@ pub fn posix_spawn<P: AsPath>(
     argv: &crate::CStringVec,
     envp: Option<&crate::CStringVec>,
 ) -> Result<libc::pid_t> {
-    unsafe {
-        posix_spawn_raw(
-            prog,
-            file_actions,
-            attr,
-            argv.as_ptr(),
-            envp.unwrap().as_ptr(),
-        )
-    }
+    let envp = envp.ok_or_else(|| Error::from_code(libc::EINVAL))?;
+    unsafe { posix_spawn_raw(prog, file_actions, attr, argv.as_ptr(), envp.as_ptr()) }
 }
 
 /// Call `posix_spawnp(3)` to launch a new process.
@@ -629,6 +624,17 @@ mod tests {
         waitpid(pid, WaitFlags::empty()).unwrap();
     }
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_posix_spawn_no_env() {
+        let mut argv = crate::CStringVec::new();
+        argv.push(CString::new("true").unwrap());
+        assert_eq!(
+            posix_spawn("/bin/true", None, None, &argv, None).unwrap_err(),
+            Errno::EINVAL
+        );
+    }
+
     #[cfg(all(feature = "std", target_os = "linux"))]
     #[test]
     fn test_posix_spawn_chdir() {
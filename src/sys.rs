@@ -53,6 +53,28 @@ pub use libc::{
     _PC_REC_XFER_ALIGN,
 };
 
+#[cfg(linuxlike)]
+pub use libc::{SI_ASYNCIO, SI_MESGQ, SI_QUEUE, SI_TIMER, SI_USER};
+#[cfg(any(apple, target_os = "freebsd"))]
+pub const SI_USER: libc::c_int = 0x10001;
+#[cfg(any(apple, target_os = "freebsd"))]
+pub const SI_QUEUE: libc::c_int = 0x10002;
+#[cfg(any(apple, target_os = "freebsd"))]
+pub const SI_TIMER: libc::c_int = 0x10003;
+#[cfg(any(apple, target_os = "freebsd"))]
+pub const SI_ASYNCIO: libc::c_int = 0x10004;
+#[cfg(any(apple, target_os = "freebsd"))]
+pub const SI_MESGQ: libc::c_int = 0x10005;
+
+#[cfg(linuxlike)]
+pub use libc::{IPV6_ADD_MEMBERSHIP, IPV6_DROP_MEMBERSHIP, TCP_KEEPIDLE};
+#[cfg(not(linuxlike))]
+pub use libc::{IPV6_JOIN_GROUP as IPV6_ADD_MEMBERSHIP, IPV6_LEAVE_GROUP as IPV6_DROP_MEMBERSHIP};
+#[cfg(apple)]
+pub use libc::TCP_KEEPALIVE as TCP_KEEPIDLE;
+#[cfg(any(freebsdlike, target_os = "netbsd"))]
+pub use libc::TCP_KEEPIDLE;
+
 cfg_if::cfg_if! {
     if #[cfg(target_os = "linux")] {
         pub use libc::{
@@ -88,6 +110,260 @@ cfg_if::cfg_if! {
 
         pub const PIDFD_NONBLOCK: libc::c_uint = libc::O_NONBLOCK as _;
 
+        pub const CLONE_NEWTIME: u64 = 0x80;
+        pub const CLONE_CLEAR_SIGHAND: u64 = 0x100000000;
+        pub const CLONE_INTO_CGROUP: u64 = 0x200000000;
+
+        pub const AT_RECURSIVE: libc::c_int = 0x8000;
+
+        pub const FSOPEN_CLOEXEC: libc::c_uint = 0x1;
+        pub const FSMOUNT_CLOEXEC: libc::c_uint = 0x1;
+
+        pub const FSCONFIG_SET_FLAG: libc::c_uint = 0;
+        pub const FSCONFIG_SET_STRING: libc::c_uint = 1;
+        pub const FSCONFIG_CMD_CREATE: libc::c_uint = 6;
+        pub const FSCONFIG_CMD_RECONFIGURE: libc::c_uint = 7;
+
+        pub const MOUNT_ATTR_RDONLY: u64 = 0x1;
+        pub const MOUNT_ATTR_NOSUID: u64 = 0x2;
+        pub const MOUNT_ATTR_NODEV: u64 = 0x4;
+        pub const MOUNT_ATTR_NOEXEC: u64 = 0x8;
+        pub const MOUNT_ATTR__ATIME: u64 = 0x70;
+        pub const MOUNT_ATTR_NOATIME: u64 = 0x10;
+        pub const MOUNT_ATTR_STRICTATIME: u64 = 0x20;
+        pub const MOUNT_ATTR_NODIRATIME: u64 = 0x80;
+        pub const MOUNT_ATTR_IDMAP: u64 = 0x100000;
+        pub const MOUNT_ATTR_NOSYMFOLLOW: u64 = 0x200000;
+
+        pub const MOVE_MOUNT_F_SYMLINKS: libc::c_uint = 0x1;
+        pub const MOVE_MOUNT_F_AUTOMOUNTS: libc::c_uint = 0x2;
+        pub const MOVE_MOUNT_F_EMPTY_PATH: libc::c_uint = 0x4;
+        pub const MOVE_MOUNT_T_SYMLINKS: libc::c_uint = 0x10;
+        pub const MOVE_MOUNT_T_AUTOMOUNTS: libc::c_uint = 0x20;
+        pub const MOVE_MOUNT_T_EMPTY_PATH: libc::c_uint = 0x40;
+
+        pub const OPEN_TREE_CLONE: libc::c_uint = 0x1;
+
+        pub const AUDIT_ARCH_AARCH64: u32 = 0xC00000B7;
+        pub const AUDIT_ARCH_ARM: u32 = 0x40000028;
+        pub const AUDIT_ARCH_I386: u32 = 0x40000003;
+        pub const AUDIT_ARCH_LOONGARCH64: u32 = 0xC0000102;
+        pub const AUDIT_ARCH_PPC64: u32 = 0x80000015;
+        pub const AUDIT_ARCH_PPC64LE: u32 = 0xC0000015;
+        pub const AUDIT_ARCH_RISCV64: u32 = 0xC00000F3;
+        pub const AUDIT_ARCH_S390X: u32 = 0x80000016;
+        pub const AUDIT_ARCH_X86_64: u32 = 0xC000003E;
+
+        cfg_if::cfg_if! {
+            if #[cfg(target_arch = "x86_64")] {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_X86_64);
+            } else if #[cfg(target_arch = "x86")] {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_I386);
+            } else if #[cfg(target_arch = "aarch64")] {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_AARCH64);
+            } else if #[cfg(all(target_arch = "arm", target_endian = "little"))] {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_ARM);
+            } else if #[cfg(target_arch = "riscv64")] {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_RISCV64);
+            } else if #[cfg(all(target_arch = "powerpc64", target_endian = "little"))] {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_PPC64LE);
+            } else if #[cfg(all(target_arch = "powerpc64", target_endian = "big"))] {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_PPC64);
+            } else if #[cfg(target_arch = "s390x")] {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_S390X);
+            } else if #[cfg(target_arch = "loongarch64")] {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = Some(AUDIT_ARCH_LOONGARCH64);
+            } else {
+                pub const AUDIT_ARCH_NATIVE: Option<u32> = None;
+            }
+        }
+
+        pub const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+
+        pub const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+
+        pub const LANDLOCK_ACCESS_FS_EXECUTE: u64 = 1 << 0;
+        pub const LANDLOCK_ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+        pub const LANDLOCK_ACCESS_FS_READ_FILE: u64 = 1 << 2;
+        pub const LANDLOCK_ACCESS_FS_READ_DIR: u64 = 1 << 3;
+        pub const LANDLOCK_ACCESS_FS_REMOVE_DIR: u64 = 1 << 4;
+        pub const LANDLOCK_ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+        pub const LANDLOCK_ACCESS_FS_MAKE_CHAR: u64 = 1 << 6;
+        pub const LANDLOCK_ACCESS_FS_MAKE_DIR: u64 = 1 << 7;
+        pub const LANDLOCK_ACCESS_FS_MAKE_REG: u64 = 1 << 8;
+        pub const LANDLOCK_ACCESS_FS_MAKE_SOCK: u64 = 1 << 9;
+        pub const LANDLOCK_ACCESS_FS_MAKE_FIFO: u64 = 1 << 10;
+        pub const LANDLOCK_ACCESS_FS_MAKE_BLOCK: u64 = 1 << 11;
+        pub const LANDLOCK_ACCESS_FS_MAKE_SYM: u64 = 1 << 12;
+        pub const LANDLOCK_ACCESS_FS_REFER: u64 = 1 << 13;
+        pub const LANDLOCK_ACCESS_FS_TRUNCATE: u64 = 1 << 14;
+        pub const LANDLOCK_ACCESS_FS_IOCTL_DEV: u64 = 1 << 15;
+
+        // libc doesn't define this on musl
+        #[derive(Copy, Clone, Debug)]
+        #[repr(C, align(8))]
+        pub struct fanotify_event_metadata {
+            pub event_len: u32,
+            pub vers: u8,
+            pub reserved: u8,
+            pub metadata_len: u16,
+            pub mask: u64,
+            pub fd: i32,
+            pub pid: i32,
+        }
+
+        // The layout of the beginning of a siginfo_t for signals sent by a user process (the `_rt`
+        // member of the `_sifields` union)
+        #[derive(Copy, Clone)]
+        #[repr(C)]
+        pub struct siginfo_rt {
+            pub si_signo: libc::c_int,
+            #[cfg(not(any(target_arch = "mips", target_arch = "mips64")))]
+            pub si_errno: libc::c_int,
+            pub si_code: libc::c_int,
+            #[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+            pub si_errno: libc::c_int,
+            pub rt: siginfo_rt_fields,
+        }
+
+        #[derive(Copy, Clone)]
+        #[repr(C)]
+        pub struct siginfo_rt_fields {
+            pub si_pid: libc::pid_t,
+            pub si_uid: libc::uid_t,
+            pub si_value: libc::sigval,
+        }
+
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C)]
+        pub struct landlock_ruleset_attr {
+            pub handled_access_fs: u64,
+            pub handled_access_net: u64,
+            pub scoped: u64,
+        }
+
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C, packed)]
+        pub struct landlock_path_beneath_attr {
+            pub allowed_access: u64,
+            pub parent_fd: i32,
+        }
+
+        pub const IORING_OFF_SQ_RING: u64 = 0;
+        pub const IORING_OFF_CQ_RING: u64 = 0x8000000;
+        pub const IORING_OFF_SQES: u64 = 0x10000000;
+
+        pub const IORING_SQ_NEED_WAKEUP: u32 = 1 << 0;
+
+        pub const IORING_OP_NOP: u8 = 0;
+        pub const IORING_OP_READV: u8 = 1;
+        pub const IORING_OP_WRITEV: u8 = 2;
+        pub const IORING_OP_FSYNC: u8 = 3;
+        pub const IORING_OP_POLL_ADD: u8 = 6;
+        pub const IORING_OP_TIMEOUT: u8 = 11;
+        pub const IORING_OP_ACCEPT: u8 = 13;
+        pub const IORING_OP_CONNECT: u8 = 16;
+        pub const IORING_OP_READ: u8 = 22;
+        pub const IORING_OP_WRITE: u8 = 23;
+        pub const IORING_OP_SEND: u8 = 26;
+        pub const IORING_OP_RECV: u8 = 27;
+
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C)]
+        pub struct io_sqring_offsets {
+            pub head: u32,
+            pub tail: u32,
+            pub ring_mask: u32,
+            pub ring_entries: u32,
+            pub flags: u32,
+            pub dropped: u32,
+            pub array: u32,
+            pub resv1: u32,
+            pub user_addr: u64,
+        }
+
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C)]
+        pub struct io_cqring_offsets {
+            pub head: u32,
+            pub tail: u32,
+            pub ring_mask: u32,
+            pub ring_entries: u32,
+            pub overflow: u32,
+            pub cqes: u32,
+            pub flags: u32,
+            pub resv1: u32,
+            pub user_addr: u64,
+        }
+
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C)]
+        pub struct io_uring_params {
+            pub sq_entries: u32,
+            pub cq_entries: u32,
+            pub flags: u32,
+            pub sq_thread_cpu: u32,
+            pub sq_thread_idle: u32,
+            pub features: u32,
+            pub wq_fd: u32,
+            pub resv: [u32; 3],
+            pub sq_off: io_sqring_offsets,
+            pub cq_off: io_cqring_offsets,
+        }
+
+        // The unions in the kernel's definition are flattened to their first members
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C)]
+        pub struct io_uring_sqe {
+            pub opcode: u8,
+            pub flags: u8,
+            pub ioprio: u16,
+            pub fd: i32,
+            pub off: u64,
+            pub addr: u64,
+            pub len: u32,
+            pub op_flags: u32,
+            pub user_data: u64,
+            pub buf_index: u16,
+            pub personality: u16,
+            pub splice_fd_in: i32,
+            pub addr3: u64,
+            pub pad2: u64,
+        }
+
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C)]
+        pub struct io_uring_cqe {
+            pub user_data: u64,
+            pub res: i32,
+            pub flags: u32,
+        }
+
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C)]
+        pub struct mount_attr {
+            pub attr_set: u64,
+            pub attr_clr: u64,
+            pub propagation: u64,
+            pub userns_fd: u64,
+        }
+
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C, align(8))]
+        pub struct clone_args {
+            pub flags: u64,
+            pub pidfd: u64,
+            pub child_tid: u64,
+            pub parent_tid: u64,
+            pub exit_signal: u64,
+            pub stack: u64,
+            pub stack_size: u64,
+            pub tls: u64,
+            pub set_tid: u64,
+            pub set_tid_size: u64,
+            pub cgroup: u64,
+        }
+
         #[cfg(any(target_env = "", target_env = "gnu"))]
         #[derive(Copy, Clone, Debug)]
         #[repr(C)]
@@ -746,6 +1022,24 @@ cfg_if::cfg_if! {
         pub const SPLICE_F_MOVE: libc::c_uint = 0x01;
         pub const SPLICE_F_NONBLOCK: libc::c_uint = 0x02;
         pub const SPLICE_F_MORE: libc::c_uint = 0x04;
+
+        pub const _LINUX_CAPABILITY_VERSION_3: u32 = 0x20080522;
+        pub const _LINUX_CAPABILITY_U32S_3: usize = 2;
+
+        #[derive(Copy, Clone, Debug)]
+        #[repr(C)]
+        pub struct __user_cap_header_struct {
+            pub version: u32,
+            pub pid: libc::c_int,
+        }
+
+        #[derive(Copy, Clone, Debug, Default)]
+        #[repr(C)]
+        pub struct __user_cap_data_struct {
+            pub effective: u32,
+            pub permitted: u32,
+            pub inheritable: u32,
+        }
     } else if #[cfg(bsd)] {
         pub use libc::IOV_MAX;
 
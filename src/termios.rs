@@ -0,0 +1,576 @@
+use crate::internal_prelude::*;
+
+use core::fmt;
+
+bitflags::bitflags! {
+    /// Input mode flags (`c_iflag`).
+    #[derive(Default)]
+    pub struct InputFlags: libc::tcflag_t {
+        const IGNBRK = libc::IGNBRK;
+        const BRKINT = libc::BRKINT;
+        const IGNPAR = libc::IGNPAR;
+        const PARMRK = libc::PARMRK;
+        const INPCK = libc::INPCK;
+        const ISTRIP = libc::ISTRIP;
+        const INLCR = libc::INLCR;
+        const IGNCR = libc::IGNCR;
+        const ICRNL = libc::ICRNL;
+        const IXON = libc::IXON;
+        const IXANY = libc::IXANY;
+        const IXOFF = libc::IXOFF;
+        const IMAXBEL = libc::IMAXBEL;
+        #[cfg(linuxlike)]
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        const IUTF8 = libc::IUTF8;
+    }
+}
+
+bitflags::bitflags! {
+    /// Output mode flags (`c_oflag`).
+    #[derive(Default)]
+    pub struct OutputFlags: libc::tcflag_t {
+        const OPOST = libc::OPOST;
+        const ONLCR = libc::ONLCR;
+        const OCRNL = libc::OCRNL;
+        const ONOCR = libc::ONOCR;
+        const ONLRET = libc::ONLRET;
+        #[cfg(any(linuxlike, target_os = "openbsd"))]
+        #[cfg_attr(
+            docsrs,
+            doc(cfg(any(target_os = "linux", target_os = "android", target_os = "openbsd")))
+        )]
+        const OLCUC = libc::OLCUC;
+        #[cfg(any(linuxlike, apple))]
+        #[cfg_attr(
+            docsrs,
+            doc(cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "macos",
+                target_os = "ios",
+            )))
+        )]
+        const OFILL = libc::OFILL;
+        #[cfg(any(linuxlike, apple))]
+        #[cfg_attr(
+            docsrs,
+            doc(cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "macos",
+                target_os = "ios",
+            )))
+        )]
+        const OFDEL = libc::OFDEL;
+    }
+}
+
+bitflags::bitflags! {
+    /// Control mode flags (`c_cflag`).
+    ///
+    /// Note that `CSIZE` is a mask covering `CS5` through `CS8` (and `CS5` is 0 on most systems).
+    #[derive(Default)]
+    pub struct ControlFlags: libc::tcflag_t {
+        const CSIZE = libc::CSIZE;
+        const CS5 = libc::CS5;
+        const CS6 = libc::CS6;
+        const CS7 = libc::CS7;
+        const CS8 = libc::CS8;
+        const CSTOPB = libc::CSTOPB;
+        const CREAD = libc::CREAD;
+        const PARENB = libc::PARENB;
+        const PARODD = libc::PARODD;
+        const HUPCL = libc::HUPCL;
+        const CLOCAL = libc::CLOCAL;
+        const CRTSCTS = libc::CRTSCTS;
+    }
+}
+
+bitflags::bitflags! {
+    /// Local mode flags (`c_lflag`).
+    #[derive(Default)]
+    pub struct LocalFlags: libc::tcflag_t {
+        const ISIG = libc::ISIG;
+        const ICANON = libc::ICANON;
+        const ECHO = libc::ECHO;
+        const ECHOE = libc::ECHOE;
+        const ECHOK = libc::ECHOK;
+        const ECHONL = libc::ECHONL;
+        const NOFLSH = libc::NOFLSH;
+        const TOSTOP = libc::TOSTOP;
+        const IEXTEN = libc::IEXTEN;
+        const ECHOCTL = libc::ECHOCTL;
+        const ECHOPRT = libc::ECHOPRT;
+        const ECHOKE = libc::ECHOKE;
+        const FLUSHO = libc::FLUSHO;
+        const PENDIN = libc::PENDIN;
+    }
+}
+
+/// An index into the control characters array of a [`Termios`] (`c_cc`).
+#[allow(
+    non_camel_case_types,
+    clippy::upper_case_acronyms,
+    clippy::unnecessary_cast
+)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[repr(usize)]
+pub enum ControlChar {
+    VEOF = libc::VEOF as usize,
+    VEOL = libc::VEOL as usize,
+    VEOL2 = libc::VEOL2 as usize,
+    VERASE = libc::VERASE as usize,
+    VWERASE = libc::VWERASE as usize,
+    VKILL = libc::VKILL as usize,
+    VREPRINT = libc::VREPRINT as usize,
+    VINTR = libc::VINTR as usize,
+    VQUIT = libc::VQUIT as usize,
+    VSUSP = libc::VSUSP as usize,
+    VSTART = libc::VSTART as usize,
+    VSTOP = libc::VSTOP as usize,
+    VLNEXT = libc::VLNEXT as usize,
+    VDISCARD = libc::VDISCARD as usize,
+    VMIN = libc::VMIN as usize,
+    VTIME = libc::VTIME as usize,
+    #[cfg(any(bsd, apple))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "openbsd",
+            target_os = "netbsd",
+            target_os = "macos",
+            target_os = "ios",
+        )))
+    )]
+    VSTATUS = libc::VSTATUS as usize,
+}
+
+/// A terminal baud rate, for use with e.g. [`Termios::set_speed()`].
+///
+/// On the BSDs and macOS, the raw values are the actual baud rates, so any value can be passed to
+/// [`Self::from_raw()`]. On Linux, only the constants defined here are valid.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BaudRate(libc::speed_t);
+
+macro_rules! define_baud_rates {
+    ($(
+        #[cfg($cfg:meta)]
+        $($name:ident,)*
+    )*) => {
+        impl BaudRate {
+            $($(
+                #[cfg($cfg)]
+                #[cfg_attr(docsrs, doc(cfg($cfg)))]
+                pub const $name: Self = Self(libc::$name);
+            )*)*
+        }
+    };
+}
+
+define_baud_rates! {
+    #[cfg(all())]
+    B0,
+    B50,
+    B75,
+    B110,
+    B134,
+    B150,
+    B200,
+    B300,
+    B600,
+    B1200,
+    B1800,
+    B2400,
+    B4800,
+    B9600,
+    B19200,
+    B38400,
+    B57600,
+    B115200,
+    B230400,
+
+    #[cfg(any(linuxlike, target_os = "freebsd", target_os = "netbsd"))]
+    B460800,
+    B921600,
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    B500000,
+    B576000,
+    B1000000,
+    B1152000,
+    B1500000,
+    B2000000,
+    B2500000,
+    B3000000,
+    B3500000,
+    B4000000,
+}
+
+impl BaudRate {
+    /// Create a `BaudRate` from the raw `speed_t` value.
+    #[inline]
+    pub const fn from_raw(speed: libc::speed_t) -> Self {
+        Self(speed)
+    }
+
+    /// Get the raw `speed_t` value of this baud rate.
+    #[inline]
+    pub const fn as_raw(self) -> libc::speed_t {
+        self.0
+    }
+}
+
+/// Terminal attributes, as retrieved by [`tcgetattr()`] and set by [`tcsetattr()`].
+///
+/// The flag setters only modify the bits that are known to this crate; any other bits set by the
+/// OS are preserved.
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+pub struct Termios(libc::termios);
+
+macro_rules! termios_flags {
+    ($($(#[doc = $doc:literal])* $get:ident, $set:ident, $field:ident: $type:ty;)*) => {
+        $(
+            $(#[doc = $doc])*
+            #[inline]
+            pub fn $get(&self) -> $type {
+                <$type>::from_bits_truncate(self.0.$field)
+            }
+
+            $(#[doc = $doc])*
+            #[inline]
+            pub fn $set(&mut self, flags: $type) {
+                self.0.$field = (self.0.$field & !<$type>::all().bits()) | flags.bits();
+            }
+        )*
+    };
+}
+
+impl Termios {
+    termios_flags! {
+        /// The input mode flags (`c_iflag`).
+        input_flags, set_input_flags, c_iflag: InputFlags;
+        /// The output mode flags (`c_oflag`).
+        output_flags, set_output_flags, c_oflag: OutputFlags;
+        /// The control mode flags (`c_cflag`).
+        control_flags, set_control_flags, c_cflag: ControlFlags;
+        /// The local mode flags (`c_lflag`).
+        local_flags, set_local_flags, c_lflag: LocalFlags;
+    }
+
+    /// Get the value of the given control character.
+    #[inline]
+    pub fn cc(&self, c: ControlChar) -> u8 {
+        self.0.c_cc[c as usize]
+    }
+
+    /// Set the value of the given control character.
+    ///
+    /// In noncanonical mode, [`ControlChar::VMIN`] and [`ControlChar::VTIME`] control the behavior
+    /// of `read()`s on the terminal; see `termios(3)` for details.
+    #[inline]
+    pub fn set_cc(&mut self, c: ControlChar, val: u8) {
+        self.0.c_cc[c as usize] = val;
+    }
+
+    /// Modify these attributes to put the terminal in "raw" mode (`cfmakeraw()`).
+    ///
+    /// Input is then available character by character, echoing is disabled, and all special
+    /// processing of terminal input and output characters is disabled.
+    #[inline]
+    pub fn make_raw(&mut self) {
+        unsafe {
+            libc::cfmakeraw(&mut self.0);
+        }
+    }
+
+    /// Get the input baud rate (`cfgetispeed()`).
+    #[inline]
+    pub fn input_speed(&self) -> BaudRate {
+        BaudRate(unsafe { libc::cfgetispeed(&self.0) })
+    }
+
+    /// Get the output baud rate (`cfgetospeed()`).
+    #[inline]
+    pub fn output_speed(&self) -> BaudRate {
+        BaudRate(unsafe { libc::cfgetospeed(&self.0) })
+    }
+
+    /// Set the input baud rate (`cfsetispeed()`).
+    ///
+    /// Note that on some systems [`BaudRate::B0`] means "the same as the output baud rate".
+    #[inline]
+    pub fn set_input_speed(&mut self, speed: BaudRate) -> Result<()> {
+        Error::unpack_nz(unsafe { libc::cfsetispeed(&mut self.0, speed.0) })
+    }
+
+    /// Set the output baud rate (`cfsetospeed()`).
+    #[inline]
+    pub fn set_output_speed(&mut self, speed: BaudRate) -> Result<()> {
+        Error::unpack_nz(unsafe { libc::cfsetospeed(&mut self.0, speed.0) })
+    }
+
+    /// Set both the input and output baud rates (`cfsetspeed()`).
+    #[inline]
+    pub fn set_speed(&mut self, speed: BaudRate) -> Result<()> {
+        Error::unpack_nz(unsafe { libc::cfsetspeed(&mut self.0, speed.0) })
+    }
+}
+
+impl fmt::Debug for Termios {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Termios")
+            .field("input_flags", &self.input_flags())
+            .field("output_flags", &self.output_flags())
+            .field("control_flags", &self.control_flags())
+            .field("local_flags", &self.local_flags())
+            .field("cc", &self.0.c_cc)
+            .field("input_speed", &self.input_speed())
+            .field("output_speed", &self.output_speed())
+            .finish()
+    }
+}
+
+impl From<libc::termios> for Termios {
+    #[inline]
+    fn from(t: libc::termios) -> Self {
+        Self(t)
+    }
+}
+
+impl AsRef<libc::termios> for Termios {
+    #[inline]
+    fn as_ref(&self) -> &libc::termios {
+        &self.0
+    }
+}
+
+/// When changes made by [`tcsetattr()`] should take effect.
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[repr(i32)]
+pub enum SetAttrWhen {
+    /// The changes occur immediately.
+    NOW = libc::TCSANOW,
+    /// The changes occur after all output written to the terminal has been transmitted.
+    DRAIN = libc::TCSADRAIN,
+    /// Like `DRAIN`, but all input that has been received and not read is also discarded.
+    FLUSH = libc::TCSAFLUSH,
+}
+
+/// The queue(s) to discard with [`tcflush()`].
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[repr(i32)]
+pub enum FlushQueue {
+    /// Data that has been received but not read.
+    IFLUSH = libc::TCIFLUSH,
+    /// Data that has been written but not transmitted.
+    OFLUSH = libc::TCOFLUSH,
+    /// Both of the above.
+    IOFLUSH = libc::TCIOFLUSH,
+}
+
+/// The action to take with [`tcflow()`].
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[repr(i32)]
+pub enum FlowAction {
+    /// Suspend output.
+    OOFF = libc::TCOOFF,
+    /// Restart suspended output.
+    OON = libc::TCOON,
+    /// Transmit a STOP character, asking the terminal to stop sending data.
+    IOFF = libc::TCIOFF,
+    /// Transmit a START character, asking the terminal to resume sending data.
+    ION = libc::TCION,
+}
+
+/// Get the attributes of the terminal referred to by `fd`.
+#[inline]
+pub fn tcgetattr(fd: RawFd) -> Result<Termios> {
+    let mut t = MaybeUninit::uninit();
+    Error::unpack_nz(unsafe { libc::tcgetattr(fd, t.as_mut_ptr()) })?;
+    Ok(Termios(unsafe { t.assume_init() }))
+}
+
+/// Set the attributes of the terminal referred to by `fd`.
+///
+/// Note that this succeeds if *any* of the requested changes could be made. To check that all
+/// changes were made, call [`tcgetattr()`] afterward and compare the results.
+#[inline]
+pub fn tcsetattr(fd: RawFd, when: SetAttrWhen, t: &Termios) -> Result<()> {
+    Error::unpack_nz(unsafe { libc::tcsetattr(fd, when as _, &t.0) })
+}
+
+/// Wait until all output written to the terminal referred to by `fd` has been transmitted.
+#[inline]
+pub fn tcdrain(fd: RawFd) -> Result<()> {
+    Error::unpack_nz(unsafe { libc::tcdrain(fd) })
+}
+
+/// Discard data written to or received by the terminal referred to by `fd`.
+#[inline]
+pub fn tcflush(fd: RawFd, queue: FlushQueue) -> Result<()> {
+    Error::unpack_nz(unsafe { libc::tcflush(fd, queue as _) })
+}
+
+/// Suspend or resume transmission or reception of data on the terminal referred to by `fd`.
+#[inline]
+pub fn tcflow(fd: RawFd, action: FlowAction) -> Result<()> {
+    Error::unpack_nz(unsafe { libc::tcflow(fd, action as _) })
+}
+
+/// Transmit a continuous stream of zero-valued bits to the terminal referred to by `fd`.
+///
+/// If `duration` is 0, the break lasts for between 0.25 and 0.5 seconds. The meaning of a nonzero
+/// `duration` is implementation-defined.
+#[inline]
+pub fn tcsendbreak(fd: RawFd, duration: i32) -> Result<()> {
+    Error::unpack_nz(unsafe { libc::tcsendbreak(fd, duration) })
+}
+
+/// A guard that puts a terminal into "raw" mode and restores the original attributes when it is
+/// dropped.
+///
+/// This can be created with [`Self::new()`] or [`BorrowedFd::raw_mode()`]. Errors restoring the
+/// original attributes are ignored.
+#[derive(Debug)]
+pub struct RawModeGuard<'a> {
+    fd: &'a BorrowedFd,
+    orig: Termios,
+}
+
+impl<'a> RawModeGuard<'a> {
+    /// Put the terminal referred to by `fd` into raw mode (see [`Termios::make_raw()`]).
+    ///
+    /// Any input that has been received but not read is discarded.
+    pub fn new(fd: &'a BorrowedFd) -> Result<Self> {
+        let orig = tcgetattr(fd.fd())?;
+
+        let mut raw = orig;
+        raw.make_raw();
+        tcsetattr(fd.fd(), SetAttrWhen::FLUSH, &raw)?;
+
+        Ok(Self { fd, orig })
+    }
+
+    /// Get the original attributes that will be restored when this guard is dropped.
+    #[inline]
+    pub fn original(&self) -> &Termios {
+        &self.orig
+    }
+}
+
+impl Drop for RawModeGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = tcsetattr(self.fd.fd(), SetAttrWhen::NOW, &self.orig);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_termios_flags() {
+        let (_master, slave) = unsafe { crate::openpty(None, None) }.unwrap();
+
+        let mut t = slave.tcgetattr().unwrap();
+        assert!(t
+            .local_flags()
+            .contains(LocalFlags::ECHO | LocalFlags::ICANON));
+
+        t.set_local_flags(t.local_flags() - LocalFlags::ECHO);
+        t.set_output_flags(t.output_flags() - OutputFlags::ONLCR);
+        t.set_cc(ControlChar::VMIN, 0);
+        t.set_cc(ControlChar::VTIME, 5);
+        slave.tcsetattr(SetAttrWhen::NOW, &t).unwrap();
+
+        let t2 = slave.tcgetattr().unwrap();
+        assert!(!t2.local_flags().contains(LocalFlags::ECHO));
+        assert!(t2.local_flags().contains(LocalFlags::ICANON));
+        assert!(!t2.output_flags().contains(OutputFlags::ONLCR));
+        assert_eq!(t2.cc(ControlChar::VMIN), 0);
+        assert_eq!(t2.cc(ControlChar::VTIME), 5);
+        assert_eq!(t2.as_ref().c_lflag, t.as_ref().c_lflag);
+
+        let r = crate::pipe().unwrap().0;
+        assert_eq!(tcgetattr(r.fd()).unwrap_err(), Errno::ENOTTY);
+        assert_eq!(
+            tcsetattr(r.fd(), SetAttrWhen::NOW, &t).unwrap_err(),
+            Errno::ENOTTY
+        );
+    }
+
+    #[test]
+    fn test_termios_speed() {
+        let (_master, slave) = unsafe { crate::openpty(None, None) }.unwrap();
+
+        let mut t = slave.tcgetattr().unwrap();
+
+        t.set_speed(BaudRate::B9600).unwrap();
+        assert_eq!(t.input_speed(), BaudRate::B9600);
+        assert_eq!(t.output_speed(), BaudRate::B9600);
+
+        // Not all systems support different input and output speeds, so set them to the same
+        // value
+        t.set_output_speed(BaudRate::B115200).unwrap();
+        assert_eq!(t.output_speed(), BaudRate::B115200);
+        t.set_input_speed(BaudRate::B115200).unwrap();
+        assert_eq!(t.input_speed(), BaudRate::B115200);
+
+        assert_eq!(
+            BaudRate::from_raw(BaudRate::B57600.as_raw()),
+            BaudRate::B57600
+        );
+    }
+
+    #[test]
+    fn test_raw_mode_guard() {
+        let (_master, slave) = unsafe { crate::openpty(None, None) }.unwrap();
+
+        let orig = slave.tcgetattr().unwrap();
+        {
+            let guard = slave.raw_mode().unwrap();
+            assert_eq!(guard.original().as_ref().c_lflag, orig.as_ref().c_lflag);
+
+            let t = slave.tcgetattr().unwrap();
+            assert!(!t
+                .local_flags()
+                .intersects(LocalFlags::ECHO | LocalFlags::ICANON | LocalFlags::ISIG));
+            assert!(!t.output_flags().contains(OutputFlags::OPOST));
+            assert_eq!(t.control_flags() & ControlFlags::CSIZE, ControlFlags::CS8);
+        }
+
+        let t = slave.tcgetattr().unwrap();
+        assert_eq!(t.as_ref().c_iflag, orig.as_ref().c_iflag);
+        assert_eq!(t.as_ref().c_oflag, orig.as_ref().c_oflag);
+        assert_eq!(t.as_ref().c_lflag, orig.as_ref().c_lflag);
+
+        let r = crate::pipe().unwrap().0;
+        assert_eq!(r.raw_mode().unwrap_err(), Errno::ENOTTY);
+    }
+
+    #[test]
+    fn test_tc_line_control() {
+        let (master, slave) = unsafe { crate::openpty(None, None) }.unwrap();
+
+        slave.write(b"abc").unwrap();
+        tcdrain(slave.fd()).unwrap();
+        tcflush(slave.fd(), FlushQueue::IOFLUSH).unwrap();
+        tcflow(slave.fd(), FlowAction::OOFF).unwrap();
+        tcflow(slave.fd(), FlowAction::OON).unwrap();
+        tcsendbreak(master.fd(), 0).unwrap();
+
+        let r = crate::pipe().unwrap().0;
+        assert_eq!(tcdrain(r.fd()).unwrap_err(), Errno::ENOTTY);
+        assert_eq!(
+            tcflush(r.fd(), FlushQueue::IFLUSH).unwrap_err(),
+            Errno::ENOTTY
+        );
+    }
+}
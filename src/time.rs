@@ -53,6 +53,40 @@ impl From<libc::timespec> for TimeSpec {
     }
 }
 
+/// Build an `itimerspec` from an `(interval, value)` pair, translating `None` to a zeroed
+/// `timespec`.
+#[cfg(any(linuxlike, target_os = "freebsd", target_os = "netbsd"))]
+#[inline]
+pub(crate) fn itimerspec_pack(
+    interval: Option<TimeSpec>,
+    value: Option<TimeSpec>,
+) -> libc::itimerspec {
+    let zero = TimeSpec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+
+    libc::itimerspec {
+        it_interval: interval.unwrap_or(zero).into(),
+        it_value: value.unwrap_or(zero).into(),
+    }
+}
+
+/// The reverse of [`itimerspec_pack()`]; zeroed `timespec`s are translated to `None`.
+#[cfg(any(linuxlike, target_os = "freebsd", target_os = "netbsd"))]
+#[inline]
+pub(crate) fn itimerspec_unpack(itspec: &libc::itimerspec) -> (Option<TimeSpec>, Option<TimeSpec>) {
+    let unpack = |ts: &libc::timespec| {
+        if ts.tv_sec == 0 && ts.tv_nsec == 0 {
+            None
+        } else {
+            Some(TimeSpec::from(*ts))
+        }
+    };
+
+    (unpack(&itspec.it_interval), unpack(&itspec.it_value))
+}
+
 impl From<Duration> for TimeSpec {
     #[inline]
     fn from(d: Duration) -> Self {
@@ -0,0 +1,256 @@
+use crate::internal_prelude::*;
+
+use crate::time::{ClockId, TimeSpec};
+use crate::{SigVal, Signal};
+
+bitflags::bitflags! {
+    /// Flags for [`PosixTimer::settime()`].
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "freebsd",
+            target_os = "netbsd",
+        )))
+    )]
+    #[derive(Default)]
+    pub struct PosixTimerFlags: libc::c_int {
+        /// Interpret the initial expiration time as an absolute value of the timer's clock.
+        const ABSTIME = libc::TIMER_ABSTIME;
+    }
+}
+
+/// Specifies how the caller should be notified when an asynchronous event (such as the
+/// expiration of a [`PosixTimer`]) occurs.
+///
+/// See `sigevent(7)` for more information.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "netbsd",
+    )))
+)]
+#[derive(Copy, Clone, Debug)]
+pub enum SigEvent {
+    /// Don't send any notification (`SIGEV_NONE`).
+    ///
+    /// The progress of the timer can still be monitored with [`PosixTimer::gettime()`].
+    None,
+    /// Send the given signal to the process, along with the given value (`SIGEV_SIGNAL`).
+    ///
+    /// The value is available to the receiver with
+    /// [`SigInfo::si_value()`](crate::SigInfo::si_value).
+    Signal(Signal, SigVal),
+    /// Like [`Self::Signal`], but send the signal to the thread with the given thread ID (as
+    /// returned by [`gettid()`](crate::gettid)), which must be in the current process
+    /// (`SIGEV_THREAD_ID`).
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    ThreadId(Signal, SigVal, libc::pid_t),
+}
+
+impl SigEvent {
+    fn to_raw(self) -> libc::sigevent {
+        // Some of the fields are private padding, so we have to start with a zeroed struct
+        let mut sev = unsafe { core::mem::zeroed::<libc::sigevent>() };
+
+        match self {
+            Self::None => sev.sigev_notify = libc::SIGEV_NONE,
+
+            Self::Signal(sig, val) => {
+                sev.sigev_notify = libc::SIGEV_SIGNAL;
+                sev.sigev_signo = sig.as_i32();
+                sev.sigev_value = val.0;
+            }
+
+            #[cfg(linuxlike)]
+            Self::ThreadId(sig, val, tid) => {
+                sev.sigev_notify = libc::SIGEV_THREAD_ID;
+                sev.sigev_signo = sig.as_i32();
+                sev.sigev_value = val.0;
+                sev.sigev_notify_thread_id = tid;
+            }
+        }
+
+        sev
+    }
+}
+
+/// A POSIX per-process timer, created with `timer_create()`.
+///
+/// The timer is deleted with `timer_delete()` when this is dropped.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "netbsd",
+    )))
+)]
+#[derive(Debug)]
+pub struct PosixTimer(libc::timer_t);
+
+// Timer IDs are valid process-wide, and all operations on them are thread-safe
+unsafe impl Send for PosixTimer {}
+unsafe impl Sync for PosixTimer {}
+
+impl PosixTimer {
+    /// Create a new timer that measures time with the given clock.
+    ///
+    /// `sevp` specifies how the caller should be notified when the timer expires. The new timer
+    /// is initially disarmed; use [`Self::settime()`] to arm it.
+    #[inline]
+    pub fn new(clockid: ClockId, sevp: &SigEvent) -> Result<Self> {
+        let mut sev = sevp.to_raw();
+        let mut timerid = MaybeUninit::uninit();
+        Error::unpack_nz(unsafe {
+            libc::timer_create(clockid.as_raw(), &mut sev, timerid.as_mut_ptr())
+        })?;
+        Ok(Self(unsafe { timerid.assume_init() }))
+    }
+
+    /// Arm/disarm the timer.
+    ///
+    /// `interval` specifies the period for repeated expirations after the initial expiration, and
+    /// `value` specifies the initial expiration time (relative to the current value of the clock,
+    /// unless [`PosixTimerFlags::ABSTIME`] is specified). If `interval` is `None` then the timer
+    /// will only expire once; if `value` is `None` then the timer is disarmed. This mirrors
+    /// [`TimerFd::settime()`](crate::TimerFd::settime) on Linux.
+    ///
+    /// On success, the previous `(interval, value)` settings are returned, as for
+    /// [`Self::gettime()`].
+    #[inline]
+    pub fn settime(
+        &self,
+        flags: PosixTimerFlags,
+        interval: Option<TimeSpec>,
+        value: Option<TimeSpec>,
+    ) -> Result<(Option<TimeSpec>, Option<TimeSpec>)> {
+        let new_itspec = crate::time::itimerspec_pack(interval, value);
+
+        let mut old_itspec = MaybeUninit::uninit();
+        Error::unpack_nz(unsafe {
+            libc::timer_settime(self.0, flags.bits(), &new_itspec, old_itspec.as_mut_ptr())
+        })?;
+        Ok(crate::time::itimerspec_unpack(unsafe {
+            &old_itspec.assume_init()
+        }))
+    }
+
+    /// Get the current setting of the timer.
+    ///
+    /// This returns an `(interval, value)` tuple, where `value` is the amount of time until the
+    /// next expiration (or `None` if the timer is disarmed). See [`Self::settime()`].
+    #[inline]
+    pub fn gettime(&self) -> Result<(Option<TimeSpec>, Option<TimeSpec>)> {
+        let mut itspec = MaybeUninit::uninit();
+        Error::unpack_nz(unsafe { libc::timer_gettime(self.0, itspec.as_mut_ptr()) })?;
+        Ok(crate::time::itimerspec_unpack(unsafe {
+            &itspec.assume_init()
+        }))
+    }
+
+    /// Get the number of additional expirations that occurred between the generation of the
+    /// last signal for this timer and its delivery (or acceptance).
+    ///
+    /// Since only one signal is queued for each timer, this is the number of expirations that
+    /// were "missed".
+    #[inline]
+    pub fn getoverrun(&self) -> Result<i32> {
+        Error::unpack(unsafe { libc::timer_getoverrun(self.0) })
+    }
+
+    /// Get the raw timer ID.
+    #[inline]
+    pub fn as_raw(&self) -> libc::timer_t {
+        self.0
+    }
+}
+
+impl Drop for PosixTimer {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            libc::timer_delete(self.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_posix_timer_none() {
+        let timer = PosixTimer::new(ClockId::MONOTONIC, &SigEvent::None).unwrap();
+        assert_eq!(timer.gettime().unwrap(), (None, None));
+        assert_eq!(timer.getoverrun().unwrap(), 0);
+
+        let interval = TimeSpec {
+            tv_sec: 100,
+            tv_nsec: 0,
+        };
+        let value = TimeSpec {
+            tv_sec: 1000,
+            tv_nsec: 0,
+        };
+        assert_eq!(
+            timer
+                .settime(PosixTimerFlags::empty(), Some(interval), Some(value))
+                .unwrap(),
+            (None, None)
+        );
+
+        let (cur_interval, cur_value) = timer.gettime().unwrap();
+        assert_eq!(cur_interval, Some(interval));
+        let cur_value = cur_value.unwrap();
+        assert!(cur_value.tv_sec <= 1000 && cur_value.tv_sec >= 990);
+
+        let (old_interval, old_value) =
+            timer.settime(PosixTimerFlags::empty(), None, None).unwrap();
+        assert_eq!(old_interval, Some(interval));
+        assert!(old_value.unwrap().tv_sec <= 1000);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_posix_timer_signal() {
+        use crate::{SigCode, SigmaskHow};
+
+        let sig = Signal::sigrtmin();
+        let set = crate::sigset!(sig);
+        let old_mask = crate::pthread_sigmask(SigmaskHow::BLOCK, Some(&set)).unwrap();
+
+        // Direct the signal at this thread, so other threads can't receive it
+        let timer = PosixTimer::new(
+            ClockId::MONOTONIC,
+            &SigEvent::ThreadId(sig, SigVal::from_int(1234), crate::gettid()),
+        )
+        .unwrap();
+
+        timer
+            .settime(
+                PosixTimerFlags::empty(),
+                None,
+                Some(TimeSpec {
+                    tv_sec: 0,
+                    tv_nsec: 1_000_000,
+                }),
+            )
+            .unwrap();
+
+        let (s, info) = crate::sigwaitinfo(&set).unwrap();
+        crate::pthread_sigmask(SigmaskHow::SETMASK, Some(&old_mask)).unwrap();
+
+        assert_eq!(s, sig);
+        assert_eq!(info.code(), SigCode::Timer);
+        assert_eq!(info.si_value().as_int(), 1234);
+        assert_eq!(timer.getoverrun().unwrap(), 0);
+        assert_eq!(timer.gettime().unwrap(), (None, None));
+    }
+}
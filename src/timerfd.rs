@@ -39,11 +39,6 @@ pub fn timerfd_create(clockid: ClockId, flags: TimerfdFlags) -> Result<FileDesc>
     unsafe { Error::unpack_fdesc(libc::timerfd_create(clockid.as_raw() as _, flags.bits)) }
 }
 
-#[inline]
-fn tspec_empty(ts: &libc::timespec) -> bool {
-    ts.tv_sec == 0 && ts.tv_nsec == 0
-}
-
 /// Arm/disarm the timer.
 ///
 /// `fd` is a file descriptor referring to the timerfd to operate on.
@@ -67,39 +62,15 @@ pub fn timerfd_settime(
     interval: Option<TimeSpec>,
     value: Option<TimeSpec>,
 ) -> Result<(Option<TimeSpec>, Option<TimeSpec>)> {
-    let new_itspec = libc::itimerspec {
-        it_interval: interval
-            .unwrap_or(TimeSpec {
-                tv_sec: 0,
-                tv_nsec: 0,
-            })
-            .into(),
-        it_value: value
-            .unwrap_or(TimeSpec {
-                tv_sec: 0,
-                tv_nsec: 0,
-            })
-            .into(),
-    };
+    let new_itspec = crate::time::itimerspec_pack(interval, value);
 
     let mut old_itspec = MaybeUninit::uninit();
     Error::unpack_nz(unsafe {
         libc::timerfd_settime(fd, flags.bits, &new_itspec, old_itspec.as_mut_ptr())
     })?;
-    let old_itspec = unsafe { old_itspec.assume_init() };
-
-    Ok((
-        if !tspec_empty(&old_itspec.it_interval) {
-            Some(old_itspec.it_interval.into())
-        } else {
-            None
-        },
-        if !tspec_empty(&old_itspec.it_value) {
-            Some(old_itspec.it_value.into())
-        } else {
-            None
-        },
-    ))
+    Ok(crate::time::itimerspec_unpack(unsafe {
+        &old_itspec.assume_init()
+    }))
 }
 
 /// Get the current setting of the timer file descriptor.
@@ -111,20 +82,9 @@ pub fn timerfd_settime(
 pub fn timerfd_gettime(fd: RawFd) -> Result<(Option<TimeSpec>, Option<TimeSpec>)> {
     let mut itspec = MaybeUninit::uninit();
     Error::unpack_nz(unsafe { libc::timerfd_gettime(fd, itspec.as_mut_ptr()) })?;
-    let itspec = unsafe { itspec.assume_init() };
-
-    Ok((
-        if !tspec_empty(&itspec.it_interval) {
-            Some(itspec.it_interval.into())
-        } else {
-            None
-        },
-        if !tspec_empty(&itspec.it_value) {
-            Some(itspec.it_value.into())
-        } else {
-            None
-        },
-    ))
+    Ok(crate::time::itimerspec_unpack(unsafe {
+        &itspec.assume_init()
+    }))
 }
 
 /// A wrapper around an timer file descriptor.
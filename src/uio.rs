@@ -272,6 +272,134 @@ pub fn pwritev2(fd: RawFd, iov: &[IoVec], offset: Option<u64>, flags: RWFlags) -
     })
 }
 
+/// Describes a region of memory in another process.
+///
+/// This is used to specify the remote addresses for [`process_vm_readv()`] and
+/// [`process_vm_writev()`].
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[cfg(linuxlike)]
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+pub struct RemoteIoVec(libc::iovec);
+
+#[cfg(linuxlike)]
+impl RemoteIoVec {
+    /// Create a new `RemoteIoVec` describing the `len` bytes starting at the address `base` in
+    /// the remote process.
+    #[inline]
+    pub fn new(base: usize, len: usize) -> Self {
+        Self(libc::iovec {
+            iov_base: base as *mut _,
+            iov_len: len,
+        })
+    }
+
+    /// Get the starting address of the memory region.
+    #[inline]
+    pub fn base(&self) -> usize {
+        self.0.iov_base as usize
+    }
+
+    /// Get the length of the memory region.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.iov_len
+    }
+
+    /// Check whether the memory region is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.iov_len == 0
+    }
+}
+
+#[cfg(linuxlike)]
+impl PartialEq for RemoteIoVec {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.base() == other.base() && self.len() == other.len()
+    }
+}
+
+#[cfg(linuxlike)]
+impl Eq for RemoteIoVec {}
+
+#[cfg(linuxlike)]
+impl Hash for RemoteIoVec {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base().hash(state);
+        self.len().hash(state);
+    }
+}
+
+#[cfg(linuxlike)]
+impl fmt::Debug for RemoteIoVec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RemoteIoVec")
+            .field("base", &(self.base() as *const u8))
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Read from the memory of the process specified by `pid` into the local buffers in `local_iov`.
+///
+/// `remote_iov` specifies the memory regions in the remote process to read from. The caller
+/// must have permission to `ptrace()` the remote process. This returns the number of bytes
+/// read, which may be less than requested if part of a remote region is inaccessible.
+///
+/// See `process_vm_readv(2)` for more information.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[cfg(linuxlike)]
+#[inline]
+pub fn process_vm_readv(
+    pid: libc::pid_t,
+    local_iov: &mut [IoVecMut],
+    remote_iov: &[RemoteIoVec],
+) -> Result<usize> {
+    Error::unpack_size(unsafe {
+        libc::process_vm_readv(
+            pid,
+            local_iov.as_ptr() as *const libc::iovec,
+            local_iov.len() as _,
+            remote_iov.as_ptr() as *const libc::iovec,
+            remote_iov.len() as _,
+            0,
+        )
+    })
+}
+
+/// Write the data in the local buffers in `local_iov` to the memory of the process specified by
+/// `pid`.
+///
+/// `remote_iov` specifies the memory regions in the remote process to write to. The caller
+/// must have permission to `ptrace()` the remote process. This returns the number of bytes
+/// written, which may be less than requested if part of a remote region is inaccessible.
+///
+/// See `process_vm_writev(2)` for more information.
+///
+/// # Safety
+///
+/// If `pid` refers to the current process, this can overwrite arbitrary memory in it.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[cfg(linuxlike)]
+#[inline]
+pub unsafe fn process_vm_writev(
+    pid: libc::pid_t,
+    local_iov: &[IoVec],
+    remote_iov: &[RemoteIoVec],
+) -> Result<usize> {
+    Error::unpack_size(libc::process_vm_writev(
+        pid,
+        local_iov.as_ptr() as *const libc::iovec,
+        local_iov.len() as _,
+        remote_iov.as_ptr() as *const libc::iovec,
+        remote_iov.len() as _,
+        0,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -370,4 +498,74 @@ mod tests {
 
         assert_eq!(buf, *b"abcdef");
     }
+
+    #[cfg(linuxlike)]
+    #[test]
+    fn test_remote_iovec() {
+        let vec = RemoteIoVec::new(0x1000, 16);
+        assert_eq!(vec.base(), 0x1000);
+        assert_eq!(vec.len(), 16);
+        assert!(!vec.is_empty());
+        assert!(RemoteIoVec::new(0x1000, 0).is_empty());
+        assert_eq!(vec, RemoteIoVec::new(0x1000, 16));
+        assert_ne!(vec, RemoteIoVec::new(0x1000, 8));
+    }
+
+    #[cfg(linuxlike)]
+    #[test]
+    fn test_process_vm_readv_writev() {
+        let mut data = *b"abcdef";
+        let addr = data.as_ptr() as usize;
+
+        let (r, w) = crate::pipe().unwrap();
+
+        let pid = crate::util::fork_child(|| {
+            // Close our copy of the write end (without moving it out of the parent)
+            unsafe {
+                libc::close(w.fd());
+            }
+            // Wait for the parent to modify our memory
+            let mut buf = [0];
+            r.read(&mut buf) == Ok(0) && unsafe { core::ptr::read_volatile(&data) } == *b"abXYef"
+        });
+        drop(r);
+
+        let mut buf1 = [0; 2];
+        let mut buf2 = [0; 3];
+        assert_eq!(
+            process_vm_readv(
+                pid,
+                &mut [IoVecMut::new(&mut buf1), IoVecMut::new(&mut buf2)],
+                &[RemoteIoVec::new(addr, 4), RemoteIoVec::new(addr + 5, 1)],
+            )
+            .unwrap(),
+            5
+        );
+        assert_eq!(buf1, *b"ab");
+        assert_eq!(buf2, *b"cdf");
+
+        // Overwrite it in the child, but not in our memory
+        assert_eq!(
+            unsafe {
+                process_vm_writev(pid, &[IoVec::new(b"XY")], &[RemoteIoVec::new(addr + 2, 2)])
+            }
+            .unwrap(),
+            2
+        );
+        data[0] = b'z';
+        assert_eq!(data, *b"zbcdef");
+
+        assert_eq!(
+            process_vm_readv(
+                pid,
+                &mut [IoVecMut::new(&mut buf2)],
+                &[RemoteIoVec::new(0, 3)]
+            )
+            .unwrap_err(),
+            Errno::EFAULT
+        );
+
+        drop(w);
+        crate::util::wait_child_ok(pid);
+    }
 }
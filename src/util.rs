@@ -273,6 +273,49 @@ pub fn getosreldate_real() -> Option<libc::c_int> {
     }
 }
 
+/// Fork a child process that runs `f()` and then exits with status 0 if it returned `true` (or 1
+/// if it returned `false` or panicked).
+///
+/// Returns the PID of the child. Use [`wait_child_ok()`] to reap it.
+#[cfg(test)]
+#[allow(dead_code)]
+pub fn fork_child<F: FnOnce() -> bool>(f: F) -> libc::pid_t {
+    struct ExitGuard;
+
+    impl Drop for ExitGuard {
+        fn drop(&mut self) {
+            // Don't let a panic unwind back into the test harness
+            unsafe { crate::_exit(1) }
+        }
+    }
+
+    match unsafe { crate::fork() }.unwrap() {
+        None => {
+            let _guard = ExitGuard;
+            let ok = f();
+            unsafe { crate::_exit(if ok { 0 } else { 1 }) }
+        }
+        Some(pid) => pid,
+    }
+}
+
+/// Wait for the given child to exit, and assert that it exited with status 0.
+#[cfg(test)]
+#[allow(dead_code)]
+pub fn wait_child_ok(pid: libc::pid_t) {
+    assert_eq!(
+        crate::waitpid(pid, crate::WaitFlags::empty()).unwrap(),
+        Some((pid, crate::WaitStatus::Exited(0)))
+    );
+}
+
+/// Run `f()` in a forked child process, and assert that it returned `true`.
+#[cfg(test)]
+#[allow(dead_code)]
+pub fn run_in_child<F: FnOnce() -> bool>(f: F) {
+    wait_child_ok(fork_child(f));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
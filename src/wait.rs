@@ -44,6 +44,11 @@ pub enum WaitStatus {
     #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
     #[cfg(linuxlike)]
     PtraceEvent(i32, i32),
+    /// The process stopped at a system call entry or exit while being traced with the
+    /// `PTRACE_O_TRACESYSGOOD` option set.
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[cfg(linuxlike)]
+    PtraceSyscall,
     Continued,
 }
 
@@ -52,19 +57,23 @@ impl WaitStatus {
     fn from_raw(stat: i32) -> Self {
         if !libc::WIFEXITED(stat) {
             if libc::WIFSIGNALED(stat) {
-                let sig = libc::WTERMSIG(stat);
+                return Self::Signaled(libc::WTERMSIG(stat), libc::WCOREDUMP(stat));
+            } else if libc::WIFSTOPPED(stat) {
+                let sig = libc::WSTOPSIG(stat);
 
                 #[cfg(linuxlike)]
-                if sig == libc::SIGTRAP {
-                    let event = (stat >> 16) & 0xF;
+                {
+                    if sig == libc::SIGTRAP | 0x80 {
+                        return Self::PtraceSyscall;
+                    }
+
+                    let event = stat >> 16;
                     if event != 0 {
                         return Self::PtraceEvent(sig, event);
                     }
                 }
 
-                return Self::Signaled(sig, libc::WCOREDUMP(stat));
-            } else if libc::WIFSTOPPED(stat) {
-                return Self::Stopped(libc::WSTOPSIG(stat));
+                return Self::Stopped(sig);
             } else if libc::WIFCONTINUED(stat) {
                 return Self::Continued;
             }
@@ -98,6 +107,42 @@ pub fn waitpid(pid: libc::pid_t, options: WaitFlags) -> Result<Option<(libc::pid
     }
 }
 
+/// Wait for the child process specified by `pid` to change state, and return its resource usage.
+///
+/// This is like [`waitpid()`], but it also returns the resource usage of the child. `Ok(None)` is
+/// returned if [`WaitFlags::WNOHANG`] was specified and no child has changed state.
+#[inline]
+pub fn wait4(
+    pid: libc::pid_t,
+    options: WaitFlags,
+) -> Result<Option<(libc::pid_t, WaitStatus, crate::Rusage)>> {
+    let mut wstat = MaybeUninit::uninit();
+    let mut rusage = MaybeUninit::uninit();
+    let pid = Error::unpack(unsafe {
+        libc::wait4(pid, wstat.as_mut_ptr(), options.bits(), rusage.as_mut_ptr())
+    })?;
+
+    if pid == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(unsafe {
+            (
+                pid,
+                WaitStatus::from_raw(wstat.assume_init()),
+                crate::Rusage(rusage.assume_init()),
+            )
+        }))
+    }
+}
+
+/// Wait for any child process to change state, and return its resource usage.
+///
+/// This is equivalent to `wait4(-1, options)`.
+#[inline]
+pub fn wait3(options: WaitFlags) -> Result<Option<(libc::pid_t, WaitStatus, crate::Rusage)>> {
+    wait4(-1, options)
+}
+
 cfg_if::cfg_if! {
     if #[cfg(any(linuxlike, target_os = "freebsd", apple))] {
         #[cfg_attr(docsrs, doc(cfg(any(
@@ -150,5 +195,88 @@ cfg_if::cfg_if! {
                 None
             })
         }
+
+        /// Like [`waitid()`], but also return the resource usage of the child.
+        ///
+        /// This calls the `waitid()` system call directly, since the libc wrapper does not expose
+        /// its fifth `rusage` argument. It works with all [`WaitidId`] variants, including
+        /// [`WaitidId::PidFd`].
+        #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+        #[cfg(linuxlike)]
+        pub fn waitid_rusage(
+            id: WaitidId,
+            options: WaitFlags,
+        ) -> Result<Option<(crate::SigInfo, crate::Rusage)>> {
+            let (idtype, id) = id.into_raw();
+            let mut info = unsafe { core::mem::zeroed() };
+            let mut rusage = MaybeUninit::<libc::rusage>::uninit();
+            Error::unpack_nz(unsafe {
+                libc::syscall(
+                    libc::SYS_waitid,
+                    idtype as libc::c_long,
+                    id as libc::c_long,
+                    &mut info as *mut libc::siginfo_t,
+                    options.bits() as libc::c_long,
+                    rusage.as_mut_ptr(),
+                ) as i32
+            })?;
+
+            let info = crate::SigInfo(info);
+            // The kernel only fills in the rusage if a child was found
+            Ok(if info.si_pid() != 0 {
+                Some((info, crate::Rusage(unsafe { rusage.assume_init() })))
+            } else {
+                None
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_child(code: i32) -> libc::pid_t {
+        match unsafe { crate::fork() }.unwrap() {
+            None => {
+                // Burn a little CPU time so the rusage isn't all zeroes
+                while crate::getrusage(crate::RusageWho::SELF)
+                    .unwrap()
+                    .utime()
+                    .tv_usec
+                    < 10_000
+                {}
+                unsafe { crate::_exit(code) }
+            }
+            Some(pid) => pid,
+        }
+    }
+
+    #[test]
+    fn test_wait4() {
+        let pid = spawn_child(3);
+
+        let (wpid, status, rusage) = wait4(pid, WaitFlags::empty()).unwrap().unwrap();
+        assert_eq!(wpid, pid);
+        assert_eq!(status, WaitStatus::Exited(3));
+        let utime = rusage.utime();
+        assert!(utime.tv_sec > 0 || utime.tv_usec > 0);
+
+        assert_eq!(wait4(pid, WaitFlags::WNOHANG).unwrap_err(), Errno::ECHILD);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_waitid_rusage() {
+        let pid = spawn_child(4);
+
+        let pidfd = crate::PidFd::open(pid, crate::PidFdOpenFlags::empty()).unwrap();
+        let (info, rusage) = waitid_rusage(WaitidId::PidFd(pidfd.fd()), WaitFlags::WEXITED)
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.si_pid(), pid);
+        assert_eq!(info.si_status(), 4);
+        let utime = rusage.utime();
+        assert!(utime.tv_sec > 0 || utime.tv_usec > 0);
     }
 }
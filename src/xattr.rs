@@ -0,0 +1,504 @@
+use crate::internal_prelude::*;
+
+bitflags::bitflags! {
+    /// Flags for [`setxattr()`], [`lsetxattr()`], and [`fsetxattr()`].
+    #[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+    #[derive(Default)]
+    pub struct XattrFlags: libc::c_int {
+        /// Fail with `EEXIST` if the attribute already exists.
+        const CREATE = libc::XATTR_CREATE;
+        /// Fail with `ENODATA` if the attribute does not already exist.
+        const REPLACE = libc::XATTR_REPLACE;
+    }
+}
+
+#[derive(Copy, Clone)]
+enum XattrTarget<'a> {
+    Path(&'a CStr),
+    Link(&'a CStr),
+    Fd(RawFd),
+}
+
+impl XattrTarget<'_> {
+    fn get(self, name: &CStr, buf: &mut [u8]) -> Result<usize> {
+        let (name, value, size) = (name.as_ptr(), buf.as_mut_ptr() as *mut _, buf.len());
+
+        Error::unpack_size(unsafe {
+            match self {
+                Self::Path(path) => libc::getxattr(path.as_ptr(), name, value, size),
+                Self::Link(path) => libc::lgetxattr(path.as_ptr(), name, value, size),
+                Self::Fd(fd) => libc::fgetxattr(fd, name, value, size),
+            }
+        })
+    }
+
+    fn set(self, name: &CStr, value: &[u8], flags: XattrFlags) -> Result<()> {
+        let (name, size, flags) = (name.as_ptr(), value.len(), flags.bits());
+        let value = value.as_ptr() as *const _;
+
+        Error::unpack_nz(unsafe {
+            match self {
+                Self::Path(path) => libc::setxattr(path.as_ptr(), name, value, size, flags),
+                Self::Link(path) => libc::lsetxattr(path.as_ptr(), name, value, size, flags),
+                Self::Fd(fd) => libc::fsetxattr(fd, name, value, size, flags),
+            }
+        })
+    }
+
+    fn list(self, buf: &mut [u8]) -> Result<usize> {
+        let (list, size) = (buf.as_mut_ptr() as *mut _, buf.len());
+
+        Error::unpack_size(unsafe {
+            match self {
+                Self::Path(path) => libc::listxattr(path.as_ptr(), list, size),
+                Self::Link(path) => libc::llistxattr(path.as_ptr(), list, size),
+                Self::Fd(fd) => libc::flistxattr(fd, list, size),
+            }
+        })
+    }
+
+    fn remove(self, name: &CStr) -> Result<()> {
+        let name = name.as_ptr();
+
+        Error::unpack_nz(unsafe {
+            match self {
+                Self::Path(path) => libc::removexattr(path.as_ptr(), name),
+                Self::Link(path) => libc::lremovexattr(path.as_ptr(), name),
+                Self::Fd(fd) => libc::fremovexattr(fd, name),
+            }
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn get_alloc(self, name: &CStr) -> Result<Vec<u8>> {
+        alloc_loop(|buf| self.get(name, buf))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn list_alloc(self) -> Result<XattrNames> {
+        Ok(XattrNames(alloc_loop(|buf| self.list(buf))?))
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn alloc_loop<F: FnMut(&mut [u8]) -> Result<usize>>(mut f: F) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+
+    loop {
+        // Find out how large the buffer needs to be
+        let size = f(&mut [])?;
+        buf.resize(size, 0);
+
+        match f(&mut buf) {
+            // If the size was 0, the second call was just another size query; if that reports a
+            // nonzero size, the value grew in between, so try again
+            Ok(n) if n > buf.len() => (),
+
+            Ok(n) => {
+                buf.truncate(n);
+                return Ok(buf);
+            }
+
+            // It grew between the two calls; try again
+            Err(e) if e == Errno::ERANGE => (),
+
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Get the value of the extended attribute `name` on the file specified by `path`.
+///
+/// The value is stored in `buf`, and its length is returned. If `buf` is empty, the value is not
+/// retrieved; instead, the current size of the value is returned. If `buf` is too small to hold
+/// the value, this fails with `ERANGE`.
+///
+/// See [`getxattr_alloc()`] for a version that allocates a buffer of the required size.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn getxattr<P: AsPath, N: AsPath>(path: P, name: N, buf: &mut [u8]) -> Result<usize> {
+    path.with_cstr(|path| name.with_cstr(|name| XattrTarget::Path(path).get(name, buf)))
+}
+
+/// Like [`getxattr()`], but if `path` refers to a symbolic link, get the attribute of the link
+/// itself.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn lgetxattr<P: AsPath, N: AsPath>(path: P, name: N, buf: &mut [u8]) -> Result<usize> {
+    path.with_cstr(|path| name.with_cstr(|name| XattrTarget::Link(path).get(name, buf)))
+}
+
+/// Like [`getxattr()`], but get the attribute of the file referred to by the file descriptor `fd`.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn fgetxattr<N: AsPath>(fd: RawFd, name: N, buf: &mut [u8]) -> Result<usize> {
+    name.with_cstr(|name| XattrTarget::Fd(fd).get(name, buf))
+}
+
+/// Equivalent to [`getxattr()`], but allocates memory to store the returned value.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(any(target_os = "linux", target_os = "android"), feature = "alloc")))
+)]
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn getxattr_alloc<P: AsPath, N: AsPath>(path: P, name: N) -> Result<Vec<u8>> {
+    path.with_cstr(|path| name.with_cstr(|name| XattrTarget::Path(path).get_alloc(name)))
+}
+
+/// Equivalent to [`lgetxattr()`], but allocates memory to store the returned value.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(any(target_os = "linux", target_os = "android"), feature = "alloc")))
+)]
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn lgetxattr_alloc<P: AsPath, N: AsPath>(path: P, name: N) -> Result<Vec<u8>> {
+    path.with_cstr(|path| name.with_cstr(|name| XattrTarget::Link(path).get_alloc(name)))
+}
+
+/// Equivalent to [`fgetxattr()`], but allocates memory to store the returned value.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(any(target_os = "linux", target_os = "android"), feature = "alloc")))
+)]
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn fgetxattr_alloc<N: AsPath>(fd: RawFd, name: N) -> Result<Vec<u8>> {
+    name.with_cstr(|name| XattrTarget::Fd(fd).get_alloc(name))
+}
+
+/// Set the value of the extended attribute `name` on the file specified by `path`.
+///
+/// By default, the attribute is created if it does not exist and replaced if it does; `flags`
+/// can be used to change this.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn setxattr<P: AsPath, N: AsPath>(
+    path: P,
+    name: N,
+    value: &[u8],
+    flags: XattrFlags,
+) -> Result<()> {
+    path.with_cstr(|path| name.with_cstr(|name| XattrTarget::Path(path).set(name, value, flags)))
+}
+
+/// Like [`setxattr()`], but if `path` refers to a symbolic link, set the attribute of the link
+/// itself.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn lsetxattr<P: AsPath, N: AsPath>(
+    path: P,
+    name: N,
+    value: &[u8],
+    flags: XattrFlags,
+) -> Result<()> {
+    path.with_cstr(|path| name.with_cstr(|name| XattrTarget::Link(path).set(name, value, flags)))
+}
+
+/// Like [`setxattr()`], but set the attribute of the file referred to by the file descriptor `fd`.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn fsetxattr<N: AsPath>(fd: RawFd, name: N, value: &[u8], flags: XattrFlags) -> Result<()> {
+    name.with_cstr(|name| XattrTarget::Fd(fd).set(name, value, flags))
+}
+
+/// List the names of the extended attributes on the file specified by `path`.
+///
+/// The names are stored in `buf` as a series of NUL-terminated strings, and the total length is
+/// returned. Use [`XattrNameIter`] to iterate over the names in `buf[..n]`.
+///
+/// If `buf` is empty, the names are not retrieved; instead, the current size of the list is
+/// returned. If `buf` is too small to hold the list, this fails with `ERANGE`.
+///
+/// See [`listxattr_alloc()`] for a version that allocates a buffer of the required size.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn listxattr<P: AsPath>(path: P, buf: &mut [u8]) -> Result<usize> {
+    path.with_cstr(|path| XattrTarget::Path(path).list(buf))
+}
+
+/// Like [`listxattr()`], but if `path` refers to a symbolic link, list the attributes of the link
+/// itself.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn llistxattr<P: AsPath>(path: P, buf: &mut [u8]) -> Result<usize> {
+    path.with_cstr(|path| XattrTarget::Link(path).list(buf))
+}
+
+/// Like [`listxattr()`], but list the attributes of the file referred to by the file descriptor
+/// `fd`.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn flistxattr(fd: RawFd, buf: &mut [u8]) -> Result<usize> {
+    XattrTarget::Fd(fd).list(buf)
+}
+
+/// Equivalent to [`listxattr()`], but allocates memory to store the returned list.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(any(target_os = "linux", target_os = "android"), feature = "alloc")))
+)]
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn listxattr_alloc<P: AsPath>(path: P) -> Result<XattrNames> {
+    path.with_cstr(|path| XattrTarget::Path(path).list_alloc())
+}
+
+/// Equivalent to [`llistxattr()`], but allocates memory to store the returned list.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(any(target_os = "linux", target_os = "android"), feature = "alloc")))
+)]
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn llistxattr_alloc<P: AsPath>(path: P) -> Result<XattrNames> {
+    path.with_cstr(|path| XattrTarget::Link(path).list_alloc())
+}
+
+/// Equivalent to [`flistxattr()`], but allocates memory to store the returned list.
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(any(target_os = "linux", target_os = "android"), feature = "alloc")))
+)]
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn flistxattr_alloc(fd: RawFd) -> Result<XattrNames> {
+    XattrTarget::Fd(fd).list_alloc()
+}
+
+/// Remove the extended attribute `name` from the file specified by `path`.
+///
+/// This fails with `ENODATA` if the attribute does not exist.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn removexattr<P: AsPath, N: AsPath>(path: P, name: N) -> Result<()> {
+    path.with_cstr(|path| name.with_cstr(|name| XattrTarget::Path(path).remove(name)))
+}
+
+/// Like [`removexattr()`], but if `path` refers to a symbolic link, remove the attribute from the
+/// link itself.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn lremovexattr<P: AsPath, N: AsPath>(path: P, name: N) -> Result<()> {
+    path.with_cstr(|path| name.with_cstr(|name| XattrTarget::Link(path).remove(name)))
+}
+
+/// Like [`removexattr()`], but remove the attribute from the file referred to by the file
+/// descriptor `fd`.
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[inline]
+pub fn fremovexattr<N: AsPath>(fd: RawFd, name: N) -> Result<()> {
+    name.with_cstr(|name| XattrTarget::Fd(fd).remove(name))
+}
+
+/// An iterator over the extended attribute names in a buffer filled by [`listxattr()`] (or
+/// [`llistxattr()`]/[`flistxattr()`]).
+#[cfg_attr(docsrs, doc(cfg(any(target_os = "linux", target_os = "android"))))]
+#[derive(Clone, Debug)]
+pub struct XattrNameIter<'a>(&'a [u8]);
+
+impl<'a> XattrNameIter<'a> {
+    /// Create a new iterator over the names in the given buffer.
+    ///
+    /// `buf` should be the portion of the buffer that was filled by [`listxattr()`] (i.e.
+    /// `&buf[..n]`, where `n` is the return value).
+    #[inline]
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self(buf)
+    }
+}
+
+impl<'a> Iterator for XattrNameIter<'a> {
+    type Item = &'a OsStr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.0.is_empty() {
+            let (name, rest) = match memchr(self.0, 0) {
+                Some(i) => (&self.0[..i], &self.0[i + 1..]),
+                None => (self.0, &[][..]),
+            };
+            self.0 = rest;
+
+            if !name.is_empty() {
+                return Some(OsStr::from_bytes(name));
+            }
+        }
+
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::from(!self.0.is_empty()), Some(self.0.len()))
+    }
+}
+
+impl core::iter::FusedIterator for XattrNameIter<'_> {}
+
+/// A list of extended attribute names, as returned by [`listxattr_alloc()`].
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(any(target_os = "linux", target_os = "android"), feature = "alloc")))
+)]
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct XattrNames(Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl XattrNames {
+    /// Iterate over the names in this list.
+    #[inline]
+    pub fn iter(&self) -> XattrNameIter<'_> {
+        XattrNameIter::new(&self.0)
+    }
+
+    /// Get the raw list of NUL-terminated names.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> IntoIterator for &'a XattrNames {
+    type Item = &'a OsStr;
+    type IntoIter = XattrNameIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_xattr_name_iter() {
+        assert_eq!(XattrNameIter::new(b"").next(), None);
+        assert_eq!(
+            XattrNameIter::new(b"user.a\0user.bc\0").collect::<Vec<_>>(),
+            [OsStr::from_bytes(b"user.a"), OsStr::from_bytes(b"user.bc")]
+        );
+        assert_eq!(
+            XattrNameIter::new(b"user.a\0\0user.b").collect::<Vec<_>>(),
+            [OsStr::from_bytes(b"user.a"), OsStr::from_bytes(b"user.b")]
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_alloc_loop() {
+        // The value is empty when its size is first checked, then grows before it can be
+        // retrieved
+        let mut size = 0;
+        let res = alloc_loop(|buf| {
+            if buf.is_empty() {
+                let old = size;
+                size = 3;
+                Ok(old)
+            } else {
+                buf[..3].copy_from_slice(b"abc");
+                Ok(3)
+            }
+        });
+        assert_eq!(res.unwrap(), b"abc");
+
+        // It grows between the two calls, so the first retrieval fails with ERANGE
+        let mut size = 2;
+        let res = alloc_loop(|buf| {
+            if buf.is_empty() {
+                Ok(size)
+            } else if buf.len() < 3 {
+                size = 3;
+                Err(Error::from_code(libc::ERANGE))
+            } else {
+                buf[..3].copy_from_slice(b"abc");
+                Ok(3)
+            }
+        });
+        assert_eq!(res.unwrap(), b"abc");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_xattr() {
+        // /dev/shm is usually a tmpfs, which supports user.* attributes (unlike some /tmp
+        // filesystems)
+        let dir = match tempfile::tempdir_in("/dev/shm") {
+            Ok(dir) => dir,
+            Err(_) => return,
+        };
+        let path = dir.path().join("file");
+        let f = crate::open(
+            &path,
+            crate::OFlag::O_RDWR | crate::OFlag::O_CREAT | crate::OFlag::O_CLOEXEC,
+            0o600,
+        )
+        .unwrap();
+
+        match setxattr(&path, "user.test", b"abc", XattrFlags::empty()) {
+            Err(e) if e == Errno::ENOTSUP || e == Errno::EPERM => return,
+            res => res.unwrap(),
+        }
+
+        let mut buf = [0; 64];
+        assert_eq!(getxattr(&path, "user.test", &mut []).unwrap(), 3);
+        assert_eq!(getxattr(&path, "user.test", &mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], b"abc");
+        assert_eq!(
+            getxattr(&path, "user.test", &mut buf[..2]).unwrap_err(),
+            Errno::ERANGE
+        );
+        assert_eq!(lgetxattr(&path, "user.test", &mut buf).unwrap(), 3);
+        assert_eq!(f.getxattr("user.test", &mut buf).unwrap(), 3);
+
+        assert_eq!(
+            setxattr(&path, "user.test", b"x", XattrFlags::CREATE).unwrap_err(),
+            Errno::EEXIST
+        );
+        assert_eq!(
+            fsetxattr(f.fd(), "user.other", b"x", XattrFlags::REPLACE).unwrap_err(),
+            Errno::ENODATA
+        );
+        f.setxattr("user.other", b"defg", XattrFlags::CREATE)
+            .unwrap();
+        lsetxattr(&path, "user.empty", b"", XattrFlags::empty()).unwrap();
+
+        let n = listxattr(&path, &mut buf).unwrap();
+        let mut names: Vec<_> = XattrNameIter::new(&buf[..n]).collect();
+        names.sort();
+        assert_eq!(names, ["user.empty", "user.other", "user.test"]);
+        assert_eq!(listxattr(&path, &mut []).unwrap(), n);
+        assert_eq!(llistxattr(&path, &mut buf).unwrap(), n);
+        assert_eq!(f.listxattr(&mut buf).unwrap(), n);
+        assert_eq!(listxattr(&path, &mut buf[..2]).unwrap_err(), Errno::ERANGE);
+
+        assert_eq!(getxattr_alloc(&path, "user.other").unwrap(), b"defg");
+        assert_eq!(lgetxattr_alloc(&path, "user.empty").unwrap(), b"");
+        assert_eq!(fgetxattr_alloc(f.fd(), "user.test").unwrap(), b"abc");
+        assert_eq!(
+            getxattr_alloc(&path, "user.nonexistent").unwrap_err(),
+            Errno::ENODATA
+        );
+
+        let names = listxattr_alloc(&path).unwrap();
+        assert_eq!(names.as_bytes().len(), n);
+        assert_eq!(names.iter().count(), 3);
+        assert_eq!(llistxattr_alloc(&path).unwrap(), names);
+        assert_eq!(flistxattr_alloc(f.fd()).unwrap(), names);
+
+        removexattr(&path, "user.test").unwrap();
+        lremovexattr(&path, "user.empty").unwrap();
+        f.removexattr("user.other").unwrap();
+        assert_eq!(
+            fremovexattr(f.fd(), "user.other").unwrap_err(),
+            Errno::ENODATA
+        );
+        assert_eq!(listxattr_alloc(&path).unwrap().iter().next(), None);
+    }
+}
@@ -28,6 +28,7 @@ fn run_child<F: FnOnce() -> slibc::Result<()>>(f: F) -> WaitStatus {
 fn do_tests() {
     restore_sigmask(test_sigmask);
     restore_sigmask(test_kill);
+    restore_sigmask(test_sigaction);
 
     #[cfg(linuxlike)]
     {
@@ -85,6 +86,86 @@ fn test_kill() {
     assert_eq!(status, WaitStatus::Exited(libc::SIGUSR1));
 }
 
+fn test_sigaction() {
+    use slibc::{raise, sigaction, SaFlags, SigAction, SigActionGuard, SigHandler, SigInfo};
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    static LAST_SIG: AtomicI32 = AtomicI32::new(0);
+    static LAST_PID: AtomicI32 = AtomicI32::new(0);
+
+    extern "C" fn handler(sig: Signal) {
+        LAST_SIG.store(sig.as_i32(), Ordering::SeqCst);
+    }
+
+    extern "C" fn handler_info(sig: Signal, info: &SigInfo, _ctx: *mut libc::c_void) {
+        LAST_SIG.store(sig.as_i32(), Ordering::SeqCst);
+        LAST_PID.store(info.si_pid(), Ordering::SeqCst);
+    }
+
+    let orig = unsafe { sigaction(Signal::SIGUSR1, None) }.unwrap();
+    assert!(matches!(orig.handler(), SigHandler::Default));
+
+    sigset!(Signal::SIGUSR1).thread_unblock().unwrap();
+
+    {
+        let act = SigAction::new(
+            SigHandler::Handler(handler),
+            SaFlags::RESTART,
+            sigset!(Signal::SIGUSR2),
+        );
+        let guard = unsafe { SigActionGuard::new(Signal::SIGUSR1, &act) }.unwrap();
+        assert_eq!(guard.signal(), Signal::SIGUSR1);
+        assert!(matches!(guard.old_action().handler(), SigHandler::Default));
+
+        let cur = unsafe { sigaction(Signal::SIGUSR1, None) }.unwrap();
+        assert!(matches!(cur.handler(), SigHandler::Handler(_)));
+        assert!(cur.flags().contains(SaFlags::RESTART));
+        assert!(!cur.flags().contains(SaFlags::SIGINFO));
+        assert!(cur.mask().contains(Signal::SIGUSR2));
+
+        raise(Signal::SIGUSR1).unwrap();
+        assert_eq!(LAST_SIG.swap(0, Ordering::SeqCst), libc::SIGUSR1);
+    }
+
+    {
+        let act = SigAction::new(
+            SigHandler::SigAction(handler_info),
+            SaFlags::empty(),
+            SigSet::empty(),
+        );
+        let _guard = unsafe { SigActionGuard::new(Signal::SIGUSR1, &act) }.unwrap();
+
+        let cur = unsafe { sigaction(Signal::SIGUSR1, None) }.unwrap();
+        assert!(matches!(cur.handler(), SigHandler::SigAction(_)));
+        assert!(cur.flags().contains(SaFlags::SIGINFO));
+
+        raise(Signal::SIGUSR1).unwrap();
+        assert_eq!(LAST_SIG.swap(0, Ordering::SeqCst), libc::SIGUSR1);
+        assert_eq!(LAST_PID.swap(0, Ordering::SeqCst), getpid());
+    }
+
+    let cur = unsafe { sigaction(Signal::SIGUSR1, None) }.unwrap();
+    assert!(matches!(cur.handler(), SigHandler::Default));
+
+    // The previous action is restored even if the new one was "ignore"
+    let act = SigAction::new(SigHandler::Ignore, SaFlags::empty(), SigSet::empty());
+    {
+        let guard = unsafe { SigActionGuard::new(Signal::SIGUSR2, &act) }.unwrap();
+        assert!(matches!(guard.old_action().handler(), SigHandler::Default));
+
+        let cur = unsafe { sigaction(Signal::SIGUSR2, None) }.unwrap();
+        assert!(matches!(cur.handler(), SigHandler::Ignore));
+
+        // Raising an ignored signal is harmless
+        raise(Signal::SIGUSR2).unwrap();
+    }
+
+    let cur = unsafe { sigaction(Signal::SIGUSR2, None) }.unwrap();
+    assert!(matches!(cur.handler(), SigHandler::Default));
+
+    unsafe { sigaction(Signal::SIGKILL, Some(&act)) }.unwrap_err();
+}
+
 #[cfg(linuxlike)]
 fn test_tgkill() {
     use slibc::{gettid, tgkill};
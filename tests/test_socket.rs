@@ -138,3 +138,178 @@ fn test_inet6_bind_connect_accept() {
     asock.read_exact(&mut buf[..3]).unwrap();
     assert_eq!(&buf[..3], b"abc");
 }
+
+#[test]
+fn test_sendmsg_recvmsg_rights() {
+    use slibc::{cmsg_space, pipe, CmsgBuilder, ControlMessage, IoVec, IoVecMut, MsgFlag};
+
+    let (a, b) = Socket::pair_cloexec(SockDomain::UNIX, SockType::STREAM, None).unwrap();
+    let (mut r, w) = pipe().unwrap();
+
+    let mut cbuf = [0; cmsg_space(core::mem::size_of::<i32>() * 2)];
+    let mut cmsgs = CmsgBuilder::new(&mut cbuf);
+    cmsgs.rights(&[r.fd(), w.fd()]).unwrap();
+
+    assert_eq!(
+        a.sendmsg(
+            &[IoVec::new(b"ab"), IoVec::new(b"c")],
+            Some(&cmsgs),
+            MsgFlag::empty(),
+            None
+        )
+        .unwrap(),
+        3
+    );
+    drop(w);
+
+    let mut buf = [0; 10];
+    let mut cbuf = [0; cmsg_space(core::mem::size_of::<i32>() * 2)];
+
+    #[cfg(any(linuxlike, freebsdlike, netbsdlike))]
+    let flags = MsgFlag::CMSG_CLOEXEC;
+    #[cfg(not(any(linuxlike, freebsdlike, netbsdlike)))]
+    let flags = MsgFlag::empty();
+
+    let mut msg = b
+        .recvmsg(&mut [IoVecMut::new(&mut buf)], &mut cbuf, flags)
+        .unwrap();
+    assert_eq!(msg.bytes(), 3);
+    assert_eq!(
+        msg.flags() & (MsgFlag::TRUNC | MsgFlag::CTRUNC),
+        MsgFlag::empty()
+    );
+
+    let mut cmsgs = msg.cmsgs();
+    let mut fds = match cmsgs.next().unwrap() {
+        ControlMessage::Rights(fds) => fds.collect::<Vec<_>>(),
+        m => panic!("{:?}", m),
+    };
+    assert!(cmsgs.next().is_none());
+    drop(msg);
+    assert_eq!(&buf[..3], b"abc");
+
+    assert_eq!(fds.len(), 2);
+    #[cfg(any(linuxlike, freebsdlike, netbsdlike))]
+    for fd in fds.iter() {
+        assert!(fd.get_cloexec().unwrap());
+    }
+
+    // The received write end refers to the same pipe
+    fds[1].write_all(b"xyz").unwrap();
+    drop(fds);
+    let mut buf = [0; 10];
+    assert_eq!(r.read(&mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"xyz");
+}
+
+#[test]
+fn test_recvmsg_trunc() {
+    use slibc::{cmsg_space, pipe, CmsgBuilder, IoVec, IoVecMut, MsgFlag};
+
+    let (a, b) = Socket::pair_cloexec(SockDomain::UNIX, SockType::DGRAM, None).unwrap();
+    let (mut r, w) = pipe().unwrap();
+
+    let mut cbuf = [0; cmsg_space(core::mem::size_of::<i32>() * 2)];
+    let mut cmsgs = CmsgBuilder::new(&mut cbuf);
+    cmsgs.rights(&[r.fd(), w.fd()]).unwrap();
+    a.sendmsg(
+        &[IoVec::new(b"abcdef")],
+        Some(&cmsgs),
+        MsgFlag::empty(),
+        None,
+    )
+    .unwrap();
+    drop(w);
+
+    // Data and control message truncated
+    let mut buf = [0; 3];
+    let mut msg = b
+        .recvmsg(&mut [IoVecMut::new(&mut buf)], &mut [], MsgFlag::empty())
+        .unwrap();
+    assert_eq!(msg.bytes(), 3);
+    assert!(msg.flags().contains(MsgFlag::TRUNC | MsgFlag::CTRUNC));
+    assert!(msg.cmsgs().next().is_none());
+    drop(msg);
+    assert_eq!(&buf, b"abc");
+
+    // The file descriptors were never received, so the write end should be closed
+    assert_eq!(r.read(&mut buf).unwrap(), 0);
+
+    // Received file descriptors that aren't consumed are closed
+    let (mut r, w) = pipe().unwrap();
+    cmsgs.clear();
+    cmsgs.rights(&[w.fd()]).unwrap();
+    a.sendmsg(&[IoVec::new(b"a")], Some(&cmsgs), MsgFlag::empty(), None)
+        .unwrap();
+    drop(w);
+
+    let mut cbuf = [0; cmsg_space(core::mem::size_of::<i32>())];
+    let msg = b
+        .recvmsg(&mut [IoVecMut::new(&mut buf)], &mut cbuf, MsgFlag::empty())
+        .unwrap();
+    assert_eq!(
+        msg.flags() & (MsgFlag::TRUNC | MsgFlag::CTRUNC),
+        MsgFlag::empty()
+    );
+    drop(msg);
+    assert_eq!(r.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn test_sendmsg_recvmsg_addr() {
+    use slibc::{IoVec, IoVecMut, MsgFlag};
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let tmpdir = tmpdir.as_ref();
+
+    let aaddr = SockAddr::Unix(UnixAddr::new(tmpdir.join("a")).unwrap());
+    let baddr = SockAddr::Unix(UnixAddr::new(tmpdir.join("b")).unwrap());
+
+    let a = Socket::new_cloexec(SockDomain::UNIX, SockType::DGRAM, None).unwrap();
+    a.bind(&aaddr).unwrap();
+    let b = Socket::new_cloexec(SockDomain::UNIX, SockType::DGRAM, None).unwrap();
+    b.bind(&baddr).unwrap();
+
+    a.sendmsg(&[IoVec::new(b"abc")], None, MsgFlag::empty(), Some(&baddr))
+        .unwrap();
+
+    let mut buf = [0; 10];
+    let msg = b
+        .recvmsg(&mut [IoVecMut::new(&mut buf)], &mut [], MsgFlag::empty())
+        .unwrap();
+    assert_eq!(msg.bytes(), 3);
+    assert_eq!(msg.addr(), Some(&aaddr));
+}
+
+#[cfg(linuxlike)]
+#[test]
+fn test_sendmsg_recvmsg_credentials() {
+    use slibc::{cmsg_space, CmsgBuilder, ControlMessage, IoVec, IoVecMut, MsgFlag, UCred};
+
+    let (a, b) = Socket::pair_cloexec(SockDomain::UNIX, SockType::DGRAM, None).unwrap();
+    unsafe {
+        b.setsockopt_raw(libc::SOL_SOCKET, libc::SO_PASSCRED, &[1 as libc::c_int])
+            .unwrap();
+    }
+
+    let cred = UCred::current();
+
+    let mut cbuf = [0; cmsg_space(core::mem::size_of::<libc::ucred>())];
+    let mut cmsgs = CmsgBuilder::new(&mut cbuf);
+    cmsgs.credentials(&cred).unwrap();
+    a.sendmsg(&[IoVec::new(b"a")], Some(&cmsgs), MsgFlag::empty(), None)
+        .unwrap();
+
+    let mut buf = [0; 10];
+    let mut cbuf = [0; cmsg_space(core::mem::size_of::<libc::ucred>())];
+    let mut msg = b
+        .recvmsg(&mut [IoVecMut::new(&mut buf)], &mut cbuf, MsgFlag::empty())
+        .unwrap();
+
+    let mut cmsgs = msg.cmsgs();
+    match cmsgs.next().unwrap() {
+        ControlMessage::Credentials(c) => assert_eq!(c, cred),
+        m => panic!("{:?}", m),
+    }
+    assert!(cmsgs.next().is_none());
+}
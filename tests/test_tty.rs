@@ -1,6 +1,6 @@
 use slibc::{
     ioctl_getwinsz, ioctl_setwinsz, isatty, isatty_raw, isatty_simple, openpty, pipe, ptsname,
-    ttyname, ttyname_r, Errno, Winsize,
+    ttyname, ttyname_r, ControlChar, Errno, LocalFlags, Winsize,
 };
 
 #[cfg(all(target_os = "linux", feature = "alloc"))]
@@ -11,7 +11,7 @@ use slibc::ttyname_alloc;
 #[test]
 fn test_tty() {
     let r = pipe().unwrap().0;
-    let (master, slave) = unsafe { openpty(None) }.unwrap();
+    let (master, slave) = unsafe { openpty(None, None) }.unwrap();
 
     let mut buf1 = [0; 4096];
 
@@ -104,11 +104,23 @@ fn test_tty() {
         ws_ypixel: 0,
     };
 
-    let (master, slave) = unsafe { openpty(Some(&winsz.clone())) }.unwrap();
+    let (master, slave) = unsafe { openpty(None, Some(&winsz.clone())) }.unwrap();
     assert_eq!(ioctl_getwinsz(master.fd()).unwrap(), winsz);
 
     winsz.ws_row = 40;
     winsz.ws_col = 80;
     ioctl_setwinsz(master.fd(), &winsz.clone()).unwrap();
     assert_eq!(ioctl_getwinsz(slave.fd()).unwrap(), winsz);
+
+    // And pass initial terminal attributes
+
+    let mut termios = slave.tcgetattr().unwrap();
+    termios.set_local_flags(termios.local_flags() - LocalFlags::ECHO);
+    termios.set_cc(ControlChar::VINTR, 0x01);
+
+    let (_master, slave) = unsafe { openpty(Some(&termios), Some(&winsz)) }.unwrap();
+    let termios2 = slave.tcgetattr().unwrap();
+    assert!(!termios2.local_flags().contains(LocalFlags::ECHO));
+    assert_eq!(termios2.cc(ControlChar::VINTR), 0x01);
+    assert_eq!(ioctl_getwinsz(slave.fd()).unwrap(), winsz);
 }